@@ -0,0 +1,86 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Someone verified a manual check by hand
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Ack {
+    /// Id of the manual check
+    check: String,
+    by: String,
+    at: DateTime<Utc>,
+    expires: DateTime<Utc>,
+}
+
+impl Ack {
+    pub fn new(check: String, by: String, valid_for: chrono::Duration) -> Self {
+        let at = Utc::now();
+        Self {
+            check,
+            by,
+            at,
+            expires: at + valid_for,
+        }
+    }
+
+    pub fn check(&self) -> &str {
+        &self.check
+    }
+
+    pub fn by(&self) -> &str {
+        &self.by
+    }
+
+    pub fn at(&self) -> DateTime<Utc> {
+        self.at
+    }
+
+    pub fn expires(&self) -> DateTime<Utc> {
+        self.expires
+    }
+
+    pub fn is_expired(&self) -> bool {
+        self.expires <= Utc::now()
+    }
+}
+
+/// Acknowledgements of manual checks, committed alongside the project
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Acks {
+    #[serde(rename = "ack", default)]
+    acks: Vec<Ack>,
+}
+
+impl Acks {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)?;
+        let acks = toml::from_str(&contents)
+            .with_context(|| format!("Invalid acknowledgements file {}", path.display()))?;
+        Ok(acks)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let contents = toml::to_string(&self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// The latest acknowledgement of a check
+    pub fn get(&self, check: &str) -> Option<&Ack> {
+        self.acks
+            .iter()
+            .filter(|ack| ack.check == check)
+            .max_by_key(|ack| ack.at)
+    }
+
+    /// Replaces any earlier acknowledgement of the same check
+    pub fn insert(&mut self, ack: Ack) {
+        self.acks.retain(|a| a.check != ack.check);
+        self.acks.push(ack);
+    }
+}
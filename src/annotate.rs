@@ -0,0 +1,115 @@
+use annotate_snippets::{Level, Renderer, Snippet};
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// A problem on a single line of a file
+#[derive(Debug, Clone)]
+pub struct LineAnnotation {
+    /// 1-indexed
+    pub line_number: usize,
+    pub line: String,
+    /// Byte range within `line`
+    pub span: Range<usize>,
+    pub label: String,
+}
+
+/// Render annotations on one file in the style of compiler diagnostics
+pub fn render(title: &str, path: &Path, annotations: &[LineAnnotation]) -> String {
+    let origin = path.display().to_string();
+    let renderer = Renderer::plain();
+    annotations
+        .iter()
+        .map(|a| {
+            let message = Level::Error.title(title).snippet(
+                Snippet::source(&a.line)
+                    .line_start(a.line_number)
+                    .origin(&origin)
+                    .annotation(Level::Error.span(a.span.clone()).label(&a.label)),
+            );
+            renderer.render(message).to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Where in a file a check failed, 1-indexed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, derive_more::Display)]
+#[display("{}:{line}:{column}", path.display())]
+pub struct Location {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Location {
+    pub fn new(path: &Path, (line, column): (usize, usize)) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            line,
+            column,
+        }
+    }
+}
+
+fn common_prefix_len(a: &str, b: &str) -> usize {
+    a.chars().zip(b.chars()).take_while(|(a, b)| a == b).count()
+}
+
+/// Line and column of the first difference between `expected` and `actual`
+pub fn first_difference(expected: &str, actual: &str) -> (usize, usize) {
+    let mut expected_lines = expected.lines();
+    for (i, actual_line) in actual.lines().enumerate() {
+        match expected_lines.next() {
+            Some(expected_line) if expected_line == actual_line => continue,
+            Some(expected_line) => {
+                return (i + 1, common_prefix_len(expected_line, actual_line) + 1);
+            }
+            None => return (i + 1, 1),
+        }
+    }
+    (actual.lines().count() + 1, 1)
+}
+
+/// Line and column where `fragment` most likely belongs in `actual`: the line sharing the longest
+/// prefix (at least half of it) with the fragment's first line, else just past the end of the file
+pub fn nearest_line(fragment: &str, actual: &str) -> (usize, usize) {
+    let needle = fragment
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default();
+    let best = actual
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let indent = line.len() - line.trim_start().len();
+            let shared = common_prefix_len(needle, line.trim_start());
+            (shared, i, indent)
+        })
+        .filter(|(shared, _, _)| *shared > 0 && *shared * 2 >= needle.chars().count())
+        .max_by_key(|(shared, i, _)| (*shared, std::cmp::Reverse(*i)));
+    match best {
+        Some((shared, i, indent)) => (i + 1, indent + shared + 1),
+        None => (actual.lines().count() + 1, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_difference_points_at_diverging_char() {
+        assert_eq!(first_difference("a\nbcd\n", "a\nbxd\n"), (2, 2));
+        assert_eq!(first_difference("a\nb\n", "a\n"), (2, 1));
+        assert_eq!(first_difference("a\n", "a\nb\n"), (2, 1));
+    }
+
+    #[test]
+    fn nearest_line_prefers_longest_shared_prefix() {
+        let actual = "[package]\nname = \"x\"\n  edition = \"2021\"\n";
+        assert_eq!(nearest_line("edition = \"2024\"", actual), (3, 17));
+        assert_eq!(nearest_line("[lints]", actual), (4, 1));
+    }
+}
@@ -0,0 +1,53 @@
+//! Template sets shipped as a single tarball or zip
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use log::debug;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+const TAR_GZ_EXTENSIONS: [&str; 2] = [".tar.gz", ".tgz"];
+const TAR_EXTENSION: &str = ".tar";
+const ZIP_EXTENSION: &str = ".zip";
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Is the file a tarball or zip, going by its name
+pub fn is_archive(path: &Path) -> bool {
+    let name = file_name(path);
+    TAR_GZ_EXTENSIONS
+        .iter()
+        .chain([&TAR_EXTENSION, &ZIP_EXTENSION])
+        .any(|ext| name.ends_with(ext))
+}
+
+/// Unpack an archive next to itself, replacing what a previous unpack left there, and return the
+/// dir it was unpacked into
+pub fn extract(path: &Path) -> Result<PathBuf> {
+    let dest = PathBuf::from(format!("{}.d", path.display()));
+    if dest.exists() {
+        fs::remove_dir_all(&dest)?;
+    }
+    fs::create_dir_all(&dest)?;
+    debug!("Extracting {} into {}", path.display(), dest.display());
+
+    let name = file_name(path);
+    let f = File::open(path)?;
+    let context = || format!("Unable to extract archive {}", path.display());
+    if name.ends_with(ZIP_EXTENSION) {
+        zip::ZipArchive::new(f)
+            .and_then(|mut archive| archive.extract(&dest))
+            .with_context(context)?;
+    } else if TAR_GZ_EXTENSIONS.iter().any(|ext| name.ends_with(ext)) {
+        tar::Archive::new(GzDecoder::new(f))
+            .unpack(&dest)
+            .with_context(context)?;
+    } else {
+        tar::Archive::new(f).unpack(&dest).with_context(context)?;
+    }
+
+    Ok(dest)
+}
@@ -0,0 +1,92 @@
+//! Failures a project knows about and accepts for now, so checks can be adopted on a big project
+//! before everything passes. Known failures are still reported, but don't fail the run
+use crate::types::Check;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+/// Id of a check in the baseline: its 'id' if it has one, or else its checklist's file name and
+/// its name, e.g. 'rust.toml/File Cargo.toml: must exist'
+pub fn check_id(checklist_path: &Path, check: &Check) -> String {
+    if let Some(id) = check.id() {
+        return id.to_string();
+    }
+    let checklist = checklist_path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!("{checklist}/{}", check.description())
+}
+
+/// Known failures, committed alongside the project
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Ids of the checks known to fail
+    #[serde(default)]
+    known: BTreeSet<String>,
+}
+
+impl Baseline {
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.is_file() {
+            return Ok(Self::default());
+        }
+        let contents = fs::read_to_string(path)?;
+        let baseline = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid baseline file {}", path.display()))?;
+        Ok(baseline)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut contents = serde_json::to_string_pretty(&self)?;
+        contents.push('\n');
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.known.contains(id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.known.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.known.is_empty()
+    }
+
+    /// Record whether the check `id` fails now
+    pub fn update(&mut self, id: &str, failing: bool) {
+        if failing {
+            self.known.insert(id.to_string());
+        } else {
+            self.known.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_are_known_by_id_or_name() {
+        let unnamed: Check = toml::from_str("type = \"file\"\npath = \"README.md\"").unwrap();
+        let named: Check =
+            toml::from_str("type = \"file\"\nid = \"license\"\npath = \"LICENSE\"").unwrap();
+        let path = Path::new("checklists/docs.toml");
+        let ids = [check_id(path, &unnamed), check_id(path, &named)];
+        assert_eq!(ids, ["docs.toml/File README.md: must exist", "license"]);
+
+        let mut baseline = Baseline::default();
+        baseline.update(&ids[0], true);
+        baseline.update(&ids[1], false);
+        assert!(baseline.contains(&ids[0]));
+        assert_eq!(baseline.len(), 1);
+        baseline.update(&ids[0], false);
+        assert!(baseline.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::checks::Metrics;
 use crate::types::Check;
 use crate::types::CheckType;
 use crate::types::Status;
@@ -7,20 +8,43 @@ use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use blake3::Hasher;
 use log::debug;
 use log::info;
+use rayon::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fs;
 use std::fs::File;
 use std::io::Write;
 use std::io::{BufReader, Read};
+use std::sync::{LazyLock, Mutex, OnceLock};
+use std::time::{Duration, SystemTime};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
 };
 
-// TODO: need a mechanism for garbage collection
+/// A file's size, mtime, and hash
+type HashedFile = (u64, SystemTime, String);
+
+/// Hashes computed so far this run. Stored alongside the file's size and mtime so a file modified
+/// mid-run is hashed again
+static FILE_HASHES: LazyLock<Mutex<HashMap<PathBuf, HashedFile>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Files at least this big are memory mapped and hashed on multiple threads
+const MMAP_THRESHOLD: u64 = 16 * 1024 * 1024;
+
+fn hash_file_uncached(path: &Path, len: u64) -> Result<String, std::io::Error> {
+    if len >= MMAP_THRESHOLD {
+        let mut hasher = blake3::Hasher::new();
+        match hasher.update_mmap_rayon(path) {
+            Ok(_) => return Ok(hasher.finalize().to_hex().to_string()),
+            Err(e) => {
+                // Some filesystems can't be mapped, read it in chunks instead
+                debug!("Unable to mmap '{}': {e}", path.display());
+            }
+        }
+    }
 
-fn hash_file(path: &Path) -> Result<String, std::io::Error> {
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
     let mut hasher = blake3::Hasher::new();
@@ -37,19 +61,64 @@ fn hash_file(path: &Path) -> Result<String, std::io::Error> {
     Ok(hasher.finalize().to_hex().to_string())
 }
 
+pub(crate) fn hash_file(path: &Path) -> Result<String, std::io::Error> {
+    let metadata = fs::metadata(path)?;
+    let len = metadata.len();
+    let modified = metadata.modified()?;
+
+    if let Some((old_len, old_modified, hash)) = FILE_HASHES.lock().unwrap().get(path)
+        && *old_len == len
+        && *old_modified == modified
+    {
+        return Ok(hash.clone());
+    }
+
+    let hash = hash_file_uncached(path, len)?;
+    FILE_HASHES
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), (len, modified, hash.clone()));
+    Ok(hash)
+}
+
+/// Hash many files in parallel, on a pool bounded by the number of cpus
+pub(crate) fn hash_files(paths: &[PathBuf]) -> Result<Vec<String>, std::io::Error> {
+    paths.par_iter().map(|path| hash_file(path)).collect()
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct PathMap {
     /// Map file path to md5
     map: HashMap<PathBuf, String>,
+    /// Set once the files were hashed this run
+    #[serde(skip)]
+    hashed: OnceLock<()>,
 }
 
 impl PathMap {
     pub fn new() -> Self {
         Self {
             map: HashMap::new(),
+            hashed: OnceLock::new(),
         }
     }
 
+    /// Hash all the files in parallel the first time one is looked up this run, so the lookups
+    /// read memoized hashes
+    fn hash_all(&self) {
+        self.hashed.get_or_init(|| {
+            let paths = self
+                .map
+                .keys()
+                .filter(|path| path.is_file())
+                .cloned()
+                .collect::<Vec<_>>();
+            if let Err(e) = hash_files(&paths) {
+                debug!("Unable to hash cached files up front: {e}");
+            }
+        });
+    }
+
     pub fn insert(&mut self, path: PathBuf) -> Result<()> {
         let hash = hash_file(&path)?;
         self.map.insert(path, hash);
@@ -61,18 +130,68 @@ impl PathMap {
     }
 }
 
-fn cache_files(dir: &Path, project_name: &str) -> (PathBuf, PathBuf, PathBuf, PathBuf) {
-    let path_file_name = format!("{project_name}-paths.json");
+fn cache_files(dir: &Path, key: &str) -> (PathBuf, PathBuf, PathBuf, PathBuf) {
+    let path_file_name = format!("{key}-paths.json");
     let path_file = dir.join(path_file_name);
-    let check_file_name = format!("{project_name}-checks.json");
+    let check_file_name = format!("{key}-checks.json");
     let check_file = dir.join(check_file_name);
-    let facts_file_name = format!("{project_name}-facts.json");
+    let facts_file_name = format!("{key}-facts.json");
     let facts_file = dir.join(facts_file_name);
-    let remote_checklist_name = format!("{project_name}-remotes.json");
+    let remote_checklist_name = format!("{key}-remotes.json");
     let remote_checklist_file = dir.join(remote_checklist_name);
     (path_file, check_file, facts_file, remote_checklist_file)
 }
 
+fn metrics_file(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{key}-metrics.json"))
+}
+
+/// Name of a project's cache namespace.
+/// Includes a hash of the project's path so that projects with the same name don't share a cache
+pub fn cache_key(project_dir: &Path, project_name: &str) -> String {
+    let path = project_dir.display().to_string();
+    let hash = blake3::hash(path.as_bytes()).to_hex();
+    format!("{project_name}-{}", &hash[..16])
+}
+
+/// Move a cache from the old layout, keyed by project name only, to its new key
+pub fn migrate_legacy_cache(cache_dir: &Path, project_name: &str, key: &str) -> Result<()> {
+    let legacy_dir = cache_dir.join(project_name);
+    let dir = cache_dir.join(key);
+    let (legacy_paths, legacy_checks, legacy_facts, legacy_remotes) =
+        cache_files(&legacy_dir, project_name);
+    if dir.exists() || !legacy_checks.is_file() {
+        return Ok(());
+    }
+
+    info!(
+        "Migrating cache {} to {}",
+        legacy_dir.display(),
+        dir.display()
+    );
+    // Remote files are stored by absolute path, easier to download them again
+    if legacy_remotes.is_file() {
+        fs::remove_file(&legacy_remotes)?;
+    }
+    let remote_dir = legacy_dir.join("remote-checklists");
+    if remote_dir.is_dir() {
+        fs::remove_dir_all(&remote_dir)?;
+    }
+
+    let (paths, checks, facts, _) = cache_files(&legacy_dir, key);
+    for (from, to) in [
+        (legacy_paths, paths),
+        (legacy_checks, checks),
+        (legacy_facts, facts),
+    ] {
+        if from.is_file() {
+            fs::rename(from, to)?;
+        }
+    }
+    fs::rename(legacy_dir, dir)?;
+    Ok(())
+}
+
 fn hash_check(check: &Check) -> Result<String> {
     let json = serde_json::to_vec(check)?;
     let mut hasher = Hasher::new();
@@ -108,35 +227,49 @@ impl CheckMap {
     }
 }
 
-#[derive(Debug)]
-struct ExternalChecklistCache {
-    dir: PathBuf,
-    /// Map hash to path
-    map: HashMap<String, PathBuf>,
-}
-
 use anyhow::bail;
 use reqwest::blocking::get;
 
-fn hash_file_contents(input: &str) -> String {
-    blake3::hash(input.as_bytes()).to_hex().to_string()
+fn hash_file_contents(input: &[u8]) -> String {
+    blake3::hash(input).to_hex().to_string()
 }
 
-#[derive(Debug, Clone)]
-pub enum Ttype {
-    Checklist,
-    Template,
+/// Downloaded checklists and templates, shared by every project.
+/// Stored as `<hash>/<name>`, so a file is only downloaded once no matter how many projects use it
+#[derive(Debug)]
+struct ExternalFileStore {
+    dir: PathBuf,
 }
 
-impl ExternalChecklistCache {
-    pub fn new(parent_dir: &Path, map: HashMap<String, PathBuf>) -> Result<Self> {
-        let dir = parent_dir.join("remote-checklists");
+/// Subdir of the cache dir that external files are stored in
+const EXTERNAL_DIR: &str = "external";
+
+/// Subdir of the cache dir that the results of each project's last run are stored in
+pub(crate) const RUNS_DIR: &str = "runs";
+
+impl ExternalFileStore {
+    pub fn new(cache_dir: &Path) -> Result<Self> {
+        let dir = cache_dir.join(EXTERNAL_DIR);
         fs::create_dir_all(&dir)?;
-        Ok(Self { dir, map })
+        Ok(Self { dir })
     }
 
-    pub fn get(&self, hash: &String) -> Option<&PathBuf> {
-        self.map.get(hash)
+    /// Path of the file with these contents, if it was downloaded before
+    pub fn get(&self, hash: &str) -> Option<PathBuf> {
+        let path = fs::read_dir(self.dir.join(hash))
+            .ok()?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .find(|path| path.is_file())?;
+        // Garbage collection goes by when a file was last used
+        if let Err(e) = File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|f| f.set_modified(SystemTime::now()))
+        {
+            debug!("Unable to mark {} as used: {e}", path.display());
+        }
+        Some(path)
     }
 
     pub fn download_and_insert(
@@ -144,30 +277,24 @@ impl ExternalChecklistCache {
         name: &str,
         url: &str,
         hash: Option<String>,
-        ttype: Ttype,
     ) -> Result<PathBuf> {
-        let dir = match ttype {
-            Ttype::Checklist => self.dir.join("checklists"),
-            Ttype::Template => self.dir.join("templates"),
-        };
-        fs::create_dir_all(&dir)?;
-
-        let dest = dir.join(name);
-
+        // Bytes rather than text, templates may come as an archive
         let response = get(url)?;
-        let mut f = File::create(&dest)?;
-        let contents = response.text()?;
-        write!(f, "{contents}")?;
+        let contents = response.bytes()?;
 
         let calculated_hash = hash_file_contents(&contents);
-        if let Some(given_hash) = hash {
-            if given_hash != calculated_hash {
-                bail!("Given hash for {name} {given_hash} != computed hash {calculated_hash}");
-            }
+        if let Some(given_hash) = hash
+            && given_hash != calculated_hash
+        {
+            bail!("Given hash for {name} {given_hash} != computed hash {calculated_hash}");
         }
         info!("Hash for {name} is {calculated_hash}");
 
-        self.map.insert(calculated_hash, dest.clone());
+        let dir = self.dir.join(&calculated_hash);
+        fs::create_dir_all(&dir)?;
+        let dest = dir.join(name);
+        let mut f = File::create(&dest)?;
+        f.write_all(&contents)?;
         Ok(dest)
     }
 }
@@ -178,27 +305,25 @@ pub struct Cache {
     cache_dir: PathBuf,
     path_map: PathMap,
     check_map: CheckMap,
-    external_checklist_cache: ExternalChecklistCache,
-    project_name: String,
+    external_files: ExternalFileStore,
+    key: String,
     facts: HashMap<String, String>,
+    metrics: Metrics,
 }
 
 impl Cache {
-    pub fn new(
-        cache_dir: PathBuf,
-        project_name: String,
-        facts: HashMap<String, String>,
-    ) -> Result<Self> {
-        let cache_dir = cache_dir.join(&project_name);
+    pub fn new(cache_dir: PathBuf, key: String, facts: HashMap<String, String>) -> Result<Self> {
+        let external_files = ExternalFileStore::new(&cache_dir)?;
+        let cache_dir = cache_dir.join(&key);
         fs::create_dir_all(&cache_dir)?;
-        let external_checklist_cache = ExternalChecklistCache::new(&cache_dir, HashMap::new())?;
         Ok(Self {
             cache_dir,
             check_map: CheckMap::new(),
             path_map: PathMap::new(),
-            external_checklist_cache,
-            project_name,
+            external_files,
+            key,
             facts,
+            metrics: Metrics::new(),
         })
     }
 
@@ -207,18 +332,25 @@ impl Cache {
         name: &str,
         url: String,
         hash: Option<String>,
-        ttype: Ttype,
     ) -> Result<PathBuf> {
-        if let Some(ref hash) = hash {
-            if let Some(path) = self.external_checklist_cache.get(hash) {
-                return Ok(path.to_path_buf());
-            }
+        if let Some(ref hash) = hash
+            && let Some(path) = self.external_files.get(hash)
+        {
+            debug!("Using cached {}", path.display());
+            return Ok(path);
         }
 
-        let path = &self
-            .external_checklist_cache
-            .download_and_insert(name, &url, hash, ttype)?;
-        Ok(path.to_path_buf())
+        self.external_files.download_and_insert(name, &url, hash)
+    }
+
+    /// A previously downloaded external file, without touching the network
+    pub fn cached_external_file(&self, hash: Option<&String>) -> Option<PathBuf> {
+        hash.and_then(|hash| self.external_files.get(hash))
+    }
+
+    /// The file was downloaded into the cache
+    pub fn is_external_file(&self, path: &Path) -> bool {
+        path.starts_with(&self.external_files.dir)
     }
 
     pub fn cache_dir(&self) -> &Path {
@@ -229,17 +361,33 @@ impl Cache {
         &self.facts
     }
 
-    pub fn load(cache_dir: PathBuf, project_name: String) -> Result<Option<Self>> {
-        let cache_dir = cache_dir.join(&project_name);
+    /// Values measured by budget checks in previous runs
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    pub fn set_metrics(&mut self, metrics: Metrics) {
+        self.metrics = metrics;
+    }
+
+    /// Delete this cache from disk
+    pub fn remove(self) -> Result<()> {
+        debug!("Removing cache {}", self.cache_dir.display());
+        fs::remove_dir_all(&self.cache_dir)?;
+        Ok(())
+    }
+
+    pub fn load(cache_dir: PathBuf, key: String) -> Result<Option<Self>> {
+        let external_files = ExternalFileStore::new(&cache_dir)?;
+        let cache_dir = cache_dir.join(&key);
 
         let (path_cache_file, check_cache_file, facts_cache_file, remote_checklist_cache_file) =
-            cache_files(&cache_dir, &project_name);
+            cache_files(&cache_dir, &key);
         debug!(
-            "Loading cache files: {}, {}, {}, {}",
+            "Loading cache files: {}, {}, {}",
             path_cache_file.display(),
             check_cache_file.display(),
             facts_cache_file.display(),
-            remote_checklist_cache_file.display(),
         );
 
         if !(path_cache_file.is_file() && check_cache_file.is_file()) {
@@ -267,21 +415,31 @@ impl Cache {
             HashMap::new()
         };
 
-        let external_checklist_cache = if remote_checklist_cache_file.is_file() {
-            let contents = fs::read_to_string(&remote_checklist_cache_file)?;
-            let external_checklist_map: HashMap<String, PathBuf> = serde_json::from_str(&contents)?;
-            ExternalChecklistCache::new(&cache_dir, external_checklist_map)?
+        let metrics_cache_file = metrics_file(&cache_dir, &key);
+        let metrics = if metrics_cache_file.is_file() {
+            let contents = fs::read_to_string(&metrics_cache_file)?;
+            serde_json::from_str(&contents)?
         } else {
-            ExternalChecklistCache::new(&cache_dir, HashMap::new())?
+            Metrics::new()
         };
 
+        // Remote files used to be downloaded per project, they now live in the shared store
+        if remote_checklist_cache_file.is_file() {
+            fs::remove_file(&remote_checklist_cache_file)?;
+        }
+        let remote_dir = cache_dir.join("remote-checklists");
+        if remote_dir.is_dir() {
+            fs::remove_dir_all(&remote_dir)?;
+        }
+
         Ok(Some(Self {
             path_map,
             check_map,
             cache_dir,
-            external_checklist_cache,
-            project_name,
+            external_files,
+            key,
             facts,
+            metrics,
         }))
     }
 
@@ -290,14 +448,13 @@ impl Cache {
             fs::create_dir_all(&self.cache_dir)?;
         }
 
-        let (path_cache_file, check_cache_file, facts_cache_file, external_checklist_cache_file) =
-            cache_files(&self.cache_dir, &self.project_name);
+        let (path_cache_file, check_cache_file, facts_cache_file, _) =
+            cache_files(&self.cache_dir, &self.key);
         debug!(
-            "Saving cache files: {}, {}, {}, {}",
+            "Saving cache files: {}, {}, {}",
             path_cache_file.display(),
             check_cache_file.display(),
             facts_cache_file.display(),
-            external_checklist_cache_file.display(),
         );
 
         let mut f = File::create(&path_cache_file)?;
@@ -312,8 +469,8 @@ impl Cache {
         let contents = serde_json::to_string(&self.facts)?;
         write!(f, "{contents}")?;
 
-        let mut f = File::create(&external_checklist_cache_file)?;
-        let contents = serde_json::to_string(&self.external_checklist_cache.map)?;
+        let mut f = File::create(metrics_file(&self.cache_dir, &self.key))?;
+        let contents = serde_json::to_string(&self.metrics)?;
         write!(f, "{contents}")?;
 
         Ok(())
@@ -324,12 +481,17 @@ impl Cache {
         debug!("Checking cache for '{check_name}'");
 
         let status = match check.ttype() {
+            CheckType::File(f) if f.is_glob() => {
+                // Globs can match a different set of files each run
+                None
+            }
             CheckType::File(f) => {
                 let path = f.path();
 
                 match &self.path_map.get(path) {
                     Some(old_hash) => {
                         // Check if file has changed
+                        self.path_map.hash_all();
                         let new_hash = hash_file(path)?;
                         if **old_hash == new_hash {
                             match &self.check_map.get(check)? {
@@ -344,22 +506,59 @@ impl Cache {
                     None => None,
                 }
             }
-            CheckType::Directory(d) => {
+            CheckType::Directory(_) => {
                 // TODO
                 None
             }
-            CheckType::Command(c) => {
+            CheckType::Command(_) => {
                 // TODO
                 None
             }
-            CheckType::Http(h) => {
+            CheckType::Http(_) => {
                 // TODO
                 None
             }
-            CheckType::VarSet(v) => {
+            CheckType::VarSet(_) => {
                 // Dont ever cache
                 None
             }
+            CheckType::Freshness(_) | CheckType::Time(_) => {
+                // Depends on the current time, dont ever cache
+                None
+            }
+            CheckType::Sql(_) => {
+                // Depends on external state, dont ever cache
+                None
+            }
+            CheckType::LineLimits(_)
+            | CheckType::Whitespace(_)
+            | CheckType::Duplicates(_)
+            | CheckType::Secrets(_)
+            | CheckType::FrontMatter(_)
+            | CheckType::Spelling(_)
+            | CheckType::Image(_)
+            | CheckType::Binaries(_)
+            | CheckType::RustWorkspace(_)
+            | CheckType::Subproject(_) => {
+                // TODO
+                None
+            }
+            CheckType::Gitignore(_) | CheckType::Platform(_) => {
+                // Cheap to check, dont bother caching
+                None
+            }
+            CheckType::Group(_) => {
+                // TODO: cacheable if every nested check is
+                None
+            }
+            CheckType::Manual(_) => {
+                // Nothing to compute
+                None
+            }
+            CheckType::Script(_) | CheckType::Budget(_) => {
+                // Can't know what the command depends on, dont ever cache
+                None
+            }
         };
         Ok(status)
     }
@@ -371,7 +570,7 @@ impl Cache {
         debug!("Inserting status ({status}) of '{check_name}' into cache");
 
         match check.ttype() {
-            CheckType::File(f) => {
+            CheckType::File(f) if !f.is_glob() => {
                 let path = f.path().to_path_buf();
 
                 match status.status() {
@@ -394,3 +593,201 @@ impl Cache {
         Ok(())
     }
 }
+
+/// Local copy of a remote file in the shared store under `cache_dir`, downloading it unless it's
+/// already there
+pub fn external_file(
+    cache_dir: &Path,
+    name: &str,
+    url: &str,
+    hash: Option<&String>,
+) -> Result<PathBuf> {
+    let mut store = ExternalFileStore::new(cache_dir)?;
+    if let Some(hash) = hash
+        && let Some(path) = store.get(hash)
+    {
+        debug!("Using cached {}", path.display());
+        return Ok(path);
+    }
+    store.download_and_insert(name, url, hash.cloned())
+}
+
+/// Disk usage of a file, or of everything under a dir, and when any of it was last written
+fn usage(path: &Path) -> Result<(u64, SystemTime)> {
+    let mut size = 0;
+    let mut modified = SystemTime::UNIX_EPOCH;
+    for entry in walkdir::WalkDir::new(path) {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            size += metadata.len();
+            modified = modified.max(metadata.modified()?);
+        }
+    }
+    Ok((size, modified))
+}
+
+/// A project's cache
+#[derive(Debug)]
+pub struct ProjectEntry {
+    pub key: String,
+    /// Cached check results
+    pub checks: usize,
+    /// Metrics recorded by budget checks
+    pub metrics: usize,
+    pub size: u64,
+    pub last_used: SystemTime,
+}
+
+#[derive(Debug, Default)]
+pub struct CacheStats {
+    pub projects: Vec<ProjectEntry>,
+    /// Downloaded checklists and templates
+    pub external_files: usize,
+    pub external_size: u64,
+    pub size: u64,
+}
+
+/// What is cached under `cache_dir`, per project
+pub fn stats(cache_dir: &Path) -> Result<CacheStats> {
+    let mut stats = CacheStats::default();
+    if !cache_dir.is_dir() {
+        return Ok(stats);
+    }
+
+    for entry in fs::read_dir(cache_dir)? {
+        let path = entry?.path();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if !path.is_dir() || name == RUNS_DIR {
+            continue;
+        }
+        if name == EXTERNAL_DIR {
+            let (size, _) = usage(&path)?;
+            stats.external_size = size;
+            stats.external_files = walkdir::WalkDir::new(&path)
+                .into_iter()
+                .filter_map(Result::ok)
+                .filter(|entry| entry.file_type().is_file())
+                .count();
+            continue;
+        }
+
+        let key = name.to_string();
+        let (_, check_file, _, _) = cache_files(&path, &key);
+        let checks = match fs::read_to_string(&check_file) {
+            Ok(contents) => serde_json::from_str::<CheckMap>(&contents)
+                .map(|checks| checks.map.len())
+                .unwrap_or_default(),
+            Err(_) => 0,
+        };
+        let metrics = match fs::read_to_string(metrics_file(&path, &key)) {
+            Ok(contents) => serde_json::from_str::<Metrics>(&contents)
+                .map(|metrics| metrics.len())
+                .unwrap_or_default(),
+            Err(_) => 0,
+        };
+
+        let (mut size, mut last_used) = usage(&path)?;
+        for run_file in run_files(cache_dir, &key) {
+            let (run_size, run_modified) = usage(&run_file)?;
+            size += run_size;
+            last_used = last_used.max(run_modified);
+        }
+
+        stats.projects.push(ProjectEntry {
+            key,
+            checks,
+            metrics,
+            size,
+            last_used,
+        });
+    }
+    stats.projects.sort_by(|a, b| a.key.cmp(&b.key));
+
+    stats.size = stats.external_size + stats.projects.iter().map(|p| p.size).sum::<u64>();
+    Ok(stats)
+}
+
+/// Files outside of a project's cache dir that belong to it: its last run and fingerprint
+fn run_files(cache_dir: &Path, key: &str) -> Vec<PathBuf> {
+    ["json", "fingerprint"]
+        .iter()
+        .map(|ext| cache_dir.join(RUNS_DIR).join(format!("{key}.{ext}")))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Delete everything cached for the project with this key
+pub fn clear_project(cache_dir: &Path, key: &str) -> Result<()> {
+    let dir = cache_dir.join(key);
+    if dir.is_dir() {
+        debug!("Removing cache {}", dir.display());
+        fs::remove_dir_all(&dir)?;
+    }
+    for path in run_files(cache_dir, key) {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// Delete project caches and external files that haven't been used for `max_age`. Returns what
+/// was removed
+pub fn gc(cache_dir: &Path, max_age: Duration) -> Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+    let Some(cutoff) = SystemTime::now().checked_sub(max_age) else {
+        return Ok(removed);
+    };
+
+    for project in stats(cache_dir)?.projects {
+        if project.last_used < cutoff {
+            clear_project(cache_dir, &project.key)?;
+            removed.push(cache_dir.join(&project.key));
+        }
+    }
+
+    let external_dir = cache_dir.join(EXTERNAL_DIR);
+    if external_dir.is_dir() {
+        for entry in fs::read_dir(&external_dir)? {
+            let path = entry?.path();
+            let (_, last_used) = usage(&path)?;
+            if last_used < cutoff {
+                debug!("Removing {}", path.display());
+                if path.is_dir() {
+                    fs::remove_dir_all(&path)?;
+                } else {
+                    fs::remove_file(&path)?;
+                }
+                removed.push(path);
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached_files_are_hashed_together() {
+        let dir = std::env::temp_dir().join(format!("checklints-hash-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let mut path_map = PathMap::new();
+        for (name, contents) in [("a", "a"), ("b", "b")] {
+            fs::write(dir.join(name), contents).unwrap();
+            path_map.map.insert(dir.join(name), String::new());
+        }
+
+        path_map.hash_all();
+        let hashed = FILE_HASHES
+            .lock()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(hashed.contains(&dir.join("a")));
+        assert!(hashed.contains(&dir.join("b")));
+    }
+}
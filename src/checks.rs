@@ -0,0 +1,39 @@
+mod binaries;
+mod budget;
+mod duplicates;
+mod freshness;
+mod front_matter;
+mod gitignore;
+mod group;
+mod image;
+mod line_limits;
+mod manual;
+mod platform;
+mod rust_workspace;
+mod script;
+mod secrets;
+mod spelling;
+mod sql;
+mod subproject;
+mod time_window;
+mod whitespace;
+
+pub use binaries::BinariesCheck;
+pub use budget::{BudgetCheck, Metrics};
+pub use duplicates::DuplicatesCheck;
+pub use freshness::FreshnessCheck;
+pub use front_matter::FrontMatterCheck;
+pub use gitignore::GitignoreCheck;
+pub use group::{GroupCheck, GroupMode};
+pub use image::ImageCheck;
+pub use line_limits::LineLimitsCheck;
+pub use manual::ManualCheck;
+pub use platform::PlatformCheck;
+pub use rust_workspace::{RustRule, RustWorkspaceCheck};
+pub use script::ScriptCheck;
+pub use secrets::SecretsCheck;
+pub use spelling::SpellingCheck;
+pub use sql::SqlCheck;
+pub use subproject::SubprojectCheck;
+pub use time_window::TimeWindowCheck;
+pub use whitespace::WhitespaceCheck;
@@ -0,0 +1,86 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use bytesize::ByteSize;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Same heuristic as git: a NUL byte within the first 8000 bytes means binary
+const SNIFF_LEN: u64 = 8000;
+
+fn is_binary(path: &Path) -> Result<bool> {
+    let mut head = Vec::new();
+    File::open(path)?.take(SNIFF_LEN).read_to_end(&mut head)?;
+    Ok(head.contains(&0))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct BinariesCheck {
+    /// Paths and/or globs of files to check
+    paths: Vec<String>,
+
+    /// No file may be larger than this, e.g. "1MB", binary or not
+    max_size: Option<ByteSize>,
+
+    /// Only enforce `max_size`, allowing binary files under it
+    #[serde(default)]
+    allow_binary: bool,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl CheckTrait for BinariesCheck {
+    fn describe(&self) -> String {
+        let mut constraints = Vec::new();
+        if !self.allow_binary {
+            constraints.push(String::from("be text"));
+        }
+        if let Some(max_size) = self.max_size {
+            constraints.push(format!("be at most {}", max_size.display().si()));
+        }
+        format!("Files {:?}: must {}", self.paths, constraints.join(", "))
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let mut offenders = Vec::new();
+        for path in walk::expand(&self.paths, self.include_ignored)? {
+            let size = ByteSize(fs::metadata(&path)?.len());
+            let too_large = self.max_size.is_some_and(|max_size| size > max_size);
+            let binary = !self.allow_binary && is_binary(&path)?;
+
+            let problem = match (binary, too_large) {
+                (true, true) => "binary, too large",
+                (true, false) => "binary",
+                (false, true) => "too large",
+                (false, false) => continue,
+            };
+            offenders.push(format!(
+                "{} ({}, {problem})",
+                path.display(),
+                size.display().si()
+            ));
+        }
+
+        if offenders.is_empty() {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{} offending file(s)", offenders.len()),
+            Some(offenders.join("\n")),
+        ))
+    }
+}
@@ -0,0 +1,216 @@
+use crate::command::run_command_line;
+use crate::types::{json_path_value, CheckTrait, Status, StatusStatus};
+use anyhow::{bail, Context, Result};
+use different::DiffSettings;
+use minijinja::Environment;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Number;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Last passing value of each budget check's metric, keyed by the check's checklist and id (see
+/// `Check::metric_key`)
+pub type Metrics = HashMap<String, f64>;
+
+/// A number measured by a command (e.g. binary size, benchmark time) must stay within a budget
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct BudgetCheck {
+    /// Name of what is measured, e.g. "binary size"
+    metric: String,
+
+    cmd: String,
+
+    /// Regex matching the number in stdout, the first capture group if it has one
+    regex: Option<String>,
+
+    /// JSONPath to the number, with stdout parsed as JSON
+    jsonpath: Option<String>,
+
+    max: Option<Number>,
+
+    min: Option<Number>,
+
+    /// Most the value may get worse since the last passing run, in percent
+    max_regression: Option<Number>,
+
+    /// Bigger values are better (e.g. throughput), so a regression is a decrease
+    #[serde(default)]
+    higher_is_better: bool,
+}
+
+fn as_f64(n: &Number) -> f64 {
+    n.as_f64().unwrap_or(f64::NAN)
+}
+
+impl BudgetCheck {
+    /// The number in the command's output
+    fn measure(&self, vars: &HashMap<String, String>) -> Result<f64> {
+        let output = run_command_line(&self.cmd, Some(vars))?;
+        if output.code() != 0 {
+            let stderr = output.stderr().map(|s| s.trim()).unwrap_or_default();
+            bail!("Command exited with {}\n{stderr}", output.code());
+        }
+        let stdout = output.stdout().map(|s| s.trim()).unwrap_or_default();
+
+        let value = if let Some(pattern) = &self.regex {
+            let re = Regex::new(pattern)?;
+            let Some(captures) = re.captures(stdout) else {
+                bail!("Regex '{pattern}' does not match the output\n{stdout}");
+            };
+            let found = captures.get(1).or_else(|| captures.get(0)).unwrap();
+            found.as_str().to_string()
+        } else if let Some(path) = &self.jsonpath {
+            let json = serde_json::from_str(stdout).context("Output is not valid JSON")?;
+            json_path_value(&json, path)?
+        } else {
+            stdout.to_string()
+        };
+
+        let value = value.trim();
+        value
+            .parse()
+            .with_context(|| format!("'{value}' is not a number"))
+    }
+
+    /// Check the measured value against the budget and the last passing value under `key` in
+    /// `metrics`, recording it there when it passes
+    pub fn check_against(
+        &self,
+        key: &str,
+        vars: &HashMap<String, String>,
+        metrics: &mut Metrics,
+    ) -> Status {
+        let metric = &self.metric;
+        let value = match self.measure(vars) {
+            Ok(value) => value,
+            Err(e) => {
+                return Status::fail(
+                    format!("Unable to measure {metric}"),
+                    Some(format!("{e:#}")),
+                );
+            }
+        };
+
+        if let Some(max) = &self.max
+            && value > as_f64(max)
+        {
+            return Status::fail(
+                format!("{metric} is over budget"),
+                Some(format!("{value} > {max}")),
+            );
+        }
+
+        if let Some(min) = &self.min
+            && value < as_f64(min)
+        {
+            return Status::fail(
+                format!("{metric} is under budget"),
+                Some(format!("{value} < {min}")),
+            );
+        }
+
+        if let Some(max_regression) = &self.max_regression
+            && let Some(previous) = metrics.get(key)
+            && *previous != 0.0
+        {
+            let change = (value - previous) / previous.abs() * 100.0;
+            let regression = if self.higher_is_better {
+                -change
+            } else {
+                change
+            };
+            if regression > as_f64(max_regression) {
+                return Status::fail(
+                    format!("{metric} regressed by {regression:.1}%"),
+                    Some(format!(
+                        "{previous} -> {value}, at most {max_regression}% allowed"
+                    )),
+                );
+            }
+        }
+
+        metrics.insert(key.to_string(), value);
+        Status::new(false, StatusStatus::Pass)
+    }
+}
+
+impl CheckTrait for BudgetCheck {
+    fn describe(&self) -> String {
+        let mut s = format!("Metric '{}' from '{}'", self.metric, self.cmd);
+
+        if let Some(max) = &self.max {
+            s.push_str(&format!(", must be at most {max}"));
+        }
+        if let Some(min) = &self.min {
+            s.push_str(&format!(", must be at least {min}"));
+        }
+        if let Some(max_regression) = &self.max_regression {
+            s.push_str(&format!(
+                ", must not regress by more than {max_regression}%"
+            ));
+        }
+
+        s
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        // Without a previous run to compare against, only the budget applies
+        Ok(self.check_against(&self.metric, vars, &mut Metrics::new()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(toml: &str) -> BudgetCheck {
+        toml::from_str(&format!("metric = \"size\"\n{toml}")).unwrap()
+    }
+
+    #[test]
+    fn value_is_found_by_regex_or_jsonpath() {
+        let vars = HashMap::new();
+        let by_regex = budget("cmd = \"echo 'size: 42 bytes'\"\nregex = 'size: (\\d+)'");
+        assert_eq!(by_regex.measure(&vars).unwrap(), 42.0);
+        let by_jsonpath =
+            budget("cmd = \"echo '{\\\"a\\\": {\\\"b\\\": 1.5}}'\"\njsonpath = '$.a.b'");
+        assert_eq!(by_jsonpath.measure(&vars).unwrap(), 1.5);
+        let unmatched = budget("cmd = \"echo 'no number'\"\nregex = '(\\d+)'");
+        assert!(unmatched.measure(&vars).is_err());
+    }
+
+    #[test]
+    fn regression_is_relative_to_the_last_passing_value() {
+        let vars = HashMap::new();
+        let mut metrics = Metrics::from([(String::from("a.toml#size"), 100.0)]);
+
+        let grown = budget("cmd = 'echo 115'\nmax_regression = 10");
+        assert!(grown
+            .check_against("a.toml#size", &vars, &mut metrics)
+            .is_failure());
+        assert_eq!(metrics["a.toml#size"], 100.0);
+
+        // Another checklist's metric of the same name has its own previous value
+        assert!(!grown
+            .check_against("b.toml#size", &vars, &mut metrics)
+            .is_failure());
+        assert_eq!(metrics["b.toml#size"], 115.0);
+
+        let shrunk = budget("cmd = 'echo 85'\nmax_regression = 10\nhigher_is_better = true");
+        assert!(shrunk
+            .check_against("a.toml#size", &vars, &mut metrics)
+            .is_failure());
+        let grown_within = budget("cmd = 'echo 105'\nmax_regression = 10");
+        assert!(!grown_within
+            .check_against("a.toml#size", &vars, &mut metrics)
+            .is_failure());
+        assert_eq!(metrics["a.toml#size"], 105.0);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::cache::hash_files;
+use crate::types::{default_true, CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct DuplicatesCheck {
+    /// Paths and/or globs of files to compare
+    paths: Vec<String>,
+
+    /// Empty files are not considered duplicates of each other
+    #[serde(default = "default_true")]
+    ignore_empty: bool,
+
+    /// BLAKE3 hashes (hex, as printed by `b3sum`) of contents no file may have
+    #[serde(default)]
+    known_bad: Vec<String>,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl CheckTrait for DuplicatesCheck {
+    fn describe(&self) -> String {
+        if self.known_bad.is_empty() {
+            format!("Files {:?}: must not be duplicates", self.paths)
+        } else {
+            format!(
+                "Files {:?}: must not be duplicates or known bad",
+                self.paths
+            )
+        }
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        // Map hash to files with that hash
+        let mut by_hash: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        let mut paths = walk::expand(&self.paths, self.include_ignored)?;
+        if self.ignore_empty {
+            paths.retain(|path| fs::metadata(path).is_ok_and(|m| m.len() > 0));
+        }
+        let hashes = hash_files(&paths)?;
+        for (path, hash) in paths.into_iter().zip(hashes) {
+            by_hash.entry(hash).or_default().push(path);
+        }
+
+        let mut known_bad = Vec::new();
+        for bad_hash in &self.known_bad {
+            if let Some(paths) = by_hash.get(&bad_hash.to_lowercase()) {
+                known_bad.extend(
+                    paths
+                        .iter()
+                        .map(|p| format!("{} is known bad", p.display())),
+                );
+            }
+        }
+
+        let duplicates: Vec<Vec<PathBuf>> = by_hash
+            .into_values()
+            .filter(|paths| paths.len() > 1)
+            .collect();
+        if duplicates.is_empty() && known_bad.is_empty() {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        let mut problems = Vec::new();
+        if !duplicates.is_empty() {
+            problems.push(format!("{} set(s) of duplicate files", duplicates.len()));
+        }
+        if !known_bad.is_empty() {
+            problems.push(format!("{} known bad file(s)", known_bad.len()));
+        }
+
+        let details = duplicates
+            .iter()
+            .map(|paths| {
+                paths
+                    .iter()
+                    .map(|p| p.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .chain(known_bad)
+            .collect::<Vec<_>>()
+            .join("\n");
+        Ok(Status::fail(problems.join(", "), Some(details)))
+    }
+}
@@ -0,0 +1,134 @@
+use crate::command::run_command;
+use crate::types::{format_duration, CheckTrait, Status, StatusStatus};
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+fn modified(path: &Path) -> Result<SystemTime> {
+    Ok(fs::metadata(path)?.modified()?)
+}
+
+fn last_tag() -> Result<Option<String>> {
+    let output = run_command(&"git", &["describe", "--tags", "--abbrev=0"], None)?;
+    if output.code() != 0 {
+        return Ok(None);
+    }
+    Ok(output.stdout().cloned())
+}
+
+/// Has `path` changed (committed or not) since `git_ref`
+fn changed_since(path: &Path, git_ref: &str) -> Result<bool> {
+    let path = path.display().to_string();
+    let output = run_command(&"git", &["diff", "--quiet", git_ref, "--", &path], None)?;
+    Ok(output.code() != 0)
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct FreshnessCheck {
+    path: PathBuf,
+
+    /// File must have been modified within this long (e.g. "30days")
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    max_age: Option<humantime::Duration>,
+
+    /// File must have been modified more recently than this other file
+    newer_than: Option<PathBuf>,
+
+    /// File must have changed (according to git) since the most recent tag
+    #[serde(default)]
+    changed_since_last_tag: bool,
+}
+
+impl CheckTrait for FreshnessCheck {
+    fn describe(&self) -> String {
+        let mut s = format!("File {}: must exist", self.path.display());
+
+        if let Some(max_age) = &self.max_age {
+            s.push_str(&format!(", must be modified within {max_age}"));
+        }
+
+        if let Some(other) = &self.newer_than {
+            s.push_str(&format!(", must be newer than {}", other.display()));
+        }
+
+        if self.changed_since_last_tag {
+            s.push_str(", must have changed since the last tag");
+        }
+
+        s
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        if !self.path.is_file() {
+            return Ok(Status::fail(
+                String::from("Path is not a valid file"),
+                Some(self.path.display().to_string()),
+            ));
+        }
+
+        let mtime = modified(&self.path)?;
+
+        if let Some(max_age) = &self.max_age {
+            let age = SystemTime::now().duration_since(mtime).unwrap_or_default();
+            if age > **max_age {
+                return Ok(Status::fail(
+                    String::from("File is stale"),
+                    Some(format!(
+                        "{} was last modified {} ago, limit is {max_age}",
+                        self.path.display(),
+                        format_duration(age)
+                    )),
+                ));
+            }
+        }
+
+        if let Some(other) = &self.newer_than {
+            if !other.is_file() {
+                return Ok(Status::fail(
+                    String::from("Path to compare against is not a valid file"),
+                    Some(other.display().to_string()),
+                ));
+            }
+
+            if mtime <= modified(other)? {
+                return Ok(Status::fail(
+                    String::from("File is older than the file it must be newer than"),
+                    Some(format!(
+                        "{} is not newer than {}",
+                        self.path.display(),
+                        other.display()
+                    )),
+                ));
+            }
+        }
+
+        if self.changed_since_last_tag {
+            let Some(tag) = last_tag()? else {
+                return Ok(Status::skip(String::from("No git tags found"), None));
+            };
+
+            if !changed_since(&self.path, &tag)? {
+                return Ok(Status::fail(
+                    String::from("File has not changed since the last tag"),
+                    Some(format!("{} is unchanged since {tag}", self.path.display())),
+                ));
+            }
+        }
+
+        Ok(Status::new(false, StatusStatus::Pass))
+    }
+}
@@ -0,0 +1,139 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+const DELIMITER: &str = "---";
+
+/// The YAML between the leading `---` lines of a document
+fn front_matter(contents: &str) -> Option<&str> {
+    let rest = contents
+        .strip_prefix(DELIMITER)?
+        .strip_prefix('\n')
+        .or_else(|| contents.strip_prefix("---\r\n"))?;
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        let trimmed = line.trim_end();
+        if trimmed == DELIMITER || trimmed == "..." {
+            return Some(&rest[..offset]);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Plain representation of a YAML value, so `1` and `"1"` compare equal
+fn display(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct FrontMatterCheck {
+    /// Paths and/or globs of documents to check
+    paths: Vec<String>,
+
+    /// Keys that must be present
+    #[serde(default)]
+    required: Vec<String>,
+
+    /// Keys that must have exactly these values
+    #[serde(default)]
+    values: BTreeMap<String, String>,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl FrontMatterCheck {
+    fn problems(&self, contents: &str) -> Vec<String> {
+        let Some(yaml) = front_matter(contents) else {
+            return vec![String::from("no front matter")];
+        };
+        let fields: BTreeMap<String, Value> = match serde_yaml_ng::from_str(yaml) {
+            Ok(Some(fields)) => fields,
+            Ok(None) => BTreeMap::new(),
+            Err(e) => return vec![format!("invalid front matter: {e}")],
+        };
+
+        let mut problems = Vec::new();
+        for key in &self.required {
+            if !fields.contains_key(key) {
+                problems.push(format!("missing '{key}'"));
+            }
+        }
+        for (key, expected) in &self.values {
+            match fields.get(key) {
+                Some(actual) if display(actual) == *expected => {}
+                Some(actual) => problems.push(format!(
+                    "'{key}' is '{}', expected '{expected}'",
+                    display(actual)
+                )),
+                None => problems.push(format!("missing '{key}'")),
+            }
+        }
+        problems
+    }
+}
+
+impl CheckTrait for FrontMatterCheck {
+    fn describe(&self) -> String {
+        let mut keys = self.required.clone();
+        keys.extend(
+            self.values
+                .iter()
+                .map(|(key, value)| format!("{key}={value}")),
+        );
+        format!(
+            "Documents {:?}: front matter must have {}",
+            self.paths,
+            keys.join(", ")
+        )
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let paths = walk::expand(&self.paths, self.include_ignored)?;
+        if paths.is_empty() {
+            return Ok(Status::fail(
+                String::from("No documents match"),
+                Some(self.paths.join("\n")),
+            ));
+        }
+
+        let mut bad_files = 0;
+        let mut details = Vec::new();
+        for path in paths {
+            let contents = fs::read_to_string(&path)?;
+            let problems = self.problems(&contents);
+            if !problems.is_empty() {
+                bad_files += 1;
+                details.push(format!("{}: {}", path.display(), problems.join(", ")));
+            }
+        }
+
+        if bad_files == 0 {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{bad_files} document(s) with bad front matter"),
+            Some(details.join("\n")),
+        ))
+    }
+}
@@ -0,0 +1,80 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use anyhow::{bail, Result};
+use different::DiffSettings;
+use ignore::gitignore::GitignoreBuilder;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+fn default_gitignore() -> PathBuf {
+    PathBuf::from(".gitignore")
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct GitignoreCheck {
+    /// Paths that must be ignored. A trailing '/' marks a directory
+    paths: Vec<String>,
+
+    /// The gitignore file to check against
+    #[serde(default = "default_gitignore")]
+    gitignore: PathBuf,
+}
+
+impl CheckTrait for GitignoreCheck {
+    fn describe(&self) -> String {
+        format!(
+            "Paths {:?}: must be ignored by '{}'",
+            self.paths,
+            self.gitignore.display()
+        )
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        if !self.gitignore.is_file() {
+            return Ok(Status::fail(
+                format!("'{}' does not exist", self.gitignore.display()),
+                None,
+            ));
+        }
+
+        // Patterns in a gitignore are relative to the directory containing it
+        let root = match self.gitignore.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let mut builder = GitignoreBuilder::new(root);
+        if let Some(e) = builder.add(&self.gitignore) {
+            bail!("Unable to parse '{}': {e}", self.gitignore.display());
+        }
+        let gitignore = builder.build()?;
+
+        let mut not_ignored = Vec::new();
+        for path in &self.paths {
+            let is_dir = path.ends_with('/') || Path::new(path).is_dir();
+            let path = path.strip_prefix("./").unwrap_or(path);
+            let path = Path::new(path.trim_end_matches('/'));
+            if !gitignore
+                .matched_path_or_any_parents(path, is_dir)
+                .is_ignore()
+            {
+                not_ignored.push(path.display().to_string());
+            }
+        }
+
+        if not_ignored.is_empty() {
+            Ok(Status::new(false, StatusStatus::Pass))
+        } else {
+            Ok(Status::fail(
+                format!("{} path(s) not ignored", not_ignored.len()),
+                Some(not_ignored.join("\n")),
+            ))
+        }
+    }
+}
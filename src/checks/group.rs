@@ -0,0 +1,105 @@
+use crate::types::{Check, CheckTrait, Status, StatusStatus};
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupMode {
+    /// Every check must pass
+    All,
+    /// At least one check must pass
+    Any,
+    /// No check may pass
+    None,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct GroupCheck {
+    mode: GroupMode,
+    checks: Vec<Check>,
+}
+
+impl GroupCheck {
+    pub fn checks(&self) -> &[Check] {
+        &self.checks
+    }
+
+    pub(crate) fn checks_mut(&mut self) -> &mut [Check] {
+        &mut self.checks
+    }
+}
+
+/// One line summary of a nested check's status
+fn summarize(description: &str, status: &Status) -> String {
+    let outcome = match status.status() {
+        StatusStatus::Pass => "passed".to_string(),
+        StatusStatus::Skip { reason } => format!("skipped ({})", reason.main()),
+        StatusStatus::Fail { reason } => format!("failed ({})", reason.main()),
+        StatusStatus::Review { reason } => format!("needs review ({})", reason.main()),
+        StatusStatus::Warn { reason } => format!("warned ({})", reason.main()),
+        StatusStatus::Known { reason } => format!("known failure ({})", reason.main()),
+    };
+    format!("{description}: {outcome}")
+}
+
+impl CheckTrait for GroupCheck {
+    fn describe(&self) -> String {
+        let joiner = match self.mode {
+            GroupMode::All => " and ",
+            GroupMode::Any => " or ",
+            GroupMode::None => " nor ",
+        };
+        let descriptions = self
+            .checks
+            .iter()
+            .map(|check| check.description())
+            .collect::<Vec<_>>()
+            .join(joiner);
+        match self.mode {
+            GroupMode::None => format!("Neither {descriptions}"),
+            _ => descriptions,
+        }
+    }
+
+    fn do_check(
+        &self,
+        diff_settings: &DiffSettings,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let mut passed = 0;
+        let mut failed = 0;
+        let mut summaries = Vec::new();
+        for check in &self.checks {
+            let status = check.do_check(diff_settings, env, this_file_path, vars)?;
+            if status.is_success() {
+                passed += 1;
+            } else if status.is_failure() {
+                failed += 1;
+            }
+            summaries.push(summarize(&check.description(), &status));
+        }
+
+        let total = self.checks.len();
+        let pass = match self.mode {
+            GroupMode::All => failed == 0,
+            GroupMode::Any => passed > 0,
+            GroupMode::None => passed == 0,
+        };
+        if pass {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        let main = match self.mode {
+            GroupMode::All => format!("{failed} of {total} checks failed"),
+            GroupMode::Any => "No check passed".to_string(),
+            GroupMode::None => format!("{passed} of {total} checks passed"),
+        };
+        Ok(Status::fail(main, Some(summaries.join("\n"))))
+    }
+}
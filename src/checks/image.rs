@@ -0,0 +1,174 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use bytesize::ByteSize;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+const SVG: &str = "svg";
+
+/// Lowercase name of the image format, e.g. "png", or None if it isn't an image
+fn format_name(contents: &[u8]) -> Option<String> {
+    if let Ok(image_type) = imagesize::image_type(contents) {
+        // e.g. "Heif(Hevc)" -> "heif"
+        let name = format!("{image_type:?}");
+        let name = name.split('(').next().unwrap_or(&name);
+        return Some(name.to_lowercase());
+    }
+
+    let text = String::from_utf8_lossy(&contents[..contents.len().min(1024)]);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if text.starts_with("<svg") || (text.starts_with("<?xml") && text.contains("<svg")) {
+        return Some(String::from(SVG));
+    }
+    None
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ImageCheck {
+    /// Paths and/or globs of images to check
+    paths: Vec<String>,
+
+    /// Allowed formats, e.g. ["svg", "png"]. Any image format is allowed if empty
+    #[serde(default)]
+    formats: Vec<String>,
+
+    /// No image may be larger than this, e.g. "500KB"
+    max_size: Option<ByteSize>,
+
+    /// Required width in pixels. Not applied to SVGs
+    width: Option<usize>,
+
+    /// Required height in pixels. Not applied to SVGs
+    height: Option<usize>,
+
+    /// Maximum width in pixels. Not applied to SVGs
+    max_width: Option<usize>,
+
+    /// Maximum height in pixels. Not applied to SVGs
+    max_height: Option<usize>,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl ImageCheck {
+    fn problems(&self, contents: &[u8]) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Some(max_size) = self.max_size {
+            let size = ByteSize(contents.len() as u64);
+            if size > max_size {
+                problems.push(format!(
+                    "{} is larger than {}",
+                    size.display().si(),
+                    max_size.display().si()
+                ));
+            }
+        }
+
+        let Some(format) = format_name(contents) else {
+            problems.push(String::from("not a recognized image"));
+            return problems;
+        };
+        if !self.formats.is_empty() && !self.formats.iter().any(|f| f.eq_ignore_ascii_case(&format))
+        {
+            problems.push(format!(
+                "format is {format}, expected one of {}",
+                self.formats.join(", ")
+            ));
+        }
+
+        // Vector images have no fixed dimensions
+        if format == SVG {
+            return problems;
+        }
+        let size = match imagesize::blob_size(contents) {
+            Ok(size) => size,
+            Err(e) => {
+                problems.push(format!("unable to read dimensions: {e}"));
+                return problems;
+            }
+        };
+        let dimensions = [
+            ("width", size.width, self.width, self.max_width),
+            ("height", size.height, self.height, self.max_height),
+        ];
+        for (name, actual, required, max) in dimensions {
+            if let Some(required) = required
+                && actual != required
+            {
+                problems.push(format!("{name} is {actual}px, expected {required}px"));
+            }
+            if let Some(max) = max
+                && actual > max
+            {
+                problems.push(format!("{name} is {actual}px, more than {max}px"));
+            }
+        }
+
+        problems
+    }
+}
+
+impl CheckTrait for ImageCheck {
+    fn describe(&self) -> String {
+        let mut constraints = Vec::new();
+        if !self.formats.is_empty() {
+            constraints.push(format!("be {}", self.formats.join(" or ")));
+        }
+        if let Some(max_size) = self.max_size {
+            constraints.push(format!("be at most {}", max_size.display().si()));
+        }
+        if let (Some(width), Some(height)) = (self.width, self.height) {
+            constraints.push(format!("be {width}x{height}px"));
+        } else if let Some(width) = self.width {
+            constraints.push(format!("be {width}px wide"));
+        } else if let Some(height) = self.height {
+            constraints.push(format!("be {height}px high"));
+        }
+        if let Some(max_width) = self.max_width {
+            constraints.push(format!("be at most {max_width}px wide"));
+        }
+        if let Some(max_height) = self.max_height {
+            constraints.push(format!("be at most {max_height}px high"));
+        }
+        if constraints.is_empty() {
+            constraints.push(String::from("be images"));
+        }
+        format!("Images {:?}: must {}", self.paths, constraints.join(", "))
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let mut bad_images = 0;
+        let mut details = Vec::new();
+        for path in walk::expand(&self.paths, self.include_ignored)? {
+            let contents = fs::read(&path)?;
+            let problems = self.problems(&contents);
+            if !problems.is_empty() {
+                bad_images += 1;
+                details.push(format!("{}: {}", path.display(), problems.join(", ")));
+            }
+        }
+
+        if bad_images == 0 {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{bad_images} image(s) with problems"),
+            Some(details.join("\n")),
+        ))
+    }
+}
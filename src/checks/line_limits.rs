@@ -0,0 +1,145 @@
+use crate::annotate::{self, LineAnnotation};
+use crate::types::{CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn default_worst() -> usize {
+    5
+}
+
+/// Byte offset of the char at `index`
+fn byte_offset(line: &str, index: usize) -> usize {
+    line.char_indices()
+        .nth(index)
+        .map(|(offset, _)| offset)
+        .unwrap_or(line.len())
+}
+
+struct Offender {
+    path: PathBuf,
+    /// How far over the limit the file or line is
+    excess: usize,
+    description: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct LineLimitsCheck {
+    /// Paths and/or globs of files to check
+    paths: Vec<String>,
+
+    /// No file may have more lines than this
+    max_lines: Option<usize>,
+
+    /// No line may have more chars than this
+    max_line_length: Option<usize>,
+
+    /// Number of offenders to report
+    #[serde(default = "default_worst")]
+    worst: usize,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl LineLimitsCheck {
+    fn offenders(&self, path: &Path, contents: &str) -> Vec<Offender> {
+        let mut offenders = Vec::new();
+
+        if let Some(max_lines) = self.max_lines {
+            let count = contents.lines().count();
+            if count > max_lines {
+                offenders.push(Offender {
+                    path: path.to_path_buf(),
+                    excess: count - max_lines,
+                    description: format!(
+                        "{}: {count} lines (limit is {max_lines})",
+                        path.display()
+                    ),
+                });
+            }
+        }
+
+        if let Some(max_len) = self.max_line_length {
+            for (i, line) in contents.lines().enumerate() {
+                let len = line.chars().count();
+                if len <= max_len {
+                    continue;
+                }
+
+                let annotation = LineAnnotation {
+                    line_number: i + 1,
+                    line: line.to_string(),
+                    span: byte_offset(line, max_len)..line.len(),
+                    label: format!("{len} chars (limit is {max_len})"),
+                };
+                offenders.push(Offender {
+                    path: path.to_path_buf(),
+                    excess: len - max_len,
+                    description: annotate::render("line too long", path, &[annotation]),
+                });
+            }
+        }
+
+        offenders
+    }
+}
+
+impl CheckTrait for LineLimitsCheck {
+    fn describe(&self) -> String {
+        let mut limits = Vec::new();
+
+        if let Some(max_lines) = &self.max_lines {
+            limits.push(format!("at most {max_lines} lines"));
+        }
+
+        if let Some(max_len) = &self.max_line_length {
+            limits.push(format!("lines at most {max_len} chars"));
+        }
+
+        format!("Files {:?}: {}", self.paths, limits.join(", "))
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let mut offenders = Vec::new();
+        for path in walk::expand(&self.paths, self.include_ignored)? {
+            // Not text, no lines to count
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+            offenders.append(&mut self.offenders(&path, &contents));
+        }
+
+        if offenders.is_empty() {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        offenders.sort_by(|a, b| b.excess.cmp(&a.excess).then(a.path.cmp(&b.path)));
+        let total = offenders.len();
+        let mut details: Vec<String> = offenders
+            .into_iter()
+            .take(self.worst)
+            .map(|o| o.description)
+            .collect();
+        if total > self.worst {
+            details.push(format!("... and {} more", total - self.worst));
+        }
+
+        Ok(Status::fail(
+            format!("{total} line limit violation(s)"),
+            Some(details.join("\n")),
+        ))
+    }
+}
@@ -0,0 +1,129 @@
+use crate::ack::Acks;
+use crate::types::{CheckTrait, Status, StatusStatus};
+use crate::ACKS_FILE_NAME;
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// A step a human has to verify (e.g. "rotate credentials quarterly"). Reported as needing review
+/// until acknowledged with `ack`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ManualCheck {
+    /// Used to acknowledge the check. Set from the id of the check it's part of
+    #[serde(skip)]
+    id: Option<String>,
+
+    /// Project whose acknowledgements are read. Set when the project loads its checklists
+    #[serde(skip)]
+    root: Option<PathBuf>,
+
+    /// Sub-items to verify
+    #[serde(default)]
+    items: Vec<String>,
+}
+
+impl ManualCheck {
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub(crate) fn set_id(&mut self, id: Option<String>) {
+        self.id = id;
+    }
+
+    pub(crate) fn set_root(&mut self, root: &Path) {
+        self.root = Some(root.to_path_buf());
+    }
+}
+
+impl CheckTrait for ManualCheck {
+    fn describe(&self) -> String {
+        match &self.id {
+            Some(id) => format!("Manual check '{id}'"),
+            None => String::from("Manual check"),
+        }
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        if let Some(id) = &self.id {
+            let root = self.root.as_deref().unwrap_or(Path::new(""));
+            let acks = Acks::load(&root.join(ACKS_FILE_NAME))?;
+            if let Some(ack) = acks.get(id) {
+                if ack.is_expired() {
+                    return Ok(Status::fail(
+                        format!(
+                            "Acknowledgement by {} expired on {}",
+                            ack.by(),
+                            ack.expires().format("%Y-%m-%d")
+                        ),
+                        None,
+                    ));
+                }
+                return Ok(Status::new(false, StatusStatus::Pass));
+            }
+        }
+
+        let items = if self.items.is_empty() {
+            None
+        } else {
+            let items = self
+                .items
+                .iter()
+                .map(|item| format!("[ ] {item}"))
+                .collect::<Vec<_>>();
+            Some(items.join("\n"))
+        };
+        Ok(Status::review(
+            String::from("Must be verified by hand"),
+            items,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ack::Ack;
+    use std::fs;
+
+    #[test]
+    fn acknowledged_in_the_project_passes() {
+        let root = std::env::temp_dir().join(format!("checklints-ack-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut check = ManualCheck {
+            id: None,
+            root: None,
+            items: Vec::new(),
+        };
+        check.set_id(Some(String::from("rotate-keys")));
+        check.set_root(&root);
+        let run = |check: &ManualCheck| {
+            let vars = HashMap::new();
+            check
+                .do_check(&DiffSettings::new(), &Environment::new(), &root, &vars)
+                .unwrap()
+        };
+        assert!(run(&check).needs_review());
+
+        let mut acks = Acks::default();
+        let ack = Ack::new(
+            String::from("rotate-keys"),
+            String::from("ops"),
+            chrono::Duration::days(1),
+        );
+        acks.insert(ack);
+        acks.save(&root.join(ACKS_FILE_NAME)).unwrap();
+        assert!(run(&check).is_success());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,80 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env::consts;
+use std::path::Path;
+
+/// Matches the platform checklints is running on. Meant to be used as a condition, anything else
+/// is reported as skipped
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct PlatformCheck {
+    /// Operating systems (e.g. "linux", "macos", "windows") or families ("unix") that match
+    #[serde(default)]
+    os: Vec<String>,
+
+    /// CPU architectures (e.g. "x86_64", "aarch64") that match
+    #[serde(default)]
+    arch: Vec<String>,
+}
+
+impl PlatformCheck {
+    fn os_matches(&self) -> bool {
+        self.os.is_empty()
+            || self
+                .os
+                .iter()
+                .any(|os| os == consts::OS || os == consts::FAMILY)
+    }
+
+    fn arch_matches(&self) -> bool {
+        self.arch.is_empty() || self.arch.iter().any(|arch| arch == consts::ARCH)
+    }
+}
+
+impl CheckTrait for PlatformCheck {
+    fn describe(&self) -> String {
+        let mut constraints = Vec::new();
+        if !self.os.is_empty() {
+            constraints.push(format!("os {}", self.os.join(" or ")));
+        }
+        if !self.arch.is_empty() {
+            constraints.push(format!("arch {}", self.arch.join(" or ")));
+        }
+        format!("Platform: must be {}", constraints.join(", "))
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        if !self.os_matches() {
+            return Ok(Status::skip(
+                format!(
+                    "Only runs on {}, this is {}",
+                    self.os.join(" or "),
+                    consts::OS
+                ),
+                None,
+            ));
+        }
+
+        if !self.arch_matches() {
+            return Ok(Status::skip(
+                format!(
+                    "Only runs on {}, this is {}",
+                    self.arch.join(" or "),
+                    consts::ARCH
+                ),
+                None,
+            ));
+        }
+
+        Ok(Status::new(false, StatusStatus::Pass))
+    }
+}
@@ -0,0 +1,253 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use anyhow::{Context, Result};
+use cargo_metadata::{DependencyKind, MetadataCommand, Package};
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum RustRule {
+    /// Every member has a description and a license
+    Metadata,
+    /// Publishable members have no path-only dependencies
+    PathDeps,
+    /// Members agree on whether a dependency's default features are enabled
+    Features,
+    /// `cargo deny check` reports no errors
+    Deny,
+    /// `cargo audit` reports no vulnerabilities
+    Audit,
+}
+
+impl fmt::Display for RustRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Metadata => "metadata",
+            Self::PathDeps => "path-deps",
+            Self::Features => "features",
+            Self::Deny => "deny",
+            Self::Audit => "audit",
+        };
+        write!(f, "{name}")
+    }
+}
+
+fn default_rules() -> Vec<RustRule> {
+    vec![RustRule::Metadata, RustRule::PathDeps, RustRule::Features]
+}
+
+fn is_publishable(package: &Package) -> bool {
+    package
+        .publish
+        .as_ref()
+        .is_none_or(|registries| !registries.is_empty())
+}
+
+fn metadata_problems(members: &[Package]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for package in members {
+        if package.description.is_none() {
+            problems.push(format!("{} has no description", package.name));
+        }
+        if package.license.is_none() && package.license_file.is_none() {
+            problems.push(format!("{} has no license", package.name));
+        }
+    }
+    problems
+}
+
+/// Path dependencies without a version can't be published
+fn path_dep_problems(members: &[Package]) -> Vec<String> {
+    let mut problems = Vec::new();
+    for package in members.iter().filter(|p| is_publishable(p)) {
+        for dep in &package.dependencies {
+            // Dev dependencies are stripped on publish
+            if dep.kind == DependencyKind::Development {
+                continue;
+            }
+            if dep.path.is_some() && dep.req.comparators.is_empty() {
+                problems.push(format!(
+                    "{} depends on {} by path only",
+                    package.name, dep.name
+                ));
+            }
+        }
+    }
+    problems
+}
+
+/// Cargo unifies features across the workspace, so disabling default features in one member is
+/// silently undone by another member that leaves them on
+fn feature_problems(members: &[Package]) -> Vec<String> {
+    // Dependency -> whether default features are used -> members
+    let mut by_dep: BTreeMap<&str, BTreeMap<bool, Vec<&str>>> = BTreeMap::new();
+    for package in members {
+        for dep in &package.dependencies {
+            if dep.path.is_some() || dep.kind != DependencyKind::Normal {
+                continue;
+            }
+            by_dep
+                .entry(&dep.name)
+                .or_default()
+                .entry(dep.uses_default_features)
+                .or_default()
+                .push(&package.name);
+        }
+    }
+
+    by_dep
+        .into_iter()
+        .filter_map(|(dep, usage)| {
+            let disabled = usage.get(&false)?;
+            let enabled = usage.get(&true)?;
+            Some(format!(
+                "{dep} has default features disabled in {} but enabled in {}",
+                disabled.join(", "),
+                enabled.join(", ")
+            ))
+        })
+        .collect()
+}
+
+/// Problem to report instead of running a missing cargo subcommand
+fn missing_subcommand(binary: &str) -> Option<String> {
+    which::which(binary).is_err().then(|| {
+        format!("{binary} is not installed, install it with: cargo install --locked {binary}")
+    })
+}
+
+/// Errors from `cargo deny`, which writes one JSON object per line to stderr
+fn deny_problems() -> Result<Vec<String>> {
+    if let Some(problem) = missing_subcommand("cargo-deny") {
+        return Ok(vec![problem]);
+    }
+
+    let output = Command::new("cargo")
+        .args(["deny", "--format", "json", "check"])
+        .output()
+        .context("Unable to run cargo deny")?;
+
+    let mut problems = Vec::new();
+    for line in String::from_utf8_lossy(&output.stderr).lines() {
+        let Ok(entry) = serde_json::from_str::<serde_json::Value>(line) else {
+            continue;
+        };
+        let fields = &entry["fields"];
+        if entry["type"] != "diagnostic" || fields["severity"] != "error" {
+            continue;
+        }
+        let message = fields["message"].as_str().unwrap_or_default();
+        match fields["code"].as_str() {
+            Some(code) => problems.push(format!("{code}: {message}")),
+            None => problems.push(message.to_string()),
+        }
+    }
+
+    if problems.is_empty() && !output.status.success() {
+        problems.push(String::from("cargo deny failed without reporting an error"));
+    }
+    Ok(problems)
+}
+
+/// Vulnerabilities found by `cargo audit`
+fn audit_problems() -> Result<Vec<String>> {
+    if let Some(problem) = missing_subcommand("cargo-audit") {
+        return Ok(vec![problem]);
+    }
+
+    let output = Command::new("cargo")
+        .args(["audit", "--json"])
+        .output()
+        .context("Unable to run cargo audit")?;
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).with_context(|| {
+        format!(
+            "Unable to parse cargo audit output: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+    })?;
+
+    let empty = Vec::new();
+    let vulnerabilities = report["vulnerabilities"]["list"]
+        .as_array()
+        .unwrap_or(&empty);
+    Ok(vulnerabilities
+        .iter()
+        .map(|vulnerability| {
+            let advisory = &vulnerability["advisory"];
+            let package = &vulnerability["package"];
+            format!(
+                "{}: {} {}: {}",
+                advisory["id"].as_str().unwrap_or_default(),
+                package["name"].as_str().unwrap_or_default(),
+                package["version"].as_str().unwrap_or_default(),
+                advisory["title"].as_str().unwrap_or_default()
+            )
+        })
+        .collect())
+}
+
+/// Rules for the members of a Rust workspace, read with `cargo metadata`. Opt-in: the shipped rust
+/// checklist doesn't include it, add `type = "rust-workspace"` to a project's checklist to use it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct RustWorkspaceCheck {
+    /// Which rules to enforce
+    #[serde(default = "default_rules")]
+    rules: Vec<RustRule>,
+}
+
+impl CheckTrait for RustWorkspaceCheck {
+    fn describe(&self) -> String {
+        let rules = self
+            .rules
+            .iter()
+            .map(|rule| rule.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("Rust workspace: must follow rules {rules}")
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let metadata = MetadataCommand::new().no_deps().exec()?;
+        let members = metadata
+            .workspace_packages()
+            .into_iter()
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut failed_rules = 0;
+        let mut details = Vec::new();
+        for rule in &self.rules {
+            let problems = match rule {
+                RustRule::Metadata => metadata_problems(&members),
+                RustRule::PathDeps => path_dep_problems(&members),
+                RustRule::Features => feature_problems(&members),
+                RustRule::Deny => deny_problems()?,
+                RustRule::Audit => audit_problems()?,
+            };
+            if !problems.is_empty() {
+                failed_rules += 1;
+                details.extend(problems.iter().map(|problem| format!("{rule}: {problem}")));
+            }
+        }
+
+        if failed_rules == 0 {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{failed_rules} of {} rule(s) failed", self.rules.len()),
+            Some(details.join("\n")),
+        ))
+    }
+}
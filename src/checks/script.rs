@@ -0,0 +1,106 @@
+use crate::command::run_command;
+use crate::types::{CheckTrait, Status, StatusStatus};
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ScriptStatus {
+    Pass,
+    Fail,
+    Skip,
+}
+
+/// What a script prints to stdout to report its result
+#[derive(Debug, Deserialize)]
+struct ScriptResult {
+    status: ScriptStatus,
+    reason: Option<String>,
+    diff: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct ScriptCheck {
+    /// Executable to run, relative to the checklist file
+    path: PathBuf,
+
+    #[serde(default)]
+    args: Vec<String>,
+}
+
+impl ScriptCheck {
+    fn exec(&self, this_file_path: &Path) -> PathBuf {
+        match this_file_path.parent() {
+            Some(dir) => dir.join(&self.path),
+            None => self.path.clone(),
+        }
+    }
+}
+
+impl CheckTrait for ScriptCheck {
+    fn describe(&self) -> String {
+        let mut command = self.path.display().to_string();
+        for arg in &self.args {
+            command.push(' ');
+            command.push_str(arg);
+        }
+        format!("Script '{command}': must pass")
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let exec = self.exec(this_file_path);
+        if !exec.is_file() {
+            return Ok(Status::fail(
+                format!("Script '{}' does not exist", exec.display()),
+                None,
+            ));
+        }
+
+        let exec = exec.into_os_string();
+        let args = self.args.iter().map(|arg| arg.into()).collect::<Vec<_>>();
+        // Facts are passed along as environment variables
+        let output = run_command(&exec, &args, Some(vars))?;
+
+        let stdout = output.stdout().map(|s| s.as_str()).unwrap_or_default();
+        let result: ScriptResult = match serde_json::from_str(stdout) {
+            Ok(result) => result,
+            Err(e) => {
+                let mut details = format!("exit code {}", output.code());
+                if let Some(stderr) = output.stderr() {
+                    details.push_str(&format!("\n{stderr}"));
+                }
+                return Ok(Status::fail(
+                    format!("Script did not print a valid result: {e}"),
+                    Some(details),
+                ));
+            }
+        };
+
+        let status = match result.status {
+            ScriptStatus::Pass => Status::new(false, StatusStatus::Pass),
+            ScriptStatus::Fail => Status::fail(
+                result
+                    .reason
+                    .unwrap_or_else(|| "Script reported a failure".to_string()),
+                result.diff,
+            ),
+            ScriptStatus::Skip => Status::skip(
+                result
+                    .reason
+                    .unwrap_or_else(|| "Script skipped the check".to_string()),
+                result.diff,
+            ),
+        };
+        Ok(status)
+    }
+}
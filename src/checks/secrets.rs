@@ -0,0 +1,183 @@
+use crate::annotate::{self, LineAnnotation};
+use crate::types::{default_true, CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Lines containing this are never reported
+const ALLOW_MARKER: &str = "checklints:allow-secret";
+
+/// Tokens at least this long are checked for randomness
+const MIN_TOKEN_LEN: usize = 32;
+
+/// Bits of entropy per char above which a token looks random.
+/// Hex (e.g. git SHAs) can't exceed 4
+const MIN_ENTROPY: f64 = 4.5;
+
+/// Number of chars of a secret left unmasked in the report
+const UNMASKED_LEN: usize = 4;
+
+/// Label, regex, and whether the match itself is sensitive and should be masked
+static RULES: LazyLock<Vec<(&str, Regex, bool)>> = LazyLock::new(|| {
+    [
+        ("AWS access key id", r"\b(AKIA|ASIA)[0-9A-Z]{16}\b", true),
+        (
+            "private key",
+            r"-----BEGIN ((RSA|DSA|EC|OPENSSH|PGP|ENCRYPTED) )?PRIVATE KEY( BLOCK)?-----",
+            false,
+        ),
+    ]
+    .into_iter()
+    .map(|(label, regex, sensitive)| (label, Regex::new(regex).unwrap(), sensitive))
+    .collect()
+});
+
+static TOKEN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[A-Za-z0-9+/=_\-]+").unwrap());
+
+/// Shannon entropy, in bits per char
+fn entropy(s: &str) -> f64 {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for c in s.chars() {
+        *counts.entry(c).or_default() += 1;
+    }
+    let len = s.chars().count() as f64;
+    counts
+        .values()
+        .map(|&count| {
+            let p = count as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Hide all but the start of the secret, so the report doesn't leak it
+fn mask(line: &str, span: &Range<usize>) -> String {
+    line.char_indices()
+        .map(|(i, c)| {
+            if span.contains(&i) && i >= span.start + UNMASKED_LEN {
+                '*'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct SecretsCheck {
+    /// Paths and/or globs of files to scan
+    paths: Vec<String>,
+
+    /// Regexes of known safe matches (e.g. example keys in docs)
+    #[serde(default)]
+    allow: Vec<String>,
+
+    /// Also report long random looking tokens
+    #[serde(default = "default_true")]
+    entropy: bool,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl SecretsCheck {
+    fn findings(&self, contents: &str, allow: &[Regex]) -> Vec<LineAnnotation> {
+        let mut findings = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            if line.contains(ALLOW_MARKER) {
+                continue;
+            }
+
+            let mut matches: Vec<(Range<usize>, &str, bool)> = Vec::new();
+            for (label, regex, sensitive) in RULES.iter() {
+                for m in regex.find_iter(line) {
+                    matches.push((m.range(), label, *sensitive));
+                }
+            }
+            if self.entropy {
+                for m in TOKEN.find_iter(line) {
+                    let already_found = matches.iter().any(|(span, _, _)| span.start == m.start());
+                    if m.len() >= MIN_TOKEN_LEN
+                        && !already_found
+                        && entropy(m.as_str()) >= MIN_ENTROPY
+                    {
+                        matches.push((m.range(), "high entropy token", true));
+                    }
+                }
+            }
+
+            for (span, label, sensitive) in matches {
+                let secret = &line[span.clone()];
+                if allow.iter().any(|regex| regex.is_match(secret)) {
+                    continue;
+                }
+                findings.push(LineAnnotation {
+                    line_number: i + 1,
+                    line: if sensitive {
+                        mask(line, &span)
+                    } else {
+                        line.to_string()
+                    },
+                    span,
+                    label: format!("possible {label}"),
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+impl CheckTrait for SecretsCheck {
+    fn describe(&self) -> String {
+        format!("Files {:?}: must not contain secrets", self.paths)
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let allow = self
+            .allow
+            .iter()
+            .map(|regex| Regex::new(regex))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut count = 0;
+        let mut details = Vec::new();
+        for path in walk::expand(&self.paths, self.include_ignored)? {
+            // Not text
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let findings = self.findings(&contents, &allow);
+            if !findings.is_empty() {
+                count += findings.len();
+                details.push(annotate::render("secret", &path, &findings));
+            }
+        }
+
+        if count == 0 {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{count} possible secret(s)"),
+            Some(details.join("\n")),
+        ))
+    }
+}
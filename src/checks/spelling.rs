@@ -0,0 +1,143 @@
+use crate::annotate::{self, LineAnnotation};
+use crate::types::{CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::{Context, Result};
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use typos::tokens::{Identifier, Tokenizer, Word};
+use typos::{Dictionary, Status as Spelling};
+use unicase::UniCase;
+
+/// typos' built-in list of common misspellings, minus the project's own words
+struct ProjectDictionary {
+    /// Lowercase
+    words: HashSet<String>,
+}
+
+impl ProjectDictionary {
+    fn is_known(&self, token: &str) -> bool {
+        self.words.contains(&token.to_lowercase())
+    }
+}
+
+impl Dictionary for ProjectDictionary {
+    fn correct_ident<'s>(&'s self, ident: Identifier<'_>) -> Option<Spelling<'s>> {
+        self.is_known(ident.token()).then_some(Spelling::Valid)
+    }
+
+    fn correct_word<'s>(&'s self, word: Word<'_>) -> Option<Spelling<'s>> {
+        if self.is_known(word.token()) {
+            return Some(Spelling::Valid);
+        }
+
+        let corrections = typos_dict::WORD.find(&UniCase::new(word.token()))?;
+        if corrections.is_empty() {
+            return Some(Spelling::Invalid);
+        }
+        Some(Spelling::Corrections(
+            corrections.iter().map(|c| Cow::Borrowed(*c)).collect(),
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct SpellingCheck {
+    /// Paths and/or globs of files to spell check
+    paths: Vec<String>,
+
+    /// Words that are spelled correctly for this project
+    #[serde(default)]
+    words: Vec<String>,
+
+    /// File of words (one per line, relative to the checklist) that are spelled correctly for
+    /// this project
+    dictionary: Option<PathBuf>,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl SpellingCheck {
+    fn dictionary(&self, this_file_path: &Path) -> Result<ProjectDictionary> {
+        let mut words: HashSet<String> = self.words.iter().map(|w| w.to_lowercase()).collect();
+
+        if let Some(path) = &self.dictionary {
+            let path = this_file_path.parent().unwrap().join(path);
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read dictionary {}", path.display()))?;
+            words.extend(
+                contents
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_lowercase),
+            );
+        }
+
+        Ok(ProjectDictionary { words })
+    }
+}
+
+impl CheckTrait for SpellingCheck {
+    fn describe(&self) -> String {
+        format!("Files {:?}: must not contain misspellings", self.paths)
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let dictionary = self.dictionary(this_file_path)?;
+        let tokenizer = Tokenizer::new();
+
+        let mut count = 0;
+        let mut details = Vec::new();
+        for path in walk::expand(&self.paths, self.include_ignored)? {
+            // Not text
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let mut findings = Vec::new();
+            for (i, line) in contents.lines().enumerate() {
+                for typo in typos::check_str(line, &tokenizer, &dictionary) {
+                    let label = match &typo.corrections {
+                        Spelling::Corrections(corrections) => {
+                            format!("did you mean '{}'?", corrections.join("', '"))
+                        }
+                        _ => String::from("misspelled"),
+                    };
+                    findings.push(LineAnnotation {
+                        line_number: i + 1,
+                        line: line.to_string(),
+                        span: typo.span(),
+                        label,
+                    });
+                }
+            }
+
+            if !findings.is_empty() {
+                count += findings.len();
+                details.push(annotate::render("misspelling", &path, &findings));
+            }
+        }
+
+        if count == 0 {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{count} misspelling(s)"),
+            Some(details.join("\n")),
+        ))
+    }
+}
@@ -0,0 +1,158 @@
+use crate::types::{CheckTrait, Status, StatusStatus, ValueSource};
+use anyhow::{bail, Result};
+use different::DiffSettings;
+use log::debug;
+use minijinja::Environment;
+use postgres::{Client, NoTls, SimpleQueryMessage};
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Rows returned by a query, with every value rendered as a string
+type Rows = Vec<Vec<String>>;
+
+fn sqlite_value(value: ValueRef) -> String {
+    match value {
+        ValueRef::Null => String::from("NULL"),
+        ValueRef::Integer(i) => i.to_string(),
+        ValueRef::Real(f) => f.to_string(),
+        ValueRef::Text(t) => String::from_utf8_lossy(t).to_string(),
+        ValueRef::Blob(b) => hex::encode(b),
+    }
+}
+
+fn query_sqlite(path: &str, query: &str) -> Result<Rows> {
+    let conn = Connection::open(path)?;
+    let mut stmt = conn.prepare(query)?;
+    let column_count = stmt.column_count();
+    let mut rows = stmt.query([])?;
+
+    let mut result = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut values = Vec::new();
+        for i in 0..column_count {
+            values.push(sqlite_value(row.get_ref(i)?));
+        }
+        result.push(values);
+    }
+    Ok(result)
+}
+
+fn query_postgres(url: &str, query: &str) -> Result<Rows> {
+    let mut client = Client::connect(url, NoTls)?;
+    let mut result = Vec::new();
+    for message in client.simple_query(query)? {
+        if let SimpleQueryMessage::Row(row) = message {
+            let values = (0..row.len())
+                .map(|i| row.get(i).unwrap_or("NULL").to_string())
+                .collect();
+            result.push(values);
+        }
+    }
+    Ok(result)
+}
+
+fn run_query(url: &str, query: &str) -> Result<Rows> {
+    if let Some(path) = url
+        .strip_prefix("sqlite://")
+        .or_else(|| url.strip_prefix("sqlite:"))
+    {
+        debug!("Querying sqlite database {path}");
+        query_sqlite(path, query)
+    } else if url.starts_with("postgres://") || url.starts_with("postgresql://") {
+        debug!("Querying postgres database");
+        query_postgres(url, query)
+    } else {
+        bail!("Unsupported database url, expected sqlite:// or postgres://");
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct SqlCheck {
+    /// Database url, sqlite://path or postgres://...
+    url: ValueSource,
+
+    query: String,
+
+    /// Exact number of rows the query must return
+    rows: Option<usize>,
+
+    /// The query must return a single value equal to this
+    equals: Option<String>,
+}
+
+impl CheckTrait for SqlCheck {
+    fn describe(&self) -> String {
+        let mut s = format!("Query '{}' must succeed", self.query);
+
+        if let Some(rows) = &self.rows {
+            s.push_str(&format!(", must return {rows} rows"));
+        }
+
+        if let Some(equals) = &self.equals {
+            s.push_str(&format!(", must return '{equals}'"));
+        }
+
+        s
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let url = match self.url.resolve(vars) {
+            Ok(url) => url,
+            Err(e) => {
+                return Ok(Status::fail(
+                    String::from("Unable to get database url"),
+                    Some(format!("{e:#}")),
+                ));
+            }
+        };
+
+        let rows = match run_query(&url, &self.query) {
+            Ok(rows) => rows,
+            Err(e) => {
+                return Ok(Status::fail(
+                    String::from("Query failed"),
+                    Some(format!("{e:#}")),
+                ));
+            }
+        };
+
+        if let Some(expected_rows) = self.rows
+            && rows.len() != expected_rows
+        {
+            return Ok(Status::fail(
+                String::from("Unexpected row count"),
+                Some(format!("expected {expected_rows}, got {}", rows.len())),
+            ));
+        }
+
+        if let Some(expected) = &self.equals {
+            let actual = match rows.as_slice() {
+                [row] if row.len() == 1 => &row[0],
+                _ => {
+                    return Ok(Status::fail(
+                        String::from("Query did not return a single value"),
+                        Some(format!("got {rows:?}")),
+                    ));
+                }
+            };
+
+            if actual != expected {
+                return Ok(Status::fail(
+                    String::from("Unexpected query result"),
+                    Some(format!("expected '{expected}', got '{actual}'")),
+                ));
+            }
+        }
+
+        Ok(Status::new(false, StatusStatus::Pass))
+    }
+}
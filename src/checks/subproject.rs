@@ -0,0 +1,156 @@
+use crate::types::{CheckTrait, Status, StatusStatus, Statuses};
+use anyhow::{bail, Context, Result};
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Canonical paths of the projects the current run is nested in, outermost first. Passed down to
+/// nested runs so a subproject can't end up checking itself
+const PARENTS_VAR: &str = "CHECKLINTS_PARENT_PROJECTS";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct SubprojectCheck {
+    /// Directory of the subproject, which has its own checklists. Relative to the project
+    path: PathBuf,
+
+    /// Extra arguments for the nested run
+    #[serde(default)]
+    args: Vec<String>,
+
+    /// Project the subproject is part of. Set when the project loads its checklists
+    #[serde(skip)]
+    root: Option<PathBuf>,
+
+    /// Options of the parent run the nested run inherits. Set when the project loads its
+    /// checklists
+    #[serde(skip)]
+    run_args: Vec<OsString>,
+}
+
+impl SubprojectCheck {
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn set_project(&mut self, root: &Path, run_args: &[OsString]) {
+        self.root = Some(root.to_path_buf());
+        self.run_args = run_args.to_vec();
+    }
+
+    fn dir(&self) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(&self.path),
+            None => self.path.clone(),
+        }
+    }
+
+    /// Projects the nested run is part of: those the current run is nested in, and its own
+    fn parents(&self) -> Vec<PathBuf> {
+        let mut parents: Vec<PathBuf> = env::var_os(PARENTS_VAR)
+            .map(|parents| env::split_paths(&parents).collect())
+            .unwrap_or_default();
+        let root = self.root.as_deref().unwrap_or(Path::new("."));
+        if let Ok(root) = root.canonicalize() {
+            parents.push(root);
+        }
+        parents
+    }
+
+    /// Run this program again on the subproject
+    fn run(&self, dir: &Path, parents: &[PathBuf]) -> Result<Statuses> {
+        let exe = env::current_exe()?;
+        let output = Command::new(exe)
+            .current_dir(dir)
+            .env(PARENTS_VAR, env::join_paths(parents)?)
+            .args(["--output", "json", "--no-user-checklists"])
+            .args(&self.run_args)
+            .args(&self.args)
+            .arg(".")
+            .output()
+            .with_context(|| format!("Unable to run checks on {}", self.path.display()))?;
+
+        match serde_json::from_slice(&output.stdout) {
+            Ok(statuses) => Ok(statuses),
+            Err(_) => bail!(
+                "Checks on {} did not finish: {}",
+                self.path.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        }
+    }
+}
+
+impl CheckTrait for SubprojectCheck {
+    fn describe(&self) -> String {
+        format!("Subproject {}: checks must pass", self.path.display())
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let dir = self.dir();
+        let Ok(canonical) = dir.canonicalize() else {
+            return Ok(Status::fail(
+                String::from("Subproject directory does not exist"),
+                None,
+            ));
+        };
+        if !canonical.is_dir() {
+            return Ok(Status::fail(
+                String::from("Subproject is not a directory"),
+                None,
+            ));
+        }
+
+        let parents = self.parents();
+        if parents.contains(&canonical) {
+            return Ok(Status::fail(
+                String::from("Subproject is already being checked, it includes itself"),
+                None,
+            ));
+        }
+
+        let statuses = self.run(&dir, &parents)?;
+        let counts = statuses.counts();
+        let status = if counts.failed > 0 {
+            Status::fail(counts.to_string(), None)
+        } else if counts.review > 0 {
+            Status::review(counts.to_string(), None)
+        } else {
+            Status::new(false, StatusStatus::Pass)
+        };
+        Ok(status.with_nested(statuses))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn subproject_including_its_project_fails() {
+        let root = env::temp_dir().join(format!("checklints-subproject-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        let mut check: SubprojectCheck = toml::from_str("path = \".\"").unwrap();
+        check.set_project(&root, &[]);
+
+        let vars = HashMap::new();
+        let status = check
+            .do_check(&DiffSettings::new(), &Environment::new(), &root, &vars)
+            .unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        assert!(status.is_failure());
+        assert!(status.nested().is_none());
+    }
+}
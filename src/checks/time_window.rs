@@ -0,0 +1,93 @@
+use crate::types::{CheckTrait, Status, StatusStatus};
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveTime, Weekday};
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct Hours {
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+impl Hours {
+    /// Windows where `end` is before `start` wrap around midnight
+    fn contains(&self, time: NaiveTime) -> bool {
+        if self.start <= self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Matches the current local time. Meant to be used as a condition, anything else is reported as
+/// skipped
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct TimeWindowCheck {
+    /// Days of the week (e.g. "mon", "friday") that match
+    #[serde(default)]
+    days: Vec<Weekday>,
+
+    /// Time of day window that matches (e.g. { start = "09:00", end = "17:00" })
+    hours: Option<Hours>,
+}
+
+impl TimeWindowCheck {
+    fn window(&self) -> String {
+        let mut window = Vec::new();
+        if !self.days.is_empty() {
+            let days = self
+                .days
+                .iter()
+                .map(|day| day.to_string())
+                .collect::<Vec<_>>();
+            window.push(days.join(", "));
+        }
+        if let Some(hours) = &self.hours {
+            window.push(format!(
+                "{}-{}",
+                hours.start.format("%H:%M"),
+                hours.end.format("%H:%M")
+            ));
+        }
+        window.join(" ")
+    }
+}
+
+impl CheckTrait for TimeWindowCheck {
+    fn describe(&self) -> String {
+        format!("Time: must be within {}", self.window())
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let now = Local::now();
+        let day_matches = self.days.is_empty() || self.days.contains(&now.weekday());
+        let hours_matches = self
+            .hours
+            .as_ref()
+            .is_none_or(|hours| hours.contains(now.time()));
+
+        if day_matches && hours_matches {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::skip(
+            format!(
+                "Only runs within {}, it is {}",
+                self.window(),
+                now.format("%a %H:%M")
+            ),
+            None,
+        ))
+    }
+}
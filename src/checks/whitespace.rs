@@ -0,0 +1,147 @@
+use crate::annotate::{self, LineAnnotation};
+use crate::types::{default_true, CheckTrait, Status, StatusStatus};
+use crate::walk;
+use anyhow::Result;
+use different::DiffSettings;
+use minijinja::Environment;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Indent {
+    Spaces,
+    Tabs,
+}
+
+impl Indent {
+    /// The indent char that is not allowed
+    fn forbidden(&self) -> char {
+        match self {
+            Self::Spaces => '\t',
+            Self::Tabs => ' ',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+pub struct WhitespaceCheck {
+    /// Paths and/or globs of files to check
+    paths: Vec<String>,
+
+    /// Lines must not end in whitespace
+    #[serde(default = "default_true")]
+    trailing_whitespace: bool,
+
+    /// Files must end with a newline
+    #[serde(default = "default_true")]
+    final_newline: bool,
+
+    /// Lines must only be indented with this
+    indent: Option<Indent>,
+
+    /// Also match gitignored files
+    #[serde(default)]
+    include_ignored: bool,
+}
+
+impl WhitespaceCheck {
+    fn problems(&self, contents: &str) -> Vec<LineAnnotation> {
+        let mut problems = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line_number = i + 1;
+
+            if self.trailing_whitespace {
+                let trimmed = line.trim_end();
+                if trimmed.len() != line.len() {
+                    problems.push(LineAnnotation {
+                        line_number,
+                        line: line.to_string(),
+                        span: trimmed.len()..line.len(),
+                        label: String::from("trailing whitespace"),
+                    });
+                }
+            }
+
+            if let Some(indent) = self.indent {
+                let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+                if line[..indent_len].contains(indent.forbidden()) {
+                    problems.push(LineAnnotation {
+                        line_number,
+                        line: line.to_string(),
+                        span: 0..indent_len,
+                        label: format!("indent must only use {indent:?}").to_lowercase(),
+                    });
+                }
+            }
+        }
+
+        if self.final_newline && !contents.is_empty() && !contents.ends_with('\n') {
+            let line_number = contents.lines().count();
+            let line = contents.lines().last().unwrap_or_default();
+            problems.push(LineAnnotation {
+                line_number,
+                line: line.to_string(),
+                span: line.len()..line.len(),
+                label: String::from("missing final newline"),
+            });
+        }
+
+        problems
+    }
+}
+
+impl CheckTrait for WhitespaceCheck {
+    fn describe(&self) -> String {
+        let mut rules = Vec::new();
+
+        if self.trailing_whitespace {
+            rules.push(String::from("no trailing whitespace"));
+        }
+
+        if self.final_newline {
+            rules.push(String::from("must end with a newline"));
+        }
+
+        if let Some(indent) = self.indent {
+            rules.push(format!("indent with {indent:?}").to_lowercase());
+        }
+
+        format!("Files {:?}: {}", self.paths, rules.join(", "))
+    }
+
+    fn do_check(
+        &self,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        let mut count = 0;
+        let mut details = Vec::new();
+        for path in walk::expand(&self.paths, self.include_ignored)? {
+            // Not text
+            let Ok(contents) = fs::read_to_string(&path) else {
+                continue;
+            };
+
+            let problems = self.problems(&contents);
+            if !problems.is_empty() {
+                count += problems.len();
+                details.push(annotate::render("whitespace", &path, &problems));
+            }
+        }
+
+        if count == 0 {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::fail(
+            format!("{count} whitespace problem(s)"),
+            Some(details.join("\n")),
+        ))
+    }
+}
@@ -1,9 +1,179 @@
-use crate::types::RemoteFile;
-use clap::Parser;
+use crate::report::{OutputFormat, ReportFormat};
+use crate::select::Selector;
+use crate::types::{RemoteFile, Severity, Show};
+use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
+/// Parse a `key=value` pair
+pub(crate) fn parse_key_value(s: &str) -> Result<(String, String), String> {
+    let Some((key, value)) = s.split_once('=') else {
+        return Err(format!("expected key=value, got '{s}'"));
+    };
+    Ok((key.to_string(), value.to_string()))
+}
+
+#[derive(Subcommand)]
+pub enum TemplatesCommand {
+    /// Check every template for syntax errors and variables no fact provides
+    Validate,
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Show how much is cached, and what, per project
+    Stats,
+
+    /// Print the cache dir
+    Path,
+
+    /// Delete the project's cache
+    Clear {
+        /// Delete the whole cache, of every project and downloaded files
+        #[clap(long)]
+        all: bool,
+    },
+
+    /// Delete project caches and downloaded files that haven't been used for a while
+    Gc {
+        /// How long since they were last used
+        #[clap(long, default_value = "30days")]
+        older_than: humantime::Duration,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the settings in effect, after layering the defaults, config files, env, and CLI
+    /// options, with where each value came from
+    Show,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Combine results JSON files from several runs into one report
+    Merge {
+        /// Results files to combine
+        #[clap(required = true)]
+        results: Vec<PathBuf>,
+
+        /// Print the aggregated report as JSON
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Set the project up with an example checklist, and write the default config if there is none
+    Init,
+
+    /// Check the project's checklists and templates for problems, without running any checks
+    Validate,
+
+    /// Check that checklints can run here: the config loads, the cache dir is writable, templates
+    /// load, remotes are reachable, and the commands checklists require are installed
+    Doctor,
+
+    /// List every checklist and its checks, and which would be skipped, without running them
+    List {
+        /// Print the list as JSON
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Rewrite the project's checklists into a canonical layout: sections in a fixed order, checks
+    /// sorted, keys ordered, and arrays of tables as '[[check]]'
+    Fmt {
+        /// Don't rewrite anything, fail if any checklist isn't formatted
+        #[clap(long)]
+        check: bool,
+
+        /// Checklists to format, instead of the project's
+        files: Vec<PathBuf>,
+    },
+
+    /// Write a check by answering questions about it, and add it to a checklist
+    NewCheck,
+
+    /// Download the remote checklists and templates into 'vendor/checklints/' in the project, with
+    /// a manifest of their hashes. Runs use the vendored copies instead of fetching them
+    Vendor,
+
+    /// Work with the templates checks are compared against
+    Templates {
+        #[command(subcommand)]
+        command: TemplatesCommand,
+    },
+
+    /// Create the files of file checks from their templates, using checklists as project
+    /// scaffolding
+    Generate {
+        /// Also overwrite files that already exist
+        #[clap(long)]
+        force: bool,
+
+        /// Instead, render the checklist templates ('*.toml.j2' in the checklist dirs) with the
+        /// facts, into '<dir>/generated/'
+        #[clap(long, conflicts_with = "force")]
+        checklists: bool,
+    },
+
+    /// Inspect the settings
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Inspect and clean up the cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+
+    /// Run the checks and compare the results with a baseline run, reporting what's newly failing,
+    /// newly passing and removed. Only fails when something is newly failing
+    Diff {
+        /// Results file (written with '--output json') of the baseline run
+        #[clap(long)]
+        baseline: PathBuf,
+
+        /// Print the differences as JSON
+        #[clap(long)]
+        json: bool,
+    },
+
+    /// Print the results of the project's most recent run again, without re-running any checks
+    Report {
+        /// Results file (written with '--output json') to print instead of the most recent run
+        results: Option<PathBuf>,
+
+        /// Print the results as a standalone HTML page or Markdown document instead, with a
+        /// section per checklist, diffs, and a summary
+        #[clap(long, value_enum, default_value_t)]
+        format: ReportFormat,
+
+        /// Write the report to this file instead of printing it
+        #[clap(long, short)]
+        output: Option<PathBuf>,
+    },
+
+    /// Record that a manual check was verified by hand
+    Ack {
+        /// Id of the manual check
+        check: String,
+
+        /// Who verified it
+        #[clap(long)]
+        by: String,
+
+        /// How long the acknowledgement lasts
+        #[clap(long, default_value = "90days")]
+        expires: humantime::Duration,
+    },
+}
+
 #[derive(Parser)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Set config dir
     #[clap(long)]
     pub config_dir: Option<PathBuf>,
@@ -12,6 +182,15 @@ pub struct Cli {
     #[clap(long)]
     pub cache_dir: Option<PathBuf>,
 
+    /// Write the default config, with a comment on each setting, to 'config.toml' in the config dir
+    /// and exit
+    #[clap(long)]
+    pub write_default_config: bool,
+
+    /// With '--write-default-config', overwrite the config file if there is one
+    #[clap(long, requires = "write_default_config")]
+    pub force: bool,
+
     /// Display more output
     #[clap(short, long)]
     pub(crate) verbose: bool,
@@ -54,9 +233,102 @@ pub struct Cli {
     #[clap(long)]
     pub(crate) external_checklist: Vec<RemoteFile>,
 
-    /// Pull external template from remote
+    /// Pull external template from remote, or a .tar.gz, .tgz, .tar or .zip of templates
     #[clap(long)]
     pub(crate) external_template: Vec<RemoteFile>,
+
+    /// Print diffs in full instead of truncating long ones
+    #[clap(long)]
+    pub(crate) full_diff: bool,
+
+    /// Write full diffs of failed checks to this dir
+    #[clap(long)]
+    pub(crate) artifacts_dir: Option<PathBuf>,
+
+    /// Which check results to report
+    #[clap(long, value_enum)]
+    pub(crate) show: Option<Show>,
+
+    /// Fail the run when a check at least this severe fails (default error). Failures of less
+    /// severe checks are reported as warnings
+    #[clap(long, value_enum, value_name = "SEVERITY")]
+    pub(crate) fail_on: Option<Severity>,
+
+    /// Only run file and directory checks that cover files changed since BASE_REF (default HEAD),
+    /// according to git
+    #[clap(
+        long,
+        value_name = "BASE_REF",
+        num_args = 0..=1,
+        default_missing_value = "HEAD"
+    )]
+    pub(crate) changed: Option<String>,
+
+    /// With '--changed', also skip checks that aren't about files
+    #[clap(long, requires = "changed")]
+    pub(crate) changed_only: bool,
+
+    /// Fix problems where possible: apply the fixes of failing checks and re-run them, and offer to
+    /// install missing tools
+    #[clap(long)]
+    pub(crate) fix: bool,
+
+    /// Record the checks failing in this run as known failures in '.checklints-baseline.json',
+    /// replacing their previous entries. Known failures don't fail later runs
+    #[clap(long)]
+    pub(crate) update_baseline: bool,
+
+    /// Don't use the network: skip checks that need it and only use cached remote files
+    #[clap(long)]
+    pub(crate) offline: bool,
+
+    /// Audit the home directory (dotfiles, tool setup) instead of a project. Only user and
+    /// external checklists are used, and paths may start with '~/'
+    #[clap(long, conflicts_with = "project_dir")]
+    pub home: bool,
+
+    /// Fail when a template uses a variable that isn't set, instead of rendering it empty
+    #[clap(long)]
+    pub(crate) strict_undefined: bool,
+
+    /// Regenerate checklists from their templates ('*.toml.j2' in the checklist dirs) before
+    /// running them
+    #[clap(long)]
+    pub(crate) sync_checklists: bool,
+
+    /// Skip running the checks when nothing changed (git HEAD, uncommitted files, checklists,
+    /// settings) since the last run where they all passed, and report that run instead
+    #[clap(long)]
+    pub(crate) fast: bool,
+
+    /// Only run checks matching this selector (repeatable): 'id:ID', 'tag:TAG', 'checklist:FILE',
+    /// 'name:TEXT', a check type and part of its name ('file:README'), or just part of the name
+    #[clap(long, value_name = "SELECTOR")]
+    pub(crate) only: Vec<Selector>,
+
+    /// Don't run checks matching this selector (repeatable), e.g. 'tag:network'
+    #[clap(long, value_name = "SELECTOR")]
+    pub(crate) skip: Vec<Selector>,
+
+    /// Format to output results in
+    #[clap(long, value_enum, default_value_t)]
+    pub output: OutputFormat,
+
+    /// Write the '--output' results to this file, and print the report as usual
+    #[clap(long, value_name = "PATH")]
+    pub output_file: Option<PathBuf>,
+
+    /// Export a trace of the run (a span per checklist and check) to this OTLP/HTTP endpoint
+    #[clap(long, value_name = "URL")]
+    pub(crate) otlp_endpoint: Option<String>,
+
+    /// Attach metadata (e.g. commit SHA, CI job URL) to the report
+    #[clap(long, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub(crate) meta: Vec<(String, String)>,
+
+    /// Set a fact for checklists and templates, overriding any fact of the same name (repeatable)
+    #[clap(long = "var", value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    pub(crate) vars: Vec<(String, String)>,
 }
 
 #[cfg(test)]
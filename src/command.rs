@@ -12,12 +12,31 @@ pub struct Output {
     code: i32,
     stdout: Option<String>,
     stderr: Option<String>,
+    /// Exact bytes, without trimming or decoding
+    raw_stdout: Vec<u8>,
+    raw_stderr: Vec<u8>,
 }
 
 impl Output {
+    pub fn code(&self) -> i32 {
+        self.code
+    }
+
     pub fn stdout(&self) -> Option<&String> {
         self.stdout.as_ref()
     }
+
+    pub fn stderr(&self) -> Option<&String> {
+        self.stderr.as_ref()
+    }
+
+    pub fn raw_stdout(&self) -> &[u8] {
+        &self.raw_stdout
+    }
+
+    pub fn raw_stderr(&self) -> &[u8] {
+        &self.raw_stderr
+    }
 }
 
 fn bytes_to_maybe_str(b: &[u8]) -> Option<String> {
@@ -62,8 +81,8 @@ impl XCommand {
     pub fn from_single(command: &str) -> Result<Self> {
         let parts = shlex::split(command).unwrap();
 
-        let foo = parts.into_iter().map(|x| x.to_string()).collect::<Vec<_>>();
-        let Some((exec, args)) = foo.split_first() else {
+        let parts = parts.into_iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        let Some((exec, args)) = parts.split_first() else {
             bail!("Invalid command '{command}'")
         };
 
@@ -74,7 +93,7 @@ impl XCommand {
     }
 
     pub fn run(&self, env: Option<&HashMap<String, String>>) -> Result<Output> {
-        let child = spawn(self, None, env)?;
+        let child = spawn(self, None, true, env)?;
         let res = child.wait_with_output()?;
         let output = output_to_output(res)?;
         Ok(output)
@@ -86,6 +105,8 @@ fn output_to_output(input: std::process::Output) -> Result<Output> {
         code: input.status.code().unwrap(),
         stdout: bytes_to_maybe_str(&input.stdout),
         stderr: bytes_to_maybe_str(&input.stderr),
+        raw_stdout: input.stdout,
+        raw_stderr: input.stderr,
     })
 }
 
@@ -97,17 +118,17 @@ pub struct Pipeline {
 
 impl Pipeline {
     pub fn new(command: &str) -> Result<Self> {
-        let foo = command.split("|").map(XCommand::from_single);
-        let foo = foo.collect::<Result<Vec<_>>>()?;
+        let commands = command.split("|").map(XCommand::from_single);
+        let commands = commands.collect::<Result<Vec<_>>>()?;
 
-        for cmd in &foo {
+        for cmd in &commands {
             let exec = &cmd.exec;
             if which::which(exec).is_err() {
                 bail!("Command {exec:?} not found");
             }
         }
 
-        let Some((first, rest)) = foo.split_first() else {
+        let Some((first, rest)) = commands.split_first() else {
             bail!("Invalid command pipeline '{command}'")
         };
 
@@ -121,11 +142,13 @@ impl Pipeline {
         let output = match self.rest.len() {
             0 => self.first.run(env)?,
             _ => {
-                let mut previous = spawn(&self.first, None, env)?;
+                let mut previous = spawn(&self.first, None, false, env)?;
                 let mut previous_stdout_fd = previous.stdout.as_ref().unwrap().as_fd();
 
-                for next in &self.rest {
-                    previous = spawn(next, Some(previous_stdout_fd), env)?;
+                let last_index = self.rest.len() - 1;
+                for (i, next) in self.rest.iter().enumerate() {
+                    let is_last = i == last_index;
+                    previous = spawn(next, Some(previous_stdout_fd), is_last, env)?;
                     previous_stdout_fd = previous.stdout.as_ref().unwrap().as_fd();
                 }
                 let res = previous.wait_with_output()?;
@@ -136,9 +159,12 @@ impl Pipeline {
     }
 }
 
+/// Only the last command of a pipeline should capture stderr,
+/// nothing would drain the stderr pipes of the others
 fn spawn(
     c: &XCommand,
     stdin_fd: Option<BorrowedFd>,
+    capture_stderr: bool,
     env: Option<&HashMap<String, String>>,
 ) -> Result<Child> {
     let exec = &c.exec;
@@ -153,6 +179,9 @@ fn spawn(
         cmd = cmd.stdin(stdin);
     }
     let mut cmd = cmd.stdout(Stdio::piped());
+    if capture_stderr {
+        cmd = cmd.stderr(Stdio::piped());
+    }
     if let Some(env) = env {
         cmd = cmd.envs(env);
     };
@@ -0,0 +1,102 @@
+//! Compare a run against an earlier one, so a project can adopt checks gradually: only what got
+//! worse since the baseline has to be fixed
+use crate::types::{Status, Statuses};
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// A check, named by its checklist's file name and its own name. Checklist paths differ between
+/// machines, so they're not compared
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct CheckId {
+    checklist: String,
+    check: String,
+}
+
+impl CheckId {
+    fn new(checklist_path: &Path, check: &str) -> Self {
+        let checklist = checklist_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+        Self {
+            checklist,
+            check: check.to_string(),
+        }
+    }
+}
+
+fn by_id(statuses: &Statuses) -> BTreeMap<CheckId, &Status> {
+    statuses
+        .iter()
+        .map(|(checklist_path, name, status)| (CheckId::new(checklist_path, name), status))
+        .collect()
+}
+
+/// How a run differs from the baseline
+#[derive(Debug, Default, Serialize)]
+pub struct RunDiff {
+    /// Failing now, but not in the baseline (including checks the baseline didn't have)
+    newly_failing: Vec<CheckId>,
+    /// Failed in the baseline, not failing now
+    newly_passing: Vec<CheckId>,
+    /// In the baseline, but not run now
+    removed: Vec<CheckId>,
+}
+
+impl RunDiff {
+    pub fn new(baseline: &Statuses, current: &Statuses) -> Self {
+        let baseline = by_id(baseline);
+        let current = by_id(current);
+
+        let mut diff = Self::default();
+        for (id, status) in &current {
+            let failed_before = baseline.get(id).is_some_and(|s| s.is_failure());
+            if status.is_failure() && !failed_before {
+                diff.newly_failing.push(id.clone());
+            } else if !status.is_failure() && failed_before {
+                diff.newly_passing.push(id.clone());
+            }
+        }
+        diff.removed = baseline
+            .keys()
+            .filter(|id| !current.contains_key(*id))
+            .cloned()
+            .collect();
+        diff
+    }
+
+    /// Something fails that didn't in the baseline
+    pub fn is_regression(&self) -> bool {
+        !self.newly_failing.is_empty()
+    }
+
+    pub fn json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string_pretty(&self)?)
+    }
+
+    pub fn print(&self) {
+        let sections = [
+            ("Newly failing", &self.newly_failing, "FAIL".red()),
+            ("Newly passing", &self.newly_passing, "PASS".green()),
+            ("Removed", &self.removed, "GONE".dimmed()),
+        ];
+        for (title, ids, label) in sections {
+            if ids.is_empty() {
+                continue;
+            }
+            println!("{}", format!("{title} ({})", ids.len()).bold());
+            for id in ids {
+                println!("    [{label}] {} ({})", id.check, id.checklist.dimmed());
+            }
+            println!();
+        }
+        println!(
+            "{} newly failing, {} newly passing, {} removed since the baseline",
+            self.newly_failing.len(),
+            self.newly_passing.len(),
+            self.removed.len()
+        );
+    }
+}
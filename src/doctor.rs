@@ -0,0 +1,281 @@
+//! Check that checklints itself can run here: its config, cache, templates, the remote files it
+//! fetches, and the commands checklists require
+use crate::project::Project;
+use crate::settings::Settings;
+use crate::types::{Checklist, RemoteFile, Requirement};
+use crate::vendor::Manifest;
+use crate::INDENT;
+use anyhow::Result;
+use colored::Colorize;
+use reqwest::blocking::Client;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+/// How long to wait for a remote to respond
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Commands checklints runs itself
+const OWN_COMMANDS: [&str; 1] = ["git"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+pub struct Finding {
+    area: &'static str,
+    health: Health,
+    message: String,
+}
+
+impl Finding {
+    fn new(area: &'static str, health: Health, message: impl Into<String>) -> Self {
+        Self {
+            area,
+            health,
+            message: message.into(),
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.health == Health::Error
+    }
+
+    pub fn print(&self) {
+        let label = match self.health {
+            Health::Ok => "[OK]".green(),
+            Health::Warning => "[WARN]".yellow(),
+            Health::Error => "[ERROR]".red(),
+        };
+        let mut lines = self.message.lines();
+        println!(
+            "{label} {}: {}",
+            self.area,
+            lines.next().unwrap_or_default()
+        );
+        for line in lines {
+            println!("{INDENT}{line}");
+        }
+    }
+}
+
+fn check_config(config_file: Option<&Path>, settings: &Result<Settings>) -> Finding {
+    match (settings, config_file) {
+        (Err(e), _) => Finding::new("Config", Health::Error, format!("{e:#}")),
+        (Ok(_), Some(path)) => {
+            Finding::new("Config", Health::Ok, format!("{} loads", path.display()))
+        }
+        (Ok(_), None) => Finding::new(
+            "Config",
+            Health::Ok,
+            "No config file, using the default settings",
+        ),
+    }
+}
+
+fn check_cache_dir(cache_dir: &Path) -> Finding {
+    let probe = cache_dir.join(".doctor");
+    let written = fs::create_dir_all(cache_dir)
+        .and_then(|()| fs::write(&probe, ""))
+        .and_then(|()| fs::remove_file(&probe));
+    match written {
+        Ok(()) => Finding::new(
+            "Cache",
+            Health::Ok,
+            format!("{} is writable", cache_dir.display()),
+        ),
+        Err(e) => Finding::new(
+            "Cache",
+            Health::Error,
+            format!("{} is not writable: {e}", cache_dir.display()),
+        ),
+    }
+}
+
+fn check_remote(client: &Client, remote: &RemoteFile, vendored: Option<&Manifest>) -> Finding {
+    let url = remote.url();
+    if let Some(manifest) = vendored
+        && let Ok(Some(path)) = manifest.get(remote)
+    {
+        return Finding::new(
+            "Remote",
+            Health::Ok,
+            format!("{url} is vendored at {}", path.display()),
+        );
+    }
+    if !matches!(url.scheme(), "http" | "https") {
+        return Finding::new(
+            "Remote",
+            Health::Warning,
+            format!("{url} not checked, only http(s) remotes are"),
+        );
+    }
+    match client.head(url.to_string()).send() {
+        Ok(response) if response.status().is_success() => {
+            Finding::new("Remote", Health::Ok, format!("{url} is reachable"))
+        }
+        Ok(response) => Finding::new(
+            "Remote",
+            Health::Error,
+            format!("{url} responded {}", response.status()),
+        ),
+        Err(e) => Finding::new(
+            "Remote",
+            Health::Error,
+            format!("{url} is unreachable: {e}"),
+        ),
+    }
+}
+
+fn check_remotes(settings: &Settings, project_dir: &Path) -> Vec<Finding> {
+    let remotes = settings
+        .external_checklists()
+        .iter()
+        .chain(settings.external_templates())
+        .collect::<Vec<_>>();
+    if remotes.is_empty() {
+        return Vec::new();
+    }
+    if settings.offline() {
+        return vec![Finding::new(
+            "Remote",
+            Health::Warning,
+            format!("{} remote(s) not checked, running offline", remotes.len()),
+        )];
+    }
+
+    let vendored = match Manifest::load(project_dir) {
+        Ok(vendored) => vendored,
+        Err(e) => return vec![Finding::new("Remote", Health::Error, format!("{e:#}"))],
+    };
+    let client = match Client::builder().timeout(REMOTE_TIMEOUT).build() {
+        Ok(client) => client,
+        Err(e) => return vec![Finding::new("Remote", Health::Error, e.to_string())],
+    };
+    remotes
+        .into_iter()
+        .map(|remote| check_remote(&client, remote, vendored.as_ref()))
+        .collect()
+}
+
+fn check_templates(project: &Project) -> Finding {
+    if let Some(e) = project.template_errors().first() {
+        let count = project.template_errors().len();
+        return Finding::new(
+            "Templates",
+            Health::Error,
+            format!("{count} template(s) don't load, the first: {e}"),
+        );
+    }
+    let count = project.template_env().templates().count();
+    Finding::new(
+        "Templates",
+        Health::Ok,
+        format!(
+            "{count} template(s) and {} checklist(s) load",
+            project.checklists().len()
+        ),
+    )
+}
+
+/// Commands checklints and the checklists require, missing ones first
+fn check_commands(checklists: &[Checklist]) -> Vec<Finding> {
+    let mut required = BTreeSet::new();
+    let mut install = Vec::new();
+    let requirements = checklists.iter().flat_map(|checklist| {
+        checklist
+            .requirements()
+            .iter()
+            .chain(checklist.checks().iter().flat_map(|c| c.requirements()))
+    });
+    for requirement in requirements {
+        if let Requirement::Command { command, .. } = requirement {
+            required.insert(command.as_str());
+        }
+        if let Some(missing) = requirement.missing_install() {
+            install.push(missing);
+        }
+    }
+
+    let (found, missing): (Vec<_>, Vec<_>) = OWN_COMMANDS
+        .into_iter()
+        .chain(required)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .partition(|command| which::which(command).is_ok());
+
+    let mut findings = missing
+        .into_iter()
+        .map(|command| {
+            let message = match install.iter().find(|(c, _)| *c == command) {
+                Some((_, install)) => format!("'{command}' is missing, install it with: {install}"),
+                None => format!("'{command}' is missing"),
+            };
+            Finding::new("Command", Health::Error, message)
+        })
+        .collect::<Vec<_>>();
+    if !found.is_empty() {
+        let found = found.iter().map(|c| format!("'{c}'")).collect::<Vec<_>>();
+        findings.push(Finding::new(
+            "Command",
+            Health::Ok,
+            format!("Found {}", found.join(", ")),
+        ));
+    }
+    findings
+}
+
+/// Check everything checklints needs, in order: the config (`settings`, as loaded from
+/// `config_file`), the cache dir, remotes, then the project `load`ed with the settings
+pub fn doctor<'a>(
+    config_file: Option<&Path>,
+    settings: Result<Settings>,
+    project_dir: &Path,
+    cache_dir: &Path,
+    load: impl FnOnce(Settings) -> Result<Project<'a>>,
+) -> Vec<Finding> {
+    let mut findings = vec![
+        check_config(config_file, &settings),
+        check_cache_dir(cache_dir),
+    ];
+    let Ok(settings) = settings else {
+        findings.push(Finding::new(
+            "Project",
+            Health::Warning,
+            "Not checked, the config doesn't load",
+        ));
+        return findings;
+    };
+
+    findings.extend(check_remotes(&settings, project_dir));
+    match load(settings) {
+        Ok(project) => {
+            findings.push(check_templates(&project));
+            findings.extend(check_commands(project.checklists()));
+        }
+        Err(e) => findings.push(Finding::new("Project", Health::Error, format!("{e:#}"))),
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_dir_must_be_writable() {
+        let dir = std::env::temp_dir().join(format!("checklints-doctor-{}", std::process::id()));
+        assert_eq!(check_cache_dir(&dir).health, Health::Ok);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let file = dir.with_extension("file");
+        fs::write(&file, "").unwrap();
+        assert!(check_cache_dir(&file).is_error());
+        fs::remove_file(&file).unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+//! Rewrite checklists into a canonical layout: sections in a fixed order, checks sorted, keys of
+//! each table ordered, and arrays of tables written as `[[check]]` rather than inline
+use anyhow::{Context, Result};
+use std::cmp::Ordering;
+use std::fs;
+use std::path::Path;
+use toml_edit::{Decor, DocumentMut, Item, Key, RawString, Table, Value};
+
+/// Order of the sections of a checklist. Other sections are kept, after these
+const SECTIONS: [&str; 5] = ["templates", "fact", "requires", "condition", "check"];
+
+/// Sections whose entries run in order, so they're not sorted
+const ORDERED_SECTIONS: [&str; 1] = ["fact"];
+
+/// Keys that come first in a table, in this order
+const LEADING_KEYS: [&str; 4] = ["type", "id", "key", "description"];
+
+/// Keys that come last in a table, in this order. Everything in between is sorted by name
+const TRAILING_KEYS: [&str; 6] = [
+    "tags",
+    "severity",
+    "conditions",
+    "conditions_mode",
+    "requirements",
+    "fix",
+];
+
+/// Keys that checks of the same type are sorted by, the first one a check has
+const SORT_KEYS: [&str; 5] = ["path", "url", "cmd", "metric", "description"];
+
+fn has_comment(raw: Option<&RawString>) -> bool {
+    raw.and_then(|raw| raw.as_str())
+        .is_some_and(|s| s.contains('#'))
+}
+
+/// Reset whitespace to `prefix` and `suffix`, unless there's a comment in it to keep
+fn normalize(decor: &mut Decor, prefix: &str, suffix: &str) {
+    if !has_comment(decor.prefix()) {
+        decor.set_prefix(prefix);
+    }
+    if !has_comment(decor.suffix()) {
+        decor.set_suffix(suffix);
+    }
+}
+
+fn key_rank(key: &str) -> (usize, &str) {
+    if let Some(i) = LEADING_KEYS.iter().position(|k| *k == key) {
+        (i, "")
+    } else if let Some(i) = TRAILING_KEYS.iter().position(|k| *k == key) {
+        (LEADING_KEYS.len() + 1 + i, "")
+    } else {
+        (LEADING_KEYS.len(), key)
+    }
+}
+
+fn compare_keys(a: &Key, _: &Item, b: &Key, _: &Item) -> Ordering {
+    key_rank(a.get()).cmp(&key_rank(b.get()))
+}
+
+fn format_value(value: &mut Value) {
+    match value {
+        Value::InlineTable(table) => {
+            table.sort_values_by(|a, _, b, _| key_rank(a.get()).cmp(&key_rank(b.get())));
+            // Inline tables can't have comments, nothing to lose
+            table.fmt();
+            table.iter_mut().for_each(|(_, value)| format_value(value));
+        }
+        Value::Array(array) => array.iter_mut().for_each(format_value),
+        _ => {}
+    }
+}
+
+fn format_table(table: &mut Table) {
+    table.sort_values_by(compare_keys);
+    for (mut key, item) in table.iter_mut() {
+        match item {
+            Item::Value(value) => {
+                normalize(key.leaf_decor_mut(), "", " ");
+                normalize(value.decor_mut(), " ", "");
+                format_value(value);
+            }
+            Item::Table(table) => format_table(table),
+            Item::ArrayOfTables(tables) => tables.iter_mut().for_each(format_table),
+            Item::None => {}
+        }
+    }
+}
+
+fn sort_key(table: &Table) -> (String, String) {
+    let value = |key: &str| {
+        table
+            .get(key)
+            .and_then(|item| item.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+    let by = SORT_KEYS
+        .iter()
+        .map(|key| value(key))
+        .find(|value| !value.is_empty())
+        .unwrap_or_default();
+    (value("type"), by)
+}
+
+/// Place `table` and the tables nested in it in the document, from position `next`
+fn set_positions(table: &mut Table, next: &mut usize) {
+    table.set_position(*next);
+    *next += 1;
+    for (_, item) in table.iter_mut() {
+        match item {
+            Item::Table(table) if !table.is_dotted() => set_positions(table, next),
+            Item::ArrayOfTables(tables) => tables
+                .iter_mut()
+                .for_each(|table| set_positions(table, next)),
+            _ => {}
+        }
+    }
+}
+
+/// `contents` of a checklist, formatted
+pub fn format(contents: &str) -> Result<String> {
+    let mut doc: DocumentMut = contents.parse()?;
+
+    let mut names = SECTIONS.map(String::from).to_vec();
+    for (key, _) in doc.iter() {
+        if !names.iter().any(|name| name == key) {
+            names.push(key.to_string());
+        }
+    }
+
+    let mut next = 0;
+    for name in &names {
+        let Some(item) = doc.remove(name) else {
+            continue;
+        };
+        let mut item = match item.into_array_of_tables() {
+            Ok(mut tables) => {
+                if !ORDERED_SECTIONS.contains(&name.as_str()) {
+                    let mut sorted = tables.iter().cloned().collect::<Vec<_>>();
+                    sorted.sort_by_key(sort_key);
+                    tables.clear();
+                    sorted.into_iter().for_each(|table| tables.push(table));
+                }
+                Item::ArrayOfTables(tables)
+            }
+            Err(item) => item,
+        };
+
+        match &mut item {
+            Item::Table(table) => {
+                format_table(table);
+                set_positions(table, &mut next);
+            }
+            Item::ArrayOfTables(tables) => {
+                for table in tables.iter_mut() {
+                    format_table(table);
+                    set_positions(table, &mut next);
+                }
+            }
+            _ => {}
+        }
+        doc.insert(name, item);
+    }
+
+    // A blank line between tables, none before the first unless there are values before it
+    let first = if doc.iter().any(|(_, item)| item.is_value()) {
+        None
+    } else {
+        Some(0)
+    };
+    for (_, item) in doc.iter_mut() {
+        space_tables(item, first);
+    }
+
+    let mut formatted = doc.to_string();
+    if !formatted.ends_with('\n') {
+        formatted.push('\n');
+    }
+    Ok(formatted)
+}
+
+fn space_tables(item: &mut Item, first: Option<usize>) {
+    let tables: Vec<&mut Table> = match item {
+        Item::Table(table) if !table.is_dotted() => vec![table],
+        Item::ArrayOfTables(tables) => tables.iter_mut().collect(),
+        _ => return,
+    };
+    for table in tables {
+        let prefix = if table.position() == first { "" } else { "\n" };
+        let decor = table.decor_mut();
+        normalize(decor, prefix, "");
+        // Comments above a table stay with it, still separated from the table before
+        if let Some(comment) = decor.prefix().and_then(|raw| raw.as_str())
+            && !comment.starts_with(prefix)
+        {
+            let comment = format!("{prefix}{}", comment.trim_start());
+            decor.set_prefix(comment);
+        }
+        for (_, item) in table.iter_mut() {
+            space_tables(item, first);
+        }
+    }
+}
+
+/// Format the checklist at `path`, only checking whether it's formatted if `check`. Returns
+/// whether it was (or would be) changed
+pub fn format_file(path: &Path, check: bool) -> Result<bool> {
+    let contents = fs::read_to_string(path)?;
+    let formatted =
+        format(&contents).with_context(|| format!("Unable to format {}", path.display()))?;
+    let changed = formatted != contents;
+    if changed && !check {
+        fs::write(path, formatted)?;
+    }
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checklist_is_formatted_canonically() {
+        let checklist = r#"condition = [{ path = "c", type = "file" }]
+[[fact]]
+value = "1"
+key = "a"
+type = "literal"
+[[check]]
+path = "b"
+type = "file"
+[[check]]
+# why
+path = "a"
+type = "file"
+"#;
+        let formatted = r#"[[fact]]
+type = "literal"
+key = "a"
+value = "1"
+
+[[condition]]
+type = "file"
+path = "c"
+
+[[check]]
+type = "file"
+# why
+path = "a"
+
+[[check]]
+type = "file"
+path = "b"
+"#;
+        assert_eq!(format(checklist).unwrap(), formatted);
+        assert_eq!(format(formatted).unwrap(), formatted);
+    }
+}
@@ -0,0 +1,103 @@
+//! Inventory of the machine checklints runs on, exposed as 'host.*' facts
+use crate::command::run_command_line;
+use directories::BaseDirs;
+use minijinja::value::{Enumerator, Object, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Arc, LazyLock};
+use std::{env, thread};
+use sysinfo::System;
+
+/// Kind of virtualization, from systemd-detect-virt when available (e.g. 'kvm', 'docker'), else a
+/// rough guess of 'container', 'vm' or 'none'
+fn virtualization() -> String {
+    if let Ok(output) = run_command_line("systemd-detect-virt", None)
+        && let Some(stdout) = output.stdout()
+        && !stdout.trim().is_empty()
+    {
+        return stdout.trim().to_string();
+    }
+
+    if Path::new("/.dockerenv").exists() || Path::new("/run/.containerenv").exists() {
+        return String::from("container");
+    }
+
+    let hypervisor = std::fs::read_to_string("/proc/cpuinfo")
+        .is_ok_and(|cpuinfo| cpuinfo.split_whitespace().any(|flag| flag == "hypervisor"));
+    if hypervisor {
+        String::from("vm")
+    } else {
+        String::from("none")
+    }
+}
+
+/// Taken the first time it's needed, since it runs commands
+static INVENTORY: LazyLock<BTreeMap<String, String>> = LazyLock::new(take_inventory);
+
+/// Facts about the machine, keyed without the 'host.' prefix
+pub fn inventory() -> &'static BTreeMap<String, String> {
+    &INVENTORY
+}
+
+/// The 'host' template global, which only takes the inventory once a template reads from it
+#[derive(Debug)]
+pub struct LazyInventory;
+
+impl Object for LazyInventory {
+    fn get_value(self: &Arc<Self>, key: &Value) -> Option<Value> {
+        inventory().get(key.as_str()?).map(Value::from)
+    }
+
+    fn enumerate(self: &Arc<Self>) -> Enumerator {
+        Enumerator::Iter(Box::new(inventory().keys().map(Value::from)))
+    }
+}
+
+fn take_inventory() -> BTreeMap<String, String> {
+    let mut system = System::new();
+    system.refresh_memory();
+
+    let user = env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_default();
+    let home = BaseDirs::new()
+        .map(|base_dirs| base_dirs.home_dir().display().to_string())
+        .unwrap_or_default();
+    let cpus = thread::available_parallelism().map_or(1, |n| n.get());
+
+    BTreeMap::from([
+        (
+            String::from("name"),
+            System::host_name().unwrap_or_default(),
+        ),
+        (String::from("os"), env::consts::OS.to_string()),
+        (
+            String::from("os_version"),
+            System::os_version().unwrap_or_default(),
+        ),
+        (
+            String::from("kernel"),
+            System::kernel_version().unwrap_or_default(),
+        ),
+        (String::from("arch"), env::consts::ARCH.to_string()),
+        (String::from("cpus"), cpus.to_string()),
+        (String::from("memory"), system.total_memory().to_string()),
+        (String::from("virtualization"), virtualization()),
+        (String::from("user"), user),
+        (String::from("home"), home),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minijinja::Environment;
+
+    #[test]
+    fn templates_read_the_inventory() {
+        let mut env = Environment::new();
+        env.add_global("host", Value::from_object(LazyInventory));
+        let rendered = env.render_str("{{ host.os }}", ()).unwrap();
+        assert_eq!(rendered, env::consts::OS);
+    }
+}
@@ -0,0 +1,94 @@
+//! Set a project up to use checklints
+use crate::settings::write_default_config;
+use anyhow::Result;
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const EXAMPLE_CHECKLIST_NAME: &str = "example.toml";
+
+const EXAMPLE_CHECKLIST: &str = r###"# Example checklist. Every .toml file in this dir is a checklist the project is checked against
+
+# Facts are values worked out for the project, usable in templates as '{{ key }}'
+# [[fact]]
+# key = "edition"
+# type = "literal"
+# value = "2024"
+
+# A file must exist
+[[check]]
+type = "file"
+path = "README.md"
+
+# A file must contain some text
+# [[check]]
+# type = "file"
+# path = "README.md"
+# contains = ["## License"]
+
+# A file must match a template (path relative to this checklist). 'run-checks --fix' writes it
+# [[check]]
+# type = "file"
+# path = ".editorconfig"
+# template = "templates/editorconfig.j2"
+# fix = { template = "templates/editorconfig.j2" }
+
+# A command must succeed
+# [[check]]
+# type = "command"
+# cmd = "cargo fmt --check"
+"###;
+
+/// Write `contents` to `path` unless it already exists. Returns whether it was written
+fn write_new(path: &Path, contents: impl FnOnce(&Path) -> Result<()>) -> Result<bool> {
+    if path.exists() {
+        debug!("Not writing {}, it already exists", path.display());
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    contents(path)?;
+    Ok(true)
+}
+
+/// Create `checklists_dir` with an example checklist, and the user config file with the default
+/// settings and a dir for user checklists. Files that already exist are left alone. Returns the
+/// files written
+pub fn init(
+    checklists_dir: &Path,
+    config_file: &Path,
+    user_checklists_dir: &Path,
+) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+
+    let example = checklists_dir.join(EXAMPLE_CHECKLIST_NAME);
+    if write_new(&example, |path| Ok(fs::write(path, EXAMPLE_CHECKLIST)?))? {
+        written.push(example);
+    }
+
+    if write_new(config_file, write_default_config)? {
+        written.push(config_file.to_path_buf());
+    }
+    // User checklists are on by default, and the dir has to exist to use them
+    fs::create_dir_all(user_checklists_dir)?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Checklist;
+
+    #[test]
+    fn example_checklist_parses() {
+        let dir = std::env::temp_dir().join(format!("checklints-init-{}", std::process::id()));
+        let path = dir.join(EXAMPLE_CHECKLIST_NAME);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, EXAMPLE_CHECKLIST).unwrap();
+        let checklist = Checklist::from_path(path);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(checklist.unwrap().checks().len(), 1);
+    }
+}
@@ -1,10 +1,35 @@
+pub mod ack;
+pub mod annotate;
+pub mod archive;
+pub mod baseline;
 pub mod cache;
+pub mod checks;
 pub mod cli;
 pub mod command;
+pub mod diff;
+pub mod doctor;
+pub mod fmt;
+pub mod host;
+pub mod init;
+pub mod list;
+pub mod merge;
+pub mod otlp;
+pub mod progress;
 pub mod project;
+pub mod region;
+pub mod report;
+pub mod select;
 pub mod settings;
+pub mod template;
 pub mod types;
+pub mod validate;
+pub mod vendor;
+pub mod walk;
+pub mod wizard;
 
 pub const THIS_CRATE_NAME: &str = env!("CARGO_PKG_NAME");
 pub const INDENT: &str = "    ";
 pub const CONFIG_FILE_NAME: &str = "config.toml";
+pub const PROJECT_CONFIG_FILE_NAME: &str = ".checklints.toml";
+pub const ACKS_FILE_NAME: &str = ".checklints-acks.toml";
+pub const BASELINE_FILE_NAME: &str = ".checklints-baseline.json";
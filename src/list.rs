@@ -0,0 +1,60 @@
+//! What a run would check, worked out without running any checks
+use crate::INDENT;
+use colored::Colorize;
+use serde::Serialize;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// Where a checklist comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Project,
+    User,
+    Remote,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Source::Project => "project",
+            Source::User => "user",
+            Source::Remote => "remote",
+        };
+        write!(f, "{s}")
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListedCheck {
+    pub description: String,
+    /// Why the check would be skipped, if it would be
+    pub skipped: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListedChecklist {
+    pub path: PathBuf,
+    pub source: Source,
+    pub checks: Vec<ListedCheck>,
+}
+
+impl ListedChecklist {
+    pub fn print(&self) {
+        println!(
+            "> Checklist '{}' ({})",
+            self.path.display().to_string().cyan(),
+            self.source
+        );
+        for check in &self.checks {
+            match &check.skipped {
+                Some(reason) => println!(
+                    "{INDENT}{} {}",
+                    check.description.dimmed(),
+                    format!("(skipped: {reason})").yellow()
+                ),
+                None => println!("{INDENT}{}", check.description),
+            }
+        }
+    }
+}
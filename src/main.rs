@@ -1,18 +1,115 @@
 use anyhow::{bail, Result};
-use checklints::cli::Cli;
-use checklints::project::Project;
+use bytesize::ByteSize;
+use checklints::ack::{Ack, Acks};
+use checklints::cache;
+use checklints::cli::{CacheCommand, Cli, Command, ConfigCommand, TemplatesCommand};
+use checklints::diff::RunDiff;
+use checklints::doctor::{doctor, Finding};
+use checklints::fmt::format_file;
+use checklints::init::init;
+use checklints::merge::MergedReport;
+use checklints::otlp;
+use checklints::project::{
+    fingerprint, is_generated_checklist, last_run_path, local_checklist_paths, project_cache_key,
+    save_fingerprint, unchanged_green_run, Project,
+};
+use checklints::report::{self, OutputFormat, ReportFormat};
 use checklints::settings::{write_default_config, Settings};
-use checklints::{CONFIG_FILE_NAME, THIS_CRATE_NAME};
+use checklints::types::Statuses;
+use checklints::validate::{lint_checklists, lint_templates, validate, Problem};
+use checklints::vendor::vendor;
+use checklints::wizard::new_check;
+use checklints::{ACKS_FILE_NAME, CONFIG_FILE_NAME, INDENT, THIS_CRATE_NAME};
 use clap::Parser;
+use colored::Colorize;
 use different::DiffSettings;
-use directories::ProjectDirs;
+use directories::{BaseDirs, ProjectDirs};
 use log::debug;
 use std::env;
 use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Checklist that checks are added to when the project has none to choose from
+const NEW_CHECKLIST_NAME: &str = "checks.toml";
+
+/// Print problems found by validating, and exit with failure if any is an error
+fn print_problems(problems: &[Problem]) -> ! {
+    for problem in problems {
+        println!("{problem}");
+    }
+    let errors = problems.iter().filter(|p| p.is_error()).count();
+    let warnings = problems.len() - errors;
+    println!("{errors} error(s), {warnings} warning(s)");
+    let code = if errors > 0 { 1 } else { exitcode::OK };
+    std::process::exit(code);
+}
+
+/// Print the checks `--show` includes, with a summary of them all
+fn print_report(statuses: &Statuses, settings: &Settings) {
+    statuses.filtered(settings.show()).print(settings);
+    println!("{}", statuses.summary_line().bold());
+}
+
+/// Print the results in `output` format, or write them to `output_file` and print the report
+fn print_statuses(
+    statuses: &Statuses,
+    settings: &Settings,
+    output: OutputFormat,
+    output_file: Option<&Path>,
+) -> Result<()> {
+    let show = settings.show();
+    let results = match output {
+        OutputFormat::Text => None,
+        OutputFormat::Json => Some(statuses.shown_json(show, settings.exit_codes())?),
+        OutputFormat::Junit => Some(report::junit(&statuses.filtered(show))),
+        OutputFormat::Sarif => Some(report::sarif(&statuses.filtered(show))?),
+        OutputFormat::Markdown => Some(report::markdown_table(statuses, show)),
+    };
+    match (results, output_file) {
+        (Some(results), Some(path)) => {
+            fs::write(path, results)?;
+            print_report(statuses, settings);
+        }
+        (Some(results), None) => println!("{results}"),
+        (None, _) => print_report(statuses, settings),
+    }
+    eprintln!("{}", statuses.verdict_line());
+    Ok(())
+}
+
+fn build_settings(config_file: Option<&Path>, project_dir: &Path, args: Cli) -> Result<Settings> {
+    let mut settings = Settings::builder();
+    if let Some(config_file) = config_file {
+        settings = settings.config_layer(config_file)?;
+    };
+
+    settings
+        .project_config_layer(project_dir)?
+        .env_layer()?
+        .arg_layer(args)
+        .build()
+}
 
 fn main() -> Result<()> {
     env_logger::init();
-    let args = Cli::parse();
+    let mut args = Cli::parse();
+
+    let command = args.command.take();
+    let output = args.output;
+    let output_file = args.output_file.clone();
+    if output_file.is_some() && output == OutputFormat::Text {
+        bail!("'--output-file' needs an '--output' format other than text");
+    }
+    if let Some(Command::Merge { results, json }) = &command {
+        let report = MergedReport::from_paths(results)?;
+        if *json {
+            println!("{}", report.json()?);
+        } else {
+            report.print();
+        }
+        return Ok(());
+    }
 
     let Some(proj_dirs) = ProjectDirs::from("", "", THIS_CRATE_NAME) else {
         bail!("Unable to get XDG project dirs");
@@ -24,7 +121,21 @@ fn main() -> Result<()> {
         proj_dirs.config_dir()
     };
     if !config_dir.is_dir() {
-        fs::create_dir_all(&config_dir)?;
+        fs::create_dir_all(config_dir)?;
+    }
+
+    // Doesn't need a project, so done before looking for one
+    let config_path = config_dir.join(CONFIG_FILE_NAME);
+    if args.write_default_config {
+        if config_path.exists() && !args.force {
+            bail!(
+                "{} already exists, pass --force to overwrite it",
+                config_path.display()
+            );
+        }
+        write_default_config(&config_path)?;
+        println!("Wrote {}", config_path.display());
+        return Ok(());
     }
 
     let cache_dir = if let Some(cache_dir) = args.cache_dir.clone() {
@@ -32,19 +143,25 @@ fn main() -> Result<()> {
     } else {
         proj_dirs.cache_dir().to_path_buf()
     };
-    if !cache_dir.is_file() {
+    // Doctor reports a cache dir it can't create instead
+    if !cache_dir.is_file() && !matches!(command, Some(Command::Doctor)) {
         fs::create_dir_all(&cache_dir)?;
     }
 
     let project_dir = match args.project_dir {
         Some(ref project_dir) => project_dir,
+        None if args.home => {
+            let Some(base_dirs) = BaseDirs::new() else {
+                bail!("Unable to get home dir");
+            };
+            &base_dirs.home_dir().to_path_buf()
+        }
         None => &env::current_dir()?,
     };
     let project_dir = project_dir.canonicalize()?;
 
-    let config_file = config_dir.join(CONFIG_FILE_NAME);
-    let config_file = if config_file.is_file() {
-        Some(config_file)
+    let config_file = if config_path.is_file() {
+        Some(config_path.clone())
     } else {
         None
     };
@@ -52,15 +169,247 @@ fn main() -> Result<()> {
     let user_checklists_dir = config_dir.join("checklists");
     let user_templates_dir = config_dir.join("templates");
 
-    let mut settings = Settings::builder();
-    if let Some(config_file) = config_file {
-        settings = settings.config_layer(&config_file)?;
-    };
+    let settings = build_settings(config_file.as_deref(), &project_dir, args);
+    let diff_settings = DiffSettings::new().names(String::from("expected"), String::from("actual")); // TODO
 
-    let settings = settings.env_layer()?.arg_layer(args).build()?;
+    if let Some(Command::Doctor) = &command {
+        let findings = doctor(
+            config_file.as_deref(),
+            settings,
+            &project_dir,
+            &cache_dir,
+            |settings| {
+                Project::new(
+                    project_dir.clone(),
+                    settings,
+                    diff_settings,
+                    user_checklists_dir,
+                    user_templates_dir,
+                    cache_dir.clone(),
+                )
+            },
+        );
+        findings.iter().for_each(Finding::print);
+        let code = if findings.iter().any(Finding::is_error) {
+            1
+        } else {
+            exitcode::OK
+        };
+        std::process::exit(code);
+    }
+
+    let settings = settings?;
     debug!("{settings:?}");
 
-    let diff_settings = DiffSettings::new().names(String::from("expected"), String::from("actual")); // TODO
+    if let Some(Command::Config {
+        command: ConfigCommand::Show,
+    }) = &command
+    {
+        print!("{}", settings.effective()?);
+        return Ok(());
+    }
+
+    if let Some(Command::Init) = &command {
+        let Some(checklists_dir) = settings.checklist_dirs().first() else {
+            bail!("No checklist dirs configured to create");
+        };
+        let checklists_dir = project_dir.join(checklists_dir);
+        let written = init(&checklists_dir, &config_path, &user_checklists_dir)?;
+        for path in &written {
+            println!("Wrote {}", path.display());
+        }
+        if written.is_empty() {
+            println!("Already set up, nothing to write");
+        }
+        println!();
+        println!("Next steps:");
+        println!(
+            "{INDENT}- Add checks to the checklists in {}",
+            checklists_dir.display()
+        );
+        let bin = env!("CARGO_BIN_NAME");
+        println!("{INDENT}- Run '{bin} validate' to look for mistakes in them");
+        println!("{INDENT}- Run '{bin}' to check the project");
+        return Ok(());
+    }
+
+    if let Some(Command::Cache { command }) = &command {
+        let key = project_cache_key(&project_dir, &settings);
+        match command {
+            CacheCommand::Stats => {
+                let stats = cache::stats(&cache_dir)?;
+                println!(
+                    "{} in {}",
+                    ByteSize(stats.size),
+                    cache_dir.display().to_string().cyan()
+                );
+                for project in &stats.projects {
+                    let this = if project.key == key {
+                        " (this project)"
+                    } else {
+                        ""
+                    };
+                    println!(
+                        "> Project '{}'{this}: {} cached check(s), {} metric(s), {}, last used {}",
+                        project.key.cyan(),
+                        project.checks,
+                        project.metrics,
+                        ByteSize(project.size),
+                        humantime::format_rfc3339_seconds(project.last_used)
+                    );
+                }
+                println!(
+                    "> Downloaded files: {} file(s), {}",
+                    stats.external_files,
+                    ByteSize(stats.external_size)
+                );
+            }
+            CacheCommand::Path => println!("{}", cache_dir.display()),
+            CacheCommand::Clear { all: true } => {
+                fs::remove_dir_all(&cache_dir)?;
+                println!("Removed {}", cache_dir.display());
+            }
+            CacheCommand::Clear { all: false } => {
+                cache::clear_project(&cache_dir, &key)?;
+                println!("Removed the cache of '{key}'");
+            }
+            CacheCommand::Gc { older_than } => {
+                let removed = cache::gc(&cache_dir, **older_than)?;
+                for path in &removed {
+                    println!("Removed {}", path.display());
+                }
+                if removed.is_empty() {
+                    println!("Nothing unused for {older_than}");
+                }
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Report {
+        results,
+        format,
+        output: report_file,
+    }) = &command
+    {
+        let path = match results {
+            Some(path) => path.clone(),
+            None => last_run_path(&project_dir, &settings, &cache_dir),
+        };
+        if results.is_none() && !path.is_file() {
+            bail!("No stored results for this project yet, run the checks first");
+        }
+        let statuses = Statuses::load(&path)?;
+        let report = match format {
+            ReportFormat::Text if report_file.is_some() => {
+                bail!("Only HTML and Markdown reports can be written to a file")
+            }
+            ReportFormat::Text => {
+                print_statuses(&statuses, &settings, output, output_file.as_deref())?;
+                std::process::exit(statuses.exit_code(settings.exit_codes()));
+            }
+            ReportFormat::Html => report::html(&statuses),
+            ReportFormat::Markdown => report::markdown(&statuses),
+        };
+        match report_file {
+            Some(report_file) => {
+                fs::write(report_file, report)?;
+                println!("Wrote {}", report_file.display());
+            }
+            None => print!("{report}"),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Fmt { check, files }) = &command {
+        let paths = if files.is_empty() {
+            local_checklist_paths(&project_dir, None, &settings)?
+                .into_iter()
+                .filter(|path| !is_generated_checklist(path))
+                .collect()
+        } else {
+            files.clone()
+        };
+        let mut unformatted = 0;
+        for path in &paths {
+            if format_file(path, *check)? {
+                unformatted += 1;
+                if *check {
+                    println!("Would reformat {}", path.display());
+                } else {
+                    println!("Formatted {}", path.display());
+                }
+            }
+        }
+        if *check && unformatted > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Vendor) = &command {
+        if settings.offline() {
+            bail!("Unable to vendor remote files offline");
+        }
+        let written = vendor(&project_dir, &settings, &cache_dir)?;
+        for path in &written {
+            println!("Vendored {}", path.display());
+        }
+        if written.is_empty() {
+            println!("No remote checklists or templates to vendor");
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::NewCheck) = &command {
+        let Some(checklists_dir) = settings.checklist_dirs().first() else {
+            bail!("No checklist dirs configured to add a checklist to");
+        };
+        let new_checklist = project_dir.join(checklists_dir).join(NEW_CHECKLIST_NAME);
+        let checklists = local_checklist_paths(&project_dir, None, &settings)?
+            .into_iter()
+            .filter(|path| !is_generated_checklist(path))
+            .collect::<Vec<_>>();
+        let path = new_check(
+            &mut io::stdin().lock(),
+            &mut io::stdout(),
+            &checklists,
+            &new_checklist,
+        )?;
+        println!("Added the check to {}", path.display());
+        return Ok(());
+    }
+
+    // Loading the project fails on the first broken checklist, so check them all beforehand
+    if let Some(Command::Validate) = &command {
+        let user_checklists_dir = settings
+            .user_checklists()
+            .then_some(user_checklists_dir.as_path());
+        let paths = local_checklist_paths(&project_dir, user_checklists_dir, &settings)?;
+        let problems = lint_checklists(&paths);
+        if !problems.is_empty() {
+            print_problems(&problems);
+        }
+    }
+
+    let fingerprint = if settings.fast() && command.is_none() && !settings.update_baseline() {
+        fingerprint(
+            &project_dir,
+            &settings,
+            &[&user_checklists_dir, &user_templates_dir],
+        )?
+    } else {
+        None
+    };
+    if let Some(fingerprint) = &fingerprint
+        && let Some(statuses) =
+            unchanged_green_run(&project_dir, &settings, &cache_dir, fingerprint)?
+    {
+        debug!("Nothing changed since the last green run, reporting it");
+        print_statuses(&statuses, &settings, output, output_file.as_deref())?;
+        std::process::exit(statuses.exit_code(settings.exit_codes()));
+    }
+
     let mut project = Project::new(
         project_dir,
         settings,
@@ -69,9 +418,95 @@ fn main() -> Result<()> {
         user_templates_dir,
         cache_dir.to_path_buf(),
     )?;
+
+    if let Some(Command::Validate) = command {
+        print_problems(&validate(&project));
+    }
+
+    if let Some(Command::List { json }) = command {
+        let checklists = project.list()?;
+        if json {
+            println!("{}", serde_json::to_string_pretty(&checklists)?);
+        } else {
+            for (i, checklist) in checklists.iter().enumerate() {
+                if i > 0 {
+                    println!();
+                }
+                checklist.print();
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Templates {
+        command: TemplatesCommand::Validate,
+    }) = command
+    {
+        print_problems(&lint_templates(&project));
+    }
+
+    if let Some(Command::Generate { force, checklists }) = command {
+        let written = if checklists {
+            project.generate_checklists()?
+        } else {
+            project.generate(force)?
+        };
+        if written.is_empty() {
+            println!("Nothing to generate");
+        }
+        for path in written {
+            println!("Wrote {}", path.display());
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Ack { check, by, expires }) = command {
+        if !project.manual_check_ids().contains(&check.as_str()) {
+            bail!("No manual check with id '{check}'");
+        }
+        let path = project.root().join(ACKS_FILE_NAME);
+        let mut acks = Acks::load(&path)?;
+        let ack = Ack::new(check, by, chrono::Duration::from_std(*expires)?);
+        println!(
+            "Acknowledged '{}' until {}",
+            ack.check(),
+            ack.expires().format("%Y-%m-%d")
+        );
+        acks.insert(ack);
+        acks.save(&path)?;
+        return Ok(());
+    }
+
     let statuses = project.run_checks()?;
-    statuses.print();
+    let settings = project.settings();
+    if let Some(endpoint) = settings.otlp_endpoint() {
+        otlp::export(endpoint, project.name(), project.run_id(), project.trace())?;
+    }
+    if let Some(fingerprint) = &fingerprint {
+        let green = statuses.is_green().then_some(fingerprint.as_str());
+        save_fingerprint(project.root(), settings, &cache_dir, green)?;
+    }
+    if let Some(artifacts_dir) = settings.artifacts_dir() {
+        statuses.write_artifacts(artifacts_dir)?;
+    }
+
+    if let Some(Command::Diff { baseline, json }) = &command {
+        let diff = RunDiff::new(&Statuses::load(baseline)?, &statuses);
+        if *json {
+            println!("{}", diff.json()?);
+        } else {
+            diff.print();
+        }
+        let code = if diff.is_regression() {
+            settings.exit_codes().regression()
+        } else {
+            exitcode::OK
+        };
+        std::process::exit(code);
+    }
+
+    print_statuses(&statuses, settings, output, output_file.as_deref())?;
 
-    let code = statuses.exit_code();
+    let code = statuses.exit_code(settings.exit_codes());
     std::process::exit(code);
 }
@@ -0,0 +1,129 @@
+use crate::types::{Counts, Statuses};
+use anyhow::Result;
+use colored::Colorize;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+/// How many offenders to report
+const MAX_OFFENDERS: usize = 10;
+
+/// A check that failed in at least one of the merged runs
+#[derive(Debug, Serialize)]
+pub struct Offender {
+    checklist: String,
+    check: String,
+    /// Number of runs it failed in
+    failures: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectReport {
+    /// Number of runs merged for this project
+    runs: usize,
+    counts: Counts,
+}
+
+/// Results of many runs, possibly from different projects and machines, combined
+#[derive(Debug, Default, Serialize)]
+pub struct MergedReport {
+    runs: usize,
+    counts: Counts,
+    projects: BTreeMap<String, ProjectReport>,
+    worst_offenders: Vec<Offender>,
+}
+
+impl MergedReport {
+    pub fn from_paths(paths: &[PathBuf]) -> Result<Self> {
+        let mut report = Self::default();
+        let mut failures: HashMap<(String, String), usize> = HashMap::new();
+
+        for path in paths {
+            let statuses = Statuses::load(path)?;
+            // Fall back to the file name for results that don't record a project
+            let project = match statuses.project() {
+                Some(project) => project.to_string(),
+                None => path.display().to_string(),
+            };
+
+            let counts = statuses.counts();
+            report.runs += 1;
+            report.counts.merge(&counts);
+            let project = report
+                .projects
+                .entry(project)
+                .or_insert_with(|| ProjectReport {
+                    runs: 0,
+                    counts: Counts::default(),
+                });
+            project.runs += 1;
+            project.counts.merge(&counts);
+
+            for (checklist_path, name, status) in statuses.iter() {
+                if !status.is_failure() {
+                    continue;
+                }
+                let checklist = checklist_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                *failures.entry((checklist, name.to_string())).or_default() += 1;
+            }
+        }
+
+        let mut offenders = failures
+            .into_iter()
+            .map(|((checklist, check), failures)| Offender {
+                checklist,
+                check,
+                failures,
+            })
+            .collect::<Vec<_>>();
+        offenders.sort_by(|a, b| {
+            b.failures
+                .cmp(&a.failures)
+                .then_with(|| a.check.cmp(&b.check))
+        });
+        offenders.truncate(MAX_OFFENDERS);
+        report.worst_offenders = offenders;
+
+        Ok(report)
+    }
+
+    pub fn json(&self) -> Result<String> {
+        let json = serde_json::to_string_pretty(&self)?;
+        Ok(json)
+    }
+
+    pub fn print(&self) {
+        println!(
+            "{} run(s) of {} project(s): {}",
+            self.runs,
+            self.projects.len(),
+            self.counts
+        );
+
+        println!();
+        for (name, project) in &self.projects {
+            println!(
+                "> Project '{}' ({} run(s)): {}",
+                name.cyan(),
+                project.runs,
+                project.counts
+            );
+        }
+
+        if !self.worst_offenders.is_empty() {
+            println!();
+            println!("{}", "Most failed checks".bold());
+            for offender in &self.worst_offenders {
+                println!(
+                    "    {} failure(s): {} ({})",
+                    offender.failures.to_string().red(),
+                    offender.check,
+                    offender.checklist.dimmed()
+                );
+            }
+        }
+    }
+}
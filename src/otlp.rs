@@ -0,0 +1,106 @@
+//! Export a trace of a run to an OpenTelemetry collector
+use crate::THIS_CRATE_NAME;
+use anyhow::Result;
+use opentelemetry::trace::{Span, TraceContextExt, Tracer, TracerProvider};
+use opentelemetry::{Context, KeyValue};
+use opentelemetry_otlp::{SpanExporter, WithExportConfig};
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use opentelemetry_sdk::Resource;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use uuid::Uuid;
+
+/// Timing of a single check
+#[derive(Debug, Clone)]
+pub struct CheckSpan {
+    pub name: String,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub status: String,
+    pub cached: bool,
+}
+
+/// Timing of a checklist and the checks in it
+#[derive(Debug, Clone)]
+pub struct ChecklistSpan {
+    pub path: PathBuf,
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub checks: Vec<CheckSpan>,
+}
+
+/// Timings of a whole run
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub start: SystemTime,
+    pub end: SystemTime,
+    pub checklists: Vec<ChecklistSpan>,
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        let now = SystemTime::now();
+        Self {
+            start: now,
+            end: now,
+            checklists: Vec::new(),
+        }
+    }
+}
+
+/// Send the trace to an OTLP/HTTP traces endpoint (e.g. 'http://localhost:4318/v1/traces').
+/// The run is the root span, with a child span per checklist and a grandchild per check
+pub fn export(endpoint: &str, project: &str, run_id: Uuid, trace: &Trace) -> Result<()> {
+    let exporter = SpanExporter::builder()
+        .with_http()
+        .with_endpoint(endpoint)
+        .build()?;
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter)
+        .with_resource(
+            Resource::builder()
+                .with_service_name(THIS_CRATE_NAME)
+                .build(),
+        )
+        .build();
+    let tracer = provider.tracer(THIS_CRATE_NAME);
+
+    let run = tracer
+        .span_builder("run")
+        .with_start_time(trace.start)
+        .with_attributes(vec![
+            KeyValue::new("checklints.project", project.to_string()),
+            KeyValue::new("checklints.run_id", run_id.to_string()),
+        ])
+        .start(&tracer);
+    let run_cx = Context::current_with_span(run);
+
+    for checklist in &trace.checklists {
+        let path = checklist.path.display().to_string();
+        let span = tracer
+            .span_builder(format!("checklist {path}"))
+            .with_start_time(checklist.start)
+            .with_attributes(vec![KeyValue::new("checklints.checklist", path.clone())])
+            .start_with_context(&tracer, &run_cx);
+        let checklist_cx = run_cx.with_span(span);
+
+        for check in &checklist.checks {
+            let mut span = tracer
+                .span_builder(check.name.clone())
+                .with_start_time(check.start)
+                .with_attributes(vec![
+                    KeyValue::new("checklints.checklist", path.clone()),
+                    KeyValue::new("checklints.status", check.status.clone()),
+                    KeyValue::new("checklints.cached", check.cached),
+                ])
+                .start_with_context(&tracer, &checklist_cx);
+            span.end_with_timestamp(check.end);
+        }
+
+        checklist_cx.span().end_with_timestamp(checklist.end);
+    }
+
+    run_cx.span().end_with_timestamp(trace.end);
+    provider.shutdown()?;
+    Ok(())
+}
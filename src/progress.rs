@@ -0,0 +1,67 @@
+//! Progress of a run while its checks run, on stderr when it's a terminal
+use crate::types::{Counts, Status};
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+use std::io::{self, IsTerminal};
+use std::time::Duration;
+
+const TEMPLATE: &str = "{spinner} [{pos}/{len}] {wide_msg}";
+
+/// How often the spinner ticks while a check runs
+const TICK: Duration = Duration::from_millis(100);
+
+/// Shows which check of how many is running, and the counts so far. Checks that fail, warn, or
+/// need review are printed above it as they finish, before the full report
+pub struct Progress {
+    bar: ProgressBar,
+    counts: Counts,
+}
+
+impl Progress {
+    /// Progress through `total` checks, hidden unless `show` and stderr is a terminal
+    pub fn new(total: usize, show: bool) -> Self {
+        let bar = if show && io::stderr().is_terminal() {
+            let bar =
+                ProgressBar::with_draw_target(Some(total as u64), ProgressDrawTarget::stderr());
+            if let Ok(style) = ProgressStyle::with_template(TEMPLATE) {
+                bar.set_style(style);
+            }
+            bar.enable_steady_tick(TICK);
+            bar
+        } else {
+            ProgressBar::hidden()
+        };
+        Self {
+            bar,
+            counts: Counts::default(),
+        }
+    }
+
+    /// `check` of the checklist `checklist` started running
+    pub fn start(&self, checklist: &str, check: &str) {
+        self.bar
+            .set_message(format!("{} | {checklist}, {check}", self.counts));
+    }
+
+    /// `check` of the checklist `checklist` finished with `status`
+    pub fn finish(&mut self, checklist: &str, check: &str, status: &Status) {
+        self.counts.add(status);
+        self.bar.inc(1);
+        let label = if status.is_failure() {
+            "FAIL".red()
+        } else if status.needs_review() {
+            "NEEDS REVIEW".blue()
+        } else if status.is_warning() {
+            "WARN".yellow()
+        } else {
+            return;
+        };
+        self.bar
+            .println(format!("[{}] {checklist}: {check}", label.bold()));
+    }
+
+    /// Remove the progress bar, leaving what was printed above it
+    pub fn clear(&self) {
+        self.bar.finish_and_clear();
+    }
+}
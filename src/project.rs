@@ -1,87 +1,560 @@
-use crate::cache::Ttype;
+use crate::archive;
+use crate::baseline::{self, Baseline};
+use crate::command::{run_command, run_command_line};
+use crate::host;
+use crate::list::{ListedCheck, ListedChecklist, Source};
+use crate::otlp::{CheckSpan, ChecklistSpan, Trace};
+use crate::progress::Progress;
 use crate::settings::Settings;
-use crate::types::CheckTrait;
+use crate::template;
+use crate::types::{Check, CheckTrait, CheckType, Fix, RemoteFile, Requirement, Status};
 use anyhow::bail;
 use anyhow::Result;
+use clap::ValueEnum;
 use different::DiffSettings;
-use log::debug;
-use minijinja::Environment;
+use log::{debug, info};
+use minijinja::{context, Environment, UndefinedBehavior, Value};
+use reqwest::blocking::Client;
+use reqwest::header::ETAG;
+use std::ffi::OsString;
 use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::time::{Duration, SystemTime};
 use std::{
     collections::HashMap,
     path::{Path, PathBuf},
 };
+use uuid::Uuid;
 
-use crate::cache::Cache;
+use crate::cache::{cache_key, hash_file, migrate_legacy_cache, Cache, RUNS_DIR};
 use crate::types::Checklist;
 use crate::types::Statuses;
+use crate::vendor::Manifest;
+use crate::walk;
+use crate::BASELINE_FILE_NAME;
 
-fn checklists_in_dir(path: &Path) -> Result<Vec<Checklist>> {
-    let mut checklists = Vec::new();
+/// Files changed since `base_ref`, including uncommitted and untracked ones
+fn changed_files(base_ref: &str) -> Result<Vec<PathBuf>> {
+    let diff = run_command(
+        &"git",
+        &["diff", "--name-only", "--relative", base_ref],
+        None,
+    )?;
+    if diff.code() != 0 {
+        let stderr = diff.stderr().map(|s| s.as_str()).unwrap_or_default();
+        bail!("Unable to list files changed since '{base_ref}': {stderr}");
+    }
+    let untracked = run_command(
+        &"git",
+        &["ls-files", "--others", "--exclude-standard"],
+        None,
+    )?;
+
+    let changed = [diff.stdout(), untracked.stdout()]
+        .into_iter()
+        .flatten()
+        .flat_map(|stdout| stdout.lines())
+        .map(PathBuf::from)
+        .collect();
+    Ok(changed)
+}
+
+/// Ecosystem name and the files, any of which mark a project as part of it
+const ECOSYSTEMS: &[(&str, &[&str])] = &[
+    ("rust", &["Cargo.toml"]),
+    ("node", &["package.json"]),
+    (
+        "python",
+        &[
+            "pyproject.toml",
+            "setup.py",
+            "setup.cfg",
+            "requirements.txt",
+        ],
+    ),
+];
+
+/// Ecosystems the project in `dir` belongs to, by marker files
+fn detect_ecosystems(dir: &Path) -> Vec<&'static str> {
+    ECOSYSTEMS
+        .iter()
+        .filter(|(_, markers)| markers.iter().any(|marker| dir.join(marker).is_file()))
+        .map(|(name, _)| *name)
+        .collect()
+}
+
+/// Facts every project has: `project.name`, `project.dir`, `project.ecosystems` (comma separated),
+/// and `project.<ecosystem>` ("true" or "false") for each known ecosystem
+fn builtin_facts(dir: &Path, name: &str, ecosystems: &[&str]) -> HashMap<String, String> {
+    let mut facts = HashMap::from([
+        (String::from("project.name"), name.to_string()),
+        (String::from("project.dir"), dir.display().to_string()),
+        (String::from("project.ecosystems"), ecosystems.join(",")),
+    ]);
+    for (ecosystem, _) in ECOSYSTEMS {
+        facts.insert(
+            format!("project.{ecosystem}"),
+            ecosystems.contains(ecosystem).to_string(),
+        );
+    }
+    facts
+}
+
+/// Add the 'host.*' facts, unless vars replace them, if any of the checklists uses them. Taking
+/// the host's inventory runs commands, so it's left out otherwise
+fn add_host_facts(
+    checklists: &[Checklist],
+    settings: &Settings,
+    facts: &mut HashMap<String, String>,
+) {
+    let uses_host = checklists.iter().any(|checklist| {
+        fs::read_to_string(checklist.path()).is_ok_and(|contents| contents.contains("host."))
+    });
+    if !uses_host {
+        return;
+    }
+    let host = host::inventory();
+    debug!("Host inventory {host:?}");
+    for (key, value) in host {
+        let key = format!("host.{key}");
+        if !settings.vars().contains_key(&key) {
+            facts.insert(key, value.clone());
+        }
+    }
+}
+
+/// Name of the project in `dir`, unless the settings override it
+fn project_name(dir: &Path, settings: &Settings) -> String {
+    match settings.name() {
+        Some(name) => name.to_string(),
+        None => dir.file_stem().unwrap().to_str().unwrap().to_string(),
+    }
+}
+
+/// Options of this run that nested runs (on subprojects) inherit
+fn nested_run_args(
+    settings: &Settings,
+    cache_dir: &Path,
+    config_dir: Option<&Path>,
+) -> Vec<OsString> {
+    let mut args = vec![OsString::from("--cache-dir"), cache_dir.into()];
+    if let Some(config_dir) = config_dir {
+        args.extend([OsString::from("--config-dir"), config_dir.into()]);
+    }
+    if settings.offline() {
+        args.push("--offline".into());
+    }
+    if settings.no_read_cache() {
+        args.push("--no-read-cache".into());
+    }
+    if settings.no_write_cache() {
+        args.push("--no-write-cache".into());
+    }
+    if let Some(fail_on) = settings.fail_on().to_possible_value() {
+        args.extend(["--fail-on".into(), fail_on.get_name().into()]);
+    }
+    args
+}
+
+/// Name of the project's cache, under the cache dir
+pub fn project_cache_key(dir: &Path, settings: &Settings) -> String {
+    cache_key(dir, &project_name(dir, settings))
+}
+
+/// Where the results of the project's most recent run are kept
+pub fn last_run_path(dir: &Path, settings: &Settings, cache_dir: &Path) -> PathBuf {
+    let key = project_cache_key(dir, settings);
+    cache_dir.join(RUNS_DIR).join(format!("{key}.json"))
+}
+
+fn fingerprint_path(dir: &Path, settings: &Settings, cache_dir: &Path) -> PathBuf {
+    last_run_path(dir, settings, cache_dir).with_extension("fingerprint")
+}
+
+/// Save the results of a run at `last_run`. The fingerprint beside it was of the run before, so
+/// it's removed, to be saved again by a fast run that's green
+fn write_last_run(last_run: &Path, json: &str) -> Result<()> {
+    if let Some(dir) = last_run.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    debug!("Saving results to {}", last_run.display());
+    fs::write(last_run, json)?;
+    let fingerprint = last_run.with_extension("fingerprint");
+    if fingerprint.is_file() {
+        fs::remove_file(fingerprint)?;
+    }
+    Ok(())
+}
+
+/// Output of a git command run in `dir`, None when it fails (e.g. not a git repo)
+fn git_output(dir: &Path, args: &[&str]) -> Result<Option<String>> {
+    let dir = dir.to_string_lossy();
+    let args = [&["-C", dir.as_ref()], args].concat();
+    let output = run_command(&"git", &args, None)?;
+    if output.code() != 0 {
+        return Ok(None);
+    }
+    Ok(Some(output.stdout().cloned().unwrap_or_default()))
+}
+
+/// Every file under `path`, sorted
+fn files_in(path: &Path) -> Result<Vec<PathBuf>> {
+    if path.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    if path.is_dir() {
+        for entry in fs::read_dir(path)? {
+            files.append(&mut files_in(&entry?.path())?);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// How long fast mode waits for a remote to say whether it changed
+const REMOTE_VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// What identifies the current version of a remote file: its ETag, or else its contents. None
+/// when it can't be reached
+fn remote_version(client: &Client, url: &str) -> Option<Vec<u8>> {
+    let response = client.head(url).send().ok()?.error_for_status().ok()?;
+    if let Some(etag) = response.headers().get(ETAG) {
+        return Some(etag.as_bytes().to_vec());
+    }
+    let response = client.get(url).send().ok()?.error_for_status().ok()?;
+    Some(response.bytes().ok()?.to_vec())
+}
+
+/// Hash of what a run's result depends on that is cheap to look at: git's HEAD and uncommitted
+/// changes, the checklists and templates outside the repo, the versions of remote ones that aren't
+/// pinned by a hash or vendored, and the settings. None when the project isn't in a git repo, or a
+/// remote can't be reached, so the checks run as usual
+pub fn fingerprint(dir: &Path, settings: &Settings, user_dirs: &[&Path]) -> Result<Option<String>> {
+    let Some(head) = git_output(dir, &["rev-parse", "HEAD"])? else {
+        return Ok(None);
+    };
+    let Some(diff) = git_output(dir, &["diff", "HEAD"])? else {
+        return Ok(None);
+    };
+    let Some(untracked) = git_output(dir, &["ls-files", "--others", "--exclude-standard"])? else {
+        return Ok(None);
+    };
+
+    let mut hasher = blake3::Hasher::new();
+    for part in [&head, &diff, &untracked, &format!("{settings:?}")] {
+        hasher.update(part.as_bytes());
+    }
+    let mut files = untracked
+        .lines()
+        .map(|path| dir.join(path))
+        .collect::<Vec<_>>();
+    for user_dir in user_dirs {
+        files.append(&mut files_in(user_dir)?);
+    }
+    for path in files.iter().filter(|path| path.is_file()) {
+        hasher.update(path.to_string_lossy().as_bytes());
+        hasher.update(hash_file(path)?.as_bytes());
+    }
+
+    // Pinned remotes are covered by the settings, and vendored ones by the repo. Offline, unpinned
+    // ones aren't used at all
+    if !settings.offline() {
+        let vendored = Manifest::load(dir)?;
+        let client = Client::builder().timeout(REMOTE_VERSION_TIMEOUT).build()?;
+        let remotes = settings
+            .required_checklists()
+            .iter()
+            .chain(settings.external_checklists())
+            .chain(settings.external_templates());
+        for remote in remotes {
+            let is_vendored = match &vendored {
+                Some(vendored) => vendored.get(remote)?.is_some(),
+                None => false,
+            };
+            if remote.hash().is_some() || is_vendored {
+                continue;
+            }
+            let url = remote.url().to_string();
+            let Some(version) = remote_version(&client, &url) else {
+                debug!("Unable to reach {url}, can't tell whether it changed");
+                return Ok(None);
+            };
+            hasher.update(&version);
+        }
+    }
+    Ok(Some(hasher.finalize().to_hex().to_string()))
+}
+
+/// Results of the last run, if it was green and nothing has changed since
+pub fn unchanged_green_run(
+    dir: &Path,
+    settings: &Settings,
+    cache_dir: &Path,
+    fingerprint: &str,
+) -> Result<Option<Statuses>> {
+    let path = fingerprint_path(dir, settings, cache_dir);
+    let last_run = last_run_path(dir, settings, cache_dir);
+    if !path.is_file() || !last_run.is_file() || fs::read_to_string(&path)? != fingerprint {
+        return Ok(None);
+    }
+    let statuses = Statuses::load(&last_run)?;
+    Ok(statuses.is_green().then_some(statuses))
+}
+
+/// Remember the fingerprint of a green run, or forget the last one
+pub fn save_fingerprint(
+    dir: &Path,
+    settings: &Settings,
+    cache_dir: &Path,
+    fingerprint: Option<&str>,
+) -> Result<()> {
+    let path = fingerprint_path(dir, settings, cache_dir);
+    match fingerprint {
+        Some(fingerprint) => fs::write(path, fingerprint)?,
+        None if path.is_file() => fs::remove_file(path)?,
+        None => {}
+    }
+    Ok(())
+}
+
+/// Ask on the terminal before doing something. Never confirmed when not interactive
+fn confirm(question: &str) -> Result<bool> {
+    if !io::stdin().is_terminal() {
+        return Ok(false);
+    }
+    print!("{question} [y/N] ");
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Offer to install the missing commands a check requires
+fn install_missing(requirements: &[Requirement]) -> Result<()> {
+    for requirement in requirements {
+        let Some((command, install)) = requirement.missing_install() else {
+            continue;
+        };
+        if !confirm(&format!("'{command}' is missing, run '{install}'?"))? {
+            continue;
+        }
+        let output = run_command_line(install, None)?;
+        if output.code() != 0 {
+            let stderr = output.stderr().map(|s| s.as_str()).unwrap_or_default();
+            bail!("'{install}' failed: {stderr}");
+        }
+    }
+    Ok(())
+}
+
+/// Checklists in the dir at `path`, sorted so they run in the same order every time
+fn checklist_paths_in_dir(path: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
     for entry in fs::read_dir(path)? {
-        let entry = entry?;
-        let path = entry.path();
+        let path = entry?.path();
         if path.extension().is_some_and(|ext| ext == "toml") {
-            debug!("Reading '{}'", path.display());
-            let checklist = Checklist::from_path(path)?;
-            checklists.push(checklist);
+            paths.push(path);
         }
     }
-    Ok(checklists)
+    paths.sort();
+    Ok(paths)
 }
 
+/// Why checks that need the network are skipped when offline
+const NEEDS_NETWORK: &str = "Needs the network, running offline";
+
+/// Subdir of a checklist dir that checklists generated from templates are written to
+const GENERATED_DIR: &str = "generated";
+
+/// Checklist templates live in the checklist dirs, alongside the checklists
+const CHECKLIST_TEMPLATE_EXTENSION: &str = ".toml.j2";
+
 // TODO: some sort of 'checklist ignore' directive for non-checklist toml files
-fn discover_project_checklists(project_dir: &Path) -> Result<Vec<Checklist>> {
-    let mut checklists = Vec::new();
+fn project_checklist_paths(project_dir: &Path, settings: &Settings) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
 
-    for name in [".checklists", "checklists", "checks", ".checks"] {
+    for name in settings.checklist_dirs() {
         let path = project_dir.join(name);
         if path.is_dir() {
-            checklists.append(&mut checklists_in_dir(&path)?);
+            paths.append(&mut checklist_paths_in_dir(&path)?);
+        }
+        let generated = path.join(GENERATED_DIR);
+        if generated.is_dir() {
+            paths.append(&mut checklist_paths_in_dir(&generated)?);
         }
     }
 
-    for name in [".checklist.toml", "checklist.toml"] {
+    for name in settings.checklist_files() {
         let path = project_dir.join(name);
         if path.is_file() {
-            let checklist = Checklist::from_path(path)?;
-            checklists.push(checklist);
+            paths.push(path);
         }
     }
 
-    Ok(checklists)
+    Ok(paths)
 }
 
-fn discover_remote_checklists(settings: &Settings, cache: &mut Cache) -> Result<Vec<Checklist>> {
-    let mut checklists = Vec::new();
+/// Render every checklist template in the project's checklist dirs with the facts, writing
+/// '<dir>/generated/<name>.toml'. Returns the checklists written
+fn generate_checklists(
+    project_dir: &Path,
+    settings: &Settings,
+    template_env: &Environment,
+    facts: &HashMap<String, String>,
+) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for name in settings.checklist_dirs() {
+        let dir = project_dir.join(name);
+        if !dir.is_dir() {
+            continue;
+        }
 
-    for external in settings.external_checklists() {
-        let url = external.url();
-        let name = url.name();
-        let hash = external.hash();
-        let path = cache.get_or_dl_external_file(
-            &name,
-            url.to_string(),
-            hash.cloned(),
-            Ttype::Checklist,
-        )?;
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let Some(stem) = file_name.strip_suffix(CHECKLIST_TEMPLATE_EXTENSION) else {
+                continue;
+            };
 
-        let checklist = Checklist::from_path(path)?;
-        checklists.push(checklist);
+            debug!("Generating checklist from '{}'", path.display());
+            let source = fs::read_to_string(&path)?;
+            let contents =
+                template_env.render_named_str(&path.display().to_string(), &source, facts)?;
+            let dest = dir.join(GENERATED_DIR).join(format!("{stem}.toml"));
+            fs::create_dir_all(dir.join(GENERATED_DIR))?;
+            fs::write(&dest, contents)?;
+            written.push(dest);
+        }
     }
+    written.sort();
+    Ok(written)
+}
 
-    Ok(checklists)
+/// Work out a checklist's facts and register its templates
+fn load_checklist(
+    checklist: &Checklist,
+    settings: &Settings,
+    diff_settings: &DiffSettings,
+    template_env: &mut Environment<'static>,
+    template_errors: &mut Vec<minijinja::Error>,
+    facts: &mut HashMap<String, String>,
+) -> Result<()> {
+    let name = checklist.name()?;
+    let path = checklist.path();
+    for fact in checklist.facts() {
+        // Vars take precedence over every fact, no need to work out ones they replace
+        let keys = fact.keys();
+        if !keys.is_empty() && keys.iter().all(|k| settings.vars().contains_key(k)) {
+            debug!("Fact(s) {keys:?} in '{name}' set by vars");
+            continue;
+        }
+
+        if settings.offline() && fact.needs_network() {
+            bail!(
+                "Fact(s) {:?} in '{name}' need the network, which is disabled by --offline",
+                fact.keys()
+            );
+        }
+
+        for requirement in fact.requirements() {
+            let status = requirement.do_check(diff_settings, template_env, path, facts)?;
+
+            if status.is_failure() {
+                bail!("{status}");
+            }
+        }
+
+        for (k, v) in fact.values(path, facts)? {
+            if settings.vars().contains_key(&k) {
+                continue;
+            }
+            debug!("Found fact '{k}'='{v}' for checklist '{name}'");
+            facts.insert(k, v);
+        }
+    }
+
+    for template in &checklist.templates() {
+        if !template.exists() {
+            bail!(
+                "Template '{}' used by '{name}' does not exist",
+                template.display()
+            );
+        }
+        add_template(template_env, template, template_errors)?;
+    }
+    Ok(())
 }
 
-fn discover_checklists(
-    project_dir: &Path,
-    user_checklists_dir: Option<PathBuf>,
+/// Local path of a remote file: its vendored copy, or downloading it if needed. None when offline
+/// and it was never downloaded before
+fn fetch_remote(
+    remote: &RemoteFile,
     settings: &Settings,
     cache: &mut Cache,
+    vendored: Option<&Manifest>,
+) -> Result<Option<PathBuf>> {
+    if let Some(vendored) = vendored
+        && let Some(path) = vendored.get(remote)?
+    {
+        return Ok(Some(path));
+    }
+
+    let url = remote.url();
+    let hash = remote.hash();
+    if settings.offline() {
+        let path = cache.cached_external_file(hash);
+        if path.is_none() {
+            info!("Offline, skipping uncached remote file {url}");
+        }
+        return Ok(path);
+    }
+
+    let path = cache.get_or_dl_external_file(&url.name(), url.to_string(), hash.cloned())?;
+    Ok(Some(path))
+}
+
+fn discover_remote_checklists(
+    settings: &Settings,
+    cache: &mut Cache,
+    vendored: Option<&Manifest>,
 ) -> Result<Vec<Checklist>> {
     let mut checklists = Vec::new();
 
-    checklists.append(&mut discover_remote_checklists(settings, cache)?);
+    // Required checklists come first, and run regardless of project config
+    for required in settings.required_checklists() {
+        let Some(path) = fetch_remote(required, settings, cache, vendored)? else {
+            bail!(
+                "Required checklist {} is not cached, unable to run offline",
+                required.url()
+            );
+        };
+        checklists.push(Checklist::from_path(path)?);
+    }
+
+    for external in settings.external_checklists() {
+        if let Some(path) = fetch_remote(external, settings, cache, vendored)? {
+            checklists.push(Checklist::from_path(path)?);
+        }
+    }
+
+    Ok(checklists)
+}
+
+/// The checklist was generated from a checklist template, rather than written by hand
+pub fn is_generated_checklist(path: &Path) -> bool {
+    path.parent()
+        .and_then(|dir| dir.file_name())
+        .is_some_and(|name| name == GENERATED_DIR)
+}
+
+/// Paths of the user's and the project's checklists, everything but remote checklists
+pub fn local_checklist_paths(
+    project_dir: &Path,
+    user_checklists_dir: Option<&Path>,
+    settings: &Settings,
+) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
 
     if let Some(user_checklists_dir) = user_checklists_dir {
         if !user_checklists_dir.is_dir() {
@@ -90,26 +563,54 @@ fn discover_checklists(
                 user_checklists_dir.display()
             );
         }
-        checklists.append(&mut checklists_in_dir(&user_checklists_dir)?);
+        paths.append(&mut checklist_paths_in_dir(user_checklists_dir)?);
     }
 
-    checklists.append(&mut discover_project_checklists(project_dir)?);
+    // A home dir isn't a project, its dotfiles are audited by the user's checklists
+    if !settings.home() {
+        paths.append(&mut project_checklist_paths(project_dir, settings)?);
+    }
+
+    Ok(paths)
+}
+
+fn discover_checklists(
+    project_dir: &Path,
+    user_checklists_dir: Option<&Path>,
+    settings: &Settings,
+    cache: &mut Cache,
+    vendored: Option<&Manifest>,
+) -> Result<Vec<Checklist>> {
+    let mut checklists = discover_remote_checklists(settings, cache, vendored)?;
+
+    for path in local_checklist_paths(project_dir, user_checklists_dir, settings)? {
+        debug!("Reading '{}'", path.display());
+        checklists.push(Checklist::from_path(path)?);
+    }
 
     Ok(checklists)
 }
 
-fn add_template(template_env: &mut Environment, path: &Path) -> Result<()> {
+/// Register a template file, or dir of them. Templates with syntax errors are skipped and their
+/// errors collected into `errors`
+fn add_template(
+    template_env: &mut Environment,
+    path: &Path,
+    errors: &mut Vec<minijinja::Error>,
+) -> Result<()> {
     if path.is_dir() {
         for entry in fs::read_dir(path)? {
             let entry = entry?;
             let path = entry.path();
-            add_template(template_env, &path)?;
+            add_template(template_env, &path, errors)?;
         }
     } else if path.is_file() {
         let name = path.display().to_string();
         debug!("adding template {}", path.display());
         let contents = fs::read_to_string(path)?;
-        template_env.add_template_owned(name, contents)?;
+        if let Err(e) = template_env.add_template_owned(name, contents) {
+            errors.push(e);
+        }
     } else {
         bail!(
             "Unsupported file type: {}, expected dir or regular file",
@@ -128,7 +629,15 @@ pub struct Project<'a> {
     settings: Settings,
     diff_settings: DiffSettings,
     template_env: Environment<'a>,
+    template_errors: Vec<minijinja::Error>,
     facts: HashMap<String, String>,
+    run_id: Uuid,
+    name: String,
+    trace: Trace,
+    last_run: PathBuf,
+    user_checklists_dir: Option<PathBuf>,
+    /// Failures known to the project, which don't fail the run
+    baseline: Baseline,
 }
 
 // TODO: need to refactor the whole discover templates and checklists thing. Its grown to be spaghetti
@@ -142,15 +651,27 @@ impl Project<'_> {
         user_templates_dir: PathBuf,
         cache_dir: PathBuf,
     ) -> Result<Self> {
-        let project_name = dir.file_stem().unwrap().to_str().unwrap();
+        let project_name = project_name(&dir, &settings);
+        let last_run = last_run_path(&dir, &settings, &cache_dir);
+        let baseline = Baseline::load(&dir.join(BASELINE_FILE_NAME))?;
+        // The user checklists are in the config dir
+        let run_args = nested_run_args(&settings, &cache_dir, user_checklists_dir.parent());
+
+        let run_id = Uuid::new_v4();
+        info!("Starting run {run_id}");
 
         let mut template_env = Environment::new();
+        template::add_builtins(&mut template_env);
+        if settings.strict_undefined() {
+            template_env.set_undefined_behavior(UndefinedBehavior::Strict);
+        }
+        let mut template_errors = Vec::new();
 
         // TODO: cache should hash the templates, because if those have changed cache is no longer valid
         let user_checklists_dir = if settings.user_checklists() {
             // Register user templates
             if user_templates_dir.is_dir() {
-                add_template(&mut template_env, &user_templates_dir)?;
+                add_template(&mut template_env, &user_templates_dir, &mut template_errors)?;
             }
 
             Some(user_checklists_dir)
@@ -158,71 +679,94 @@ impl Project<'_> {
             None
         };
 
-        let mut facts = HashMap::new();
-
-        let mut cache = match Cache::load(cache_dir.clone(), project_name.to_string())? {
-            Some(cache) => {
-                let cache = if *cache.facts() == facts {
-                    cache
-                } else {
-                    // Facts are out of date, remove old cache entry and create new one
-                    let cache_dir = cache.cache_dir();
-                    fs::remove_dir_all(cache_dir)?; // TODO: make a method to remove the cache for DRY
-                    Cache::new(
-                        cache_dir.to_path_buf(),
-                        project_name.to_string(),
-                        facts.clone(),
-                    )?
-                };
+        let ecosystems = detect_ecosystems(&dir);
+        debug!("Detected ecosystems {ecosystems:?}");
+        let mut facts = builtin_facts(&dir, &project_name, &ecosystems);
+        template_env.add_global("host", Value::from_object(host::LazyInventory));
+        for (key, value) in settings.vars() {
+            debug!("Using var '{key}'='{value}'");
+            facts.insert(key.clone(), value.clone());
+        }
+        // Dotted fact names aren't reachable from templates, so expose them as an object too
+        template_env.add_global(
+            "project",
+            context! {
+                name => project_name,
+                dir => dir.display().to_string(),
+                ecosystems => ecosystems,
+            },
+        );
 
-                if settings.clear_cache() {
-                    fs::remove_dir_all(&cache_dir)?;
-                    Cache::new(cache_dir.clone(), project_name.to_string(), facts.clone())?
-                } else {
-                    cache
-                }
+        let cache_key = cache_key(&dir, &project_name);
+        migrate_legacy_cache(&cache_dir, &project_name, &cache_key)?;
+        let mut cache = match Cache::load(cache_dir.clone(), cache_key.clone())? {
+            Some(cache) if settings.clear_cache() || *cache.facts() != facts => {
+                // Facts are out of date (or asked to start fresh), remove old cache entry and create new one
+                cache.remove()?;
+                Cache::new(cache_dir, cache_key, facts.clone())?
             }
-            None => Cache::new(cache_dir.clone(), project_name.to_string(), facts.clone())?,
+            Some(cache) => cache,
+            None => Cache::new(cache_dir, cache_key, facts.clone())?,
         };
 
+        let vendored = Manifest::load(&dir)?;
         for template in settings.external_templates() {
-            let url = template.url();
-            let name = url.name();
-            let hash = template.hash();
-            let path = cache.get_or_dl_external_file(
-                &name,
-                url.to_string(),
-                hash.cloned(),
-                Ttype::Template,
-            )?;
+            let Some(path) = fetch_remote(template, &settings, &mut cache, vendored.as_ref())?
+            else {
+                continue;
+            };
             let path = path.canonicalize()?;
-            add_template(&mut template_env, &path)?;
+            let path = if archive::is_archive(&path) {
+                archive::extract(&path)?
+            } else {
+                path
+            };
+            add_template(&mut template_env, &path, &mut template_errors)?;
         }
 
-        let checklists = discover_checklists(&dir, user_checklists_dir, &settings, &mut cache)?;
+        let mut checklists = discover_checklists(
+            &dir,
+            user_checklists_dir.as_deref(),
+            &settings,
+            &mut cache,
+            vendored.as_ref(),
+        )?;
+        add_host_facts(&checklists, &settings, &mut facts);
         for checklist in &checklists {
-            let name = checklist.name()?;
-            let path = checklist.path();
-            for fact in checklist.facts() {
-                for requirement in fact.requirements() {
-                    let status =
-                        requirement.do_check(&diff_settings, &template_env, path, &facts)?;
-
-                    if status.is_failure() {
-                        bail!("{status}");
-                    }
-                }
-
-                let k = fact.key();
-                let v = fact.value(&facts)?;
-                debug!("Found fact '{k}'='{v}' for checklist '{name}'");
-                facts.insert(k, v);
-            }
+            load_checklist(
+                checklist,
+                &settings,
+                &diff_settings,
+                &mut template_env,
+                &mut template_errors,
+                &mut facts,
+            )?;
+        }
 
-            for template in &checklist.templates() {
-                add_template(&mut template_env, template)?;
+        if settings.sync_checklists() {
+            let generated = generate_checklists(&dir, &settings, &template_env, &facts)?;
+            // Replace what discovery read from the last time they were generated
+            checklists.retain(|checklist| !generated.iter().any(|path| path == checklist.path()));
+            let generated = generated
+                .into_iter()
+                .map(Checklist::from_path)
+                .collect::<Result<Vec<_>>>()?;
+            add_host_facts(&generated, &settings, &mut facts);
+            for checklist in generated {
+                load_checklist(
+                    &checklist,
+                    &settings,
+                    &diff_settings,
+                    &mut template_env,
+                    &mut template_errors,
+                    &mut facts,
+                )?;
+                checklists.push(checklist);
             }
         }
+        for checklist in &mut checklists {
+            checklist.set_project(&dir, &run_args);
+        }
 
         Ok(Self {
             root: dir,
@@ -231,34 +775,295 @@ impl Project<'_> {
             settings,
             diff_settings,
             template_env,
+            template_errors,
             facts,
+            run_id,
+            name: project_name,
+            trace: Trace::default(),
+            last_run,
+            user_checklists_dir,
+            baseline,
         })
     }
 
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn run_id(&self) -> Uuid {
+        self.run_id
+    }
+
+    /// Timings of the last run of the checks
+    pub fn trace(&self) -> &Trace {
+        &self.trace
+    }
+
+    pub fn checklists(&self) -> &[Checklist] {
+        &self.checklists
+    }
+
+    pub fn template_env(&self) -> &Environment<'_> {
+        &self.template_env
+    }
+
+    /// Ids of every manual check
+    pub fn manual_check_ids(&self) -> Vec<&str> {
+        self.checklists
+            .iter()
+            .flat_map(|checklist| checklist.checks())
+            .filter_map(|check| match check.ttype() {
+                CheckType::Manual(m) => m.id(),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Render the template of every single-file check to its file, skipping files that already
+    /// exist unless `overwrite`. Returns the files written
+    pub fn generate(&self, overwrite: bool) -> Result<Vec<PathBuf>> {
+        if let Some(e) = self.template_errors.first() {
+            bail!("{e}");
+        }
+
+        let mut written = Vec::new();
+        for checklist in &self.checklists {
+            for check in checklist.checks() {
+                let CheckType::File(f) = check.ttype() else {
+                    continue;
+                };
+                let Some(template) = f.template() else {
+                    continue;
+                };
+                if f.is_glob() {
+                    continue;
+                }
+
+                let path = walk::expand_tilde(&f.path().to_string_lossy());
+                if f.region().is_some() && !path.exists() {
+                    debug!(
+                        "Not generating {}, only a region of it is managed",
+                        path.display()
+                    );
+                    continue;
+                }
+                if written.contains(&path) || (path.exists() && !overwrite) {
+                    debug!("Not generating {}, it already exists", path.display());
+                    continue;
+                }
+                Fix::Template(template.to_path_buf()).apply(
+                    check.ttype(),
+                    &self.template_env,
+                    checklist.path(),
+                    &self.facts,
+                )?;
+                written.push(path);
+            }
+        }
+        Ok(written)
+    }
+
+    /// Render the project's checklist templates into checklists. Returns the checklists written
+    pub fn generate_checklists(&self) -> Result<Vec<PathBuf>> {
+        generate_checklists(&self.root, &self.settings, &self.template_env, &self.facts)
+    }
+
+    /// Facts worked out for the project, including built-in ones and vars
+    pub fn facts(&self) -> &HashMap<String, String> {
+        &self.facts
+    }
+
+    /// Syntax errors of templates that could not be registered
+    pub fn template_errors(&self) -> &[minijinja::Error] {
+        &self.template_errors
+    }
+
+    /// Why a check of the checklist at `checklist_path` is left out of the run entirely, with only
+    /// the files in `changed` checked
+    fn left_out(
+        &self,
+        checklist_path: &Path,
+        check: &Check,
+        changed: Option<&[PathBuf]>,
+    ) -> Result<Option<&'static str>> {
+        let only = self.settings.only();
+        if !only.is_empty() && !only.iter().any(|s| s.matches(checklist_path, check)) {
+            return Ok(Some("Not selected by --only"));
+        }
+        if self
+            .settings
+            .skip()
+            .iter()
+            .any(|s| s.matches(checklist_path, check))
+        {
+            return Ok(Some("Deselected by --skip"));
+        }
+
+        let Some(changed) = changed else {
+            return Ok(None);
+        };
+        let reason = match check.ttype().path() {
+            Some(path) if !walk::covers_any(&path.to_string_lossy(), changed)? => {
+                Some("No changed files")
+            }
+            None if self.settings.changed_only() => Some("Not a file check"),
+            _ => None,
+        };
+        Ok(reason)
+    }
+
+    fn changed_files(&self) -> Result<Option<Vec<PathBuf>>> {
+        let Some(base_ref) = self.settings.changed() else {
+            return Ok(None);
+        };
+        let changed = changed_files(base_ref)?;
+        debug!("{} file(s) changed since '{base_ref}'", changed.len());
+        Ok(Some(changed))
+    }
+
+    /// Every checklist and its checks, with why each check would be skipped if run now.
+    /// Conditions and requirements are evaluated, the checks themselves aren't run
+    pub fn list(&self) -> Result<Vec<ListedChecklist>> {
+        let changed = self.changed_files()?;
+
+        let mut listed = Vec::new();
+        for checklist in &self.checklists {
+            let path = checklist.path();
+            let source = if self.cache.is_external_file(path) {
+                Source::Remote
+            } else if let Some(dir) = &self.user_checklists_dir
+                && path.starts_with(dir)
+            {
+                Source::User
+            } else {
+                Source::Project
+            };
+
+            let offline = self.settings.offline() && checklist.needs_network();
+            let inherited = if offline {
+                None
+            } else {
+                checklist.inherited_status(&self.diff_settings, &self.template_env, &self.facts)?
+            };
+
+            let mut checks = Vec::new();
+            for check in checklist.checks() {
+                let skipped = if let Some(reason) =
+                    self.left_out(path, check, changed.as_deref())?
+                {
+                    Some(reason.to_string())
+                } else if offline || (self.settings.offline() && check.needs_network()) {
+                    Some(NEEDS_NETWORK.to_string())
+                } else if let Some(status) = &inherited {
+                    status.status().reason().map(|r| r.main().to_string())
+                } else {
+                    check
+                        .unmet(&self.diff_settings, &self.template_env, path, &self.facts)?
+                        .and_then(|status| status.status().reason().map(|r| r.main().to_string()))
+                };
+                checks.push(ListedCheck {
+                    description: check.description(),
+                    skipped,
+                });
+            }
+
+            listed.push(ListedChecklist {
+                path: path.to_path_buf(),
+                source,
+                checks,
+            });
+        }
+        Ok(listed)
+    }
+
     pub fn run_checks(&mut self) -> Result<Statuses> {
+        if let Some(e) = self.template_errors.first() {
+            bail!("{e}");
+        }
+
+        let mut trace = Trace::default();
         let mut statuses = Statuses::new();
+        statuses.set_meta(self.settings.meta().clone());
+        statuses.set_run_id(self.run_id);
+        statuses.set_project(self.name.clone());
+
+        let changed = self.changed_files()?;
 
+        let mut metrics = self.cache.metrics().clone();
+        let total = self.checklists.iter().map(|c| c.checks().len()).sum();
+        // Fixing prompts on the terminal
+        let mut progress = Progress::new(total, !self.settings.fix());
         for checklist in &self.checklists {
             let checklist_path = checklist.path();
             let checklist_name = checklist.name()?;
+            let checklist_file = checklist_path
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| checklist_name.clone());
             debug!("Running with checklist {checklist_name}");
+            let checklist_start = SystemTime::now();
+            let mut check_spans = Vec::new();
+
+            if self.settings.fix() {
+                install_missing(checklist.requirements())?;
+            }
+            let inherited = if self.settings.offline() && checklist.needs_network() {
+                None
+            } else {
+                checklist.inherited_status(&self.diff_settings, &self.template_env, &self.facts)?
+            };
 
             for check in checklist.checks() {
                 let check_name = check.description();
+
+                if let Some(reason) = self.left_out(checklist_path, check, changed.as_deref())? {
+                    debug!("Skipping check '{check_name}': {reason}");
+                    let status = Status::skip(reason.to_string(), None);
+                    progress.finish(&checklist_file, &check_name, &status);
+                    statuses.insert(checklist_path.to_path_buf(), check_name.to_string(), status);
+                    continue;
+                }
+
                 debug!("Running check: {check_name}");
+                progress.start(&checklist_file, &check_name);
+                let check_start = SystemTime::now();
+                let mut cached = false;
 
-                let status = if self.settings.no_read_cache() {
+                if self.settings.fix() {
+                    install_missing(check.requirements())?;
+                }
+
+                if self.settings.offline() && (check.needs_network() || checklist.needs_network()) {
+                    let status = Status::skip(String::from(NEEDS_NETWORK), None);
+                    progress.finish(&checklist_file, &check_name, &status);
+                    statuses.insert(checklist_path.to_path_buf(), check_name.to_string(), status);
+                    continue;
+                }
+
+                let status = if let Some(status) = &inherited {
+                    status.clone()
+                } else if self.settings.no_read_cache() {
                     match self.cache.get(check)? {
                         Some(status) => {
                             debug!("Check '{check_name}' status pulled from cache");
+                            cached = true;
                             status
                         }
                         None => {
-                            let status = check.do_check(
+                            let status = check.do_check_with_metrics(
                                 &self.diff_settings,
                                 &self.template_env,
                                 checklist_path,
                                 &self.facts,
+                                &mut metrics,
                             )?;
                             if !self.settings.no_write_cache() {
                                 self.cache.insert(check.clone(), status.clone())?;
@@ -267,11 +1072,12 @@ impl Project<'_> {
                         }
                     }
                 } else {
-                    let status = check.do_check(
+                    let status = check.do_check_with_metrics(
                         &self.diff_settings,
                         &self.template_env,
                         checklist_path,
                         &self.facts,
+                        &mut metrics,
                     )?;
                     if !self.settings.no_write_cache() {
                         self.cache.insert(check.clone(), status.clone())?;
@@ -279,11 +1085,197 @@ impl Project<'_> {
                     status
                 };
 
+                let status = match check.fix() {
+                    Some(fix)
+                        if self.settings.fix() && inherited.is_none() && status.is_failure() =>
+                    {
+                        eprintln!("Fixing '{check_name}': {fix}");
+                        fix.apply(
+                            check.ttype(),
+                            &self.template_env,
+                            checklist_path,
+                            &self.facts,
+                        )?;
+                        let status = check.do_check_with_metrics(
+                            &self.diff_settings,
+                            &self.template_env,
+                            checklist_path,
+                            &self.facts,
+                            &mut metrics,
+                        )?;
+                        if !self.settings.no_write_cache() {
+                            self.cache.insert(check.clone(), status.clone())?;
+                        }
+                        status
+                    }
+                    _ => status,
+                };
+
+                let mut status = status.with_severity(check.severity(), self.settings.fail_on());
+                let known_id = baseline::check_id(checklist_path, check);
+                if self.settings.update_baseline() {
+                    self.baseline.update(&known_id, status.is_failure());
+                }
+                if self.baseline.contains(&known_id) {
+                    status = status.known();
+                }
+                let check_end = SystemTime::now();
+                status.set_duration(check_end.duration_since(check_start).unwrap_or_default());
+                check_spans.push(CheckSpan {
+                    name: check_name.to_string(),
+                    start: check_start,
+                    end: check_end,
+                    status: status.status().label().to_string(),
+                    cached,
+                });
+                progress.finish(&checklist_file, &check_name, &status);
                 statuses.insert(checklist_path.to_path_buf(), check_name.to_string(), status);
             }
+
+            trace.checklists.push(ChecklistSpan {
+                path: checklist_path.to_path_buf(),
+                start: checklist_start,
+                end: SystemTime::now(),
+                checks: check_spans,
+            });
         }
 
+        progress.clear();
+        trace.end = SystemTime::now();
+        statuses.set_duration(trace.end.duration_since(trace.start).unwrap_or_default());
+        self.trace = trace;
+        if !self.settings.no_write_cache() {
+            self.cache.set_metrics(metrics);
+        }
         self.cache.save()?;
+        if self.settings.update_baseline() {
+            let path = self.root.join(BASELINE_FILE_NAME);
+            self.baseline.save(&path)?;
+            eprintln!(
+                "Recorded {} known failure(s) in {}",
+                self.baseline.len(),
+                path.display()
+            );
+        }
+        self.save_run(&statuses)?;
         Ok(statuses)
     }
+
+    /// Keep the results around for re-printing with 'report'
+    fn save_run(&self, statuses: &Statuses) -> Result<()> {
+        write_last_run(&self.last_run, &statuses.json(self.settings.exit_codes())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ExitCodes;
+
+    #[test]
+    fn fast_run_does_not_replay_a_later_failure() {
+        let dir = std::env::temp_dir().join(format!("checklints-fast-{}", std::process::id()));
+        let settings = Settings::default();
+        let cache_dir = dir.join("cache");
+        let last_run = last_run_path(&dir, &settings, &cache_dir);
+        let codes = ExitCodes::default();
+
+        // A green fast run
+        write_last_run(&last_run, &Statuses::new().json(&codes).unwrap()).unwrap();
+        save_fingerprint(&dir, &settings, &cache_dir, Some("fingerprint")).unwrap();
+        assert!(
+            unchanged_green_run(&dir, &settings, &cache_dir, "fingerprint")
+                .unwrap()
+                .is_some()
+        );
+
+        // Then a plain run that fails
+        let mut failed = Statuses::new();
+        let fail = Status::fail(String::from("File missing"), None);
+        failed.insert(PathBuf::from("a.toml"), String::from("fails"), fail);
+        write_last_run(&last_run, &failed.json(&codes).unwrap()).unwrap();
+        assert!(
+            unchanged_green_run(&dir, &settings, &cache_dir, "fingerprint")
+                .unwrap()
+                .is_none()
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn unreachable_remote_runs_the_checks() {
+        let dir = std::env::temp_dir().join(format!("checklints-remote-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .args(["-c", "user.name=a", "-c", "user.email=a@a"])
+                .args(args)
+                .current_dir(&dir)
+                .output()
+                .unwrap()
+        };
+        git(&["init", "-q"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "init"]);
+
+        let local = Settings::default();
+        let remote: RemoteFile = "http://127.0.0.1:9/checklist.toml".parse().unwrap();
+        let with_remote = Settings::builder()
+            .add_external_checklist(remote)
+            .build()
+            .unwrap();
+        let fingerprints = [
+            fingerprint(&dir, &local, &[]).unwrap(),
+            fingerprint(&dir, &with_remote, &[]).unwrap(),
+        ];
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(fingerprints[0].is_some());
+        assert!(fingerprints[1].is_none());
+    }
+
+    #[test]
+    fn checks_left_out_are_reported_as_skipped() {
+        let dir = std::env::temp_dir().join(format!("checklints-left-out-{}", std::process::id()));
+        fs::create_dir_all(dir.join("checklists")).unwrap();
+        fs::write(dir.join("a.md"), "a").unwrap();
+        let check = |name: &str| {
+            let path = dir.join(name);
+            format!(
+                "[[check]]\ntype = \"file\"\npath = {:?}\n",
+                path.display().to_string()
+            )
+        };
+        fs::write(
+            dir.join("checklists/a.toml"),
+            format!("{}\n{}", check("a.md"), check("b.md")),
+        )
+        .unwrap();
+        fs::write(
+            dir.join(crate::PROJECT_CONFIG_FILE_NAME),
+            "skip = [\"name:b.md\"]",
+        )
+        .unwrap();
+
+        let settings = Settings::builder()
+            .user_checklists(false)
+            .project_config_layer(&dir)
+            .unwrap()
+            .build()
+            .unwrap();
+        let mut project = Project::new(
+            dir.clone(),
+            settings,
+            DiffSettings::new(),
+            dir.join("config/checklists"),
+            dir.join("config/templates"),
+            dir.join("cache"),
+        )
+        .unwrap();
+        let statuses = project.run_checks();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let counts = statuses.unwrap().counts();
+        assert_eq!((counts.passed, counts.skipped), (1, 1));
+    }
 }
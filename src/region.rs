@@ -0,0 +1,70 @@
+//! Managed regions: the part of a file between 'BEGIN <name>' and 'END <name>' marker lines, in
+//! whatever comment syntax the file uses (e.g. '# BEGIN checklints')
+use std::ops::Range;
+
+/// Where a region's contents are within a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Bytes between the marker lines
+    pub range: Range<usize>,
+    /// Lines before the region's first line
+    pub line_offset: usize,
+}
+
+/// The region named `name` in `contents`, if both of its markers are there
+pub fn find(contents: &str, name: &str) -> Option<Region> {
+    let begin = format!("BEGIN {name}");
+    let end = format!("END {name}");
+
+    let mut start = None;
+    let mut offset = 0;
+    for (i, line) in contents.split_inclusive('\n').enumerate() {
+        match start {
+            None if line.contains(&begin) => start = Some((offset + line.len(), i + 1)),
+            Some((start, line_offset)) if line.contains(&end) => {
+                return Some(Region {
+                    range: start..offset,
+                    line_offset,
+                });
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// `contents` with the region named `name` replaced by `replacement`, None if it has no such region
+pub fn replace(contents: &str, name: &str, replacement: &str) -> Option<String> {
+    let region = find(contents, name)?;
+    let mut replaced = contents[..region.range.start].to_string();
+    replaced.push_str(replacement);
+    if !replacement.is_empty() && !replacement.ends_with('\n') {
+        replaced.push('\n');
+    }
+    replaced.push_str(&contents[region.range.end..]);
+    Some(replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "hand\n# BEGIN checklints\na\nb\n# END checklints\nedited\n";
+
+    #[test]
+    fn find_region_between_markers() {
+        let region = find(FILE, "checklints").unwrap();
+        assert_eq!(&FILE[region.range], "a\nb\n");
+        assert_eq!(region.line_offset, 2);
+        assert_eq!(find("# BEGIN checklints\na\n", "checklints"), None);
+    }
+
+    #[test]
+    fn replace_keeps_the_rest_of_the_file() {
+        assert_eq!(
+            replace(FILE, "checklints", "c").unwrap(),
+            "hand\n# BEGIN checklints\nc\n# END checklints\nedited\n"
+        );
+    }
+}
@@ -0,0 +1,624 @@
+//! Standalone reports of a run's results, to share with people who don't use the CLI
+use crate::types::{Counts, Reason, Show, Status, StatusStatus, Statuses};
+use crate::THIS_CRATE_NAME;
+use anyhow::Result;
+use indexmap::IndexMap;
+use serde_json::json;
+use std::env;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Formats results can be printed in by 'report'
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// Same as a run prints
+    #[default]
+    Text,
+    Html,
+    Markdown,
+}
+
+/// Formats a run's results can be output in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// A report for people
+    #[default]
+    Text,
+    /// Every check's status, reason, whether it was cached, and duration, by checklist, with a
+    /// summary
+    Json,
+    /// JUnit XML, a testsuite per checklist and a testcase per check, for CI test reports
+    Junit,
+    /// SARIF 2.1, failures as results located in the files they're about, for code scanning
+    Sarif,
+    /// A compact Markdown table, for PR comments and CI step summaries
+    Markdown,
+}
+
+/// Every check's name and status, by checklist, in the order they ran
+fn by_checklist(statuses: &Statuses) -> IndexMap<&Path, Vec<(&str, &Status)>> {
+    let mut checklists: IndexMap<&Path, Vec<(&str, &Status)>> = IndexMap::new();
+    for (checklist, name, status) in statuses.iter() {
+        checklists
+            .entry(checklist)
+            .or_default()
+            .push((name, status));
+    }
+    checklists
+}
+
+fn counts<'a>(checks: impl IntoIterator<Item = &'a (&'a str, &'a Status)>) -> Counts {
+    let mut counts = Counts::default();
+    for (_, status) in checks {
+        counts.add(status);
+    }
+    counts
+}
+
+fn label(status: &Status) -> &'static str {
+    match status.status() {
+        StatusStatus::Pass => "PASS",
+        StatusStatus::Skip { .. } => "SKIP",
+        StatusStatus::Fail { .. } => "FAIL",
+        StatusStatus::Review { .. } => "NEEDS REVIEW",
+        StatusStatus::Warn { .. } => "WARN",
+        StatusStatus::Known { .. } => "KNOWN",
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .unwrap_or(path.as_os_str())
+        .to_string_lossy()
+        .to_string()
+}
+
+fn title(statuses: &Statuses) -> String {
+    match statuses.project() {
+        Some(project) => format!("{THIS_CRATE_NAME} report: {project}"),
+        None => format!("{THIS_CRATE_NAME} report"),
+    }
+}
+
+/// Details of a reason (assertions and diff), colors removed
+fn details(reason: &Reason) -> Vec<String> {
+    let mut lines = Vec::new();
+    if let Some(location) = reason.location()
+        && reason.assertions().is_empty()
+    {
+        lines.push(format!("--> {location}"));
+    }
+    for assertion in reason.assertions() {
+        let mark = if assertion.passed() { "✓" } else { "✗" };
+        match assertion.location() {
+            Some(location) => {
+                lines.push(format!("{mark} {} --> {location}", assertion.description()))
+            }
+            None => lines.push(format!("{mark} {}", assertion.description())),
+        }
+    }
+    if let Some(secondary) = reason.secondary() {
+        lines.push(strip_ansi_escapes::strip_str(secondary));
+    }
+    lines
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 70em; margin: 2em auto; }
+table { border-collapse: collapse; width: 100%; margin-bottom: 1em; }
+th, td { border: 1px solid #ccc; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+pre { background: #f6f6f6; padding: 0.5em; overflow-x: auto; }
+.PASS { color: #1a7f37; } .FAIL { color: #cf222e; } .SKIP { color: #9a6700; }
+.REVIEW { color: #0969da; } .WARN { color: #bf8700; }
+.KNOWN { color: #8250df; }";
+
+/// A standalone HTML page of the results
+pub fn html(statuses: &Statuses) -> String {
+    let checklists = by_checklist(statuses);
+    let total = statuses.counts();
+    let title = escape_html(&title(statuses));
+
+    let mut s = String::new();
+    let _ = writeln!(
+        s,
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">"
+    );
+    let _ = writeln!(
+        s,
+        "<title>{title}</title>\n<style>\n{STYLE}\n</style>\n</head>\n<body>"
+    );
+    let _ = writeln!(s, "<h1>{title}</h1>");
+    let verdict = total.verdict().to_string();
+    let _ = writeln!(
+        s,
+        "<p><strong class=\"{verdict}\">{verdict}</strong>: {}</p>",
+        escape_html(&total.to_string())
+    );
+    if !statuses.meta().is_empty() {
+        let _ = writeln!(s, "<table>");
+        for (key, value) in statuses.meta() {
+            let _ = writeln!(
+                s,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                escape_html(key),
+                escape_html(value)
+            );
+        }
+        let _ = writeln!(s, "</table>");
+    }
+
+    let _ = writeln!(s, "<h2>Summary</h2>\n<table>");
+    let _ = writeln!(
+        s,
+        "<tr><th>Checklist</th><th>Passed</th><th>Failed</th><th>Warned</th><th>Known</th><th>Skipped</th><th>Review</th></tr>"
+    );
+    for (checklist, checks) in &checklists {
+        let counts = counts(checks);
+        let _ = writeln!(
+            s,
+            "<tr><td><a href=\"#{id}\">{id}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            counts.passed,
+            counts.failed,
+            counts.warned,
+            counts.known,
+            counts.skipped,
+            counts.review,
+            id = escape_html(&file_name(checklist))
+        );
+    }
+    let _ = writeln!(s, "</table>");
+
+    for (checklist, checks) in &checklists {
+        let name = escape_html(&file_name(checklist));
+        let _ = writeln!(s, "<h2 id=\"{name}\">{name}</h2>");
+        let _ = writeln!(
+            s,
+            "<table>\n<tr><th>Status</th><th>Check</th><th>Reason</th></tr>"
+        );
+        for (check, status) in checks {
+            let label = label(status);
+            let class = label.split(' ').next_back().unwrap_or(label);
+            let reason = match status.status().reason() {
+                Some(reason) => {
+                    let details = details(reason);
+                    let mut cell = escape_html(reason.main());
+                    if !details.is_empty() {
+                        let _ = write!(
+                            cell,
+                            "<details><summary>Details</summary><pre>{}</pre></details>",
+                            escape_html(&details.join("\n"))
+                        );
+                    }
+                    cell
+                }
+                None => String::new(),
+            };
+            let _ = writeln!(
+                s,
+                "<tr><td class=\"{class}\">{label}</td><td>{}</td><td>{reason}</td></tr>",
+                escape_html(check)
+            );
+        }
+        let _ = writeln!(s, "</table>");
+    }
+
+    if let Some(run_id) = statuses.run_id() {
+        let _ = writeln!(s, "<p><small>Run {run_id}</small></p>");
+    }
+    let _ = writeln!(s, "</body>\n</html>");
+    s
+}
+
+fn escape_markdown(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
+}
+
+/// A fence that doesn't occur in `contents`
+fn fence(contents: &str) -> String {
+    let mut fence = "```".to_string();
+    while contents.contains(&fence) {
+        fence.push('`');
+    }
+    fence
+}
+
+/// A Markdown document of the results
+pub fn markdown(statuses: &Statuses) -> String {
+    let checklists = by_checklist(statuses);
+    let total = statuses.counts();
+
+    let mut s = String::new();
+    let _ = writeln!(s, "# {}\n", title(statuses));
+    let _ = writeln!(s, "**{}**: {total}\n", total.verdict());
+    for (key, value) in statuses.meta() {
+        let _ = writeln!(s, "- {}: {}", escape_markdown(key), escape_markdown(value));
+    }
+    if !statuses.meta().is_empty() {
+        let _ = writeln!(s);
+    }
+
+    let _ = writeln!(s, "## Summary\n");
+    let _ = writeln!(
+        s,
+        "| Checklist | Passed | Failed | Warned | Known | Skipped | Review |"
+    );
+    let _ = writeln!(s, "|---|---|---|---|---|---|---|");
+    for (checklist, checks) in &checklists {
+        let counts = counts(checks);
+        let _ = writeln!(
+            s,
+            "| {} | {} | {} | {} | {} | {} | {} |",
+            escape_markdown(&file_name(checklist)),
+            counts.passed,
+            counts.failed,
+            counts.warned,
+            counts.known,
+            counts.skipped,
+            counts.review
+        );
+    }
+
+    for (checklist, checks) in &checklists {
+        let _ = writeln!(s, "\n## {}\n", file_name(checklist));
+        let _ = writeln!(s, "| Status | Check | Reason |");
+        let _ = writeln!(s, "|---|---|---|");
+        let mut failures = Vec::new();
+        for (check, status) in checks {
+            let reason = status.status().reason();
+            let main = reason
+                .map(|r| escape_markdown(r.main()))
+                .unwrap_or_default();
+            let _ = writeln!(
+                s,
+                "| {} | {} | {main} |",
+                label(status),
+                escape_markdown(check)
+            );
+            if let Some(reason) = reason {
+                let details = details(reason);
+                if !details.is_empty() {
+                    failures.push((check, details.join("\n")));
+                }
+            }
+        }
+        for (check, details) in failures {
+            let fence = fence(&details);
+            let _ = writeln!(
+                s,
+                "\n<details><summary>{}</summary>\n\n{fence}\n{details}\n{fence}\n\n</details>",
+                escape_html(check)
+            );
+        }
+    }
+
+    if let Some(run_id) = statuses.run_id() {
+        let _ = writeln!(s, "\nRun {run_id}");
+    }
+    s
+}
+
+/// A compact Markdown table of the results (checklist, check, status, reason) with the verdict
+/// above it, for PR comments and CI step summaries. Only checks `show` includes get a row
+pub fn markdown_table(statuses: &Statuses, show: Show) -> String {
+    let total = statuses.counts();
+    let checklists = by_checklist(statuses);
+
+    let mut s = String::new();
+    let _ = writeln!(s, "**{THIS_CRATE_NAME}: {}** ({total})\n", total.verdict());
+    let _ = writeln!(s, "| Checklist | Check | Status | Reason |");
+    let _ = writeln!(s, "|---|---|---|---|");
+    for (checklist, checks) in &checklists {
+        for (check, status) in checks {
+            if !show.includes(status) {
+                continue;
+            }
+            let reason = status
+                .status()
+                .reason()
+                .map(|r| escape_markdown(r.main()))
+                .unwrap_or_default();
+            let _ = writeln!(
+                s,
+                "| {} | {} | {} | {reason} |",
+                escape_markdown(&file_name(checklist)),
+                escape_markdown(check),
+                label(status)
+            );
+        }
+    }
+    s
+}
+
+fn seconds(status: &Status) -> f64 {
+    status.duration().unwrap_or_default().as_secs_f64()
+}
+
+/// JUnit XML of the results: a testsuite per checklist and a testcase per check, failures with
+/// their details. Checks that need review, warnings, and known failures are reported as skipped
+pub fn junit(statuses: &Statuses) -> String {
+    let checklists = by_checklist(statuses);
+    let total = statuses.counts();
+    let time = |checks: &[(&str, &Status)]| checks.iter().map(|(_, s)| seconds(s)).sum::<f64>();
+
+    let mut s = String::new();
+    let _ = writeln!(s, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    let _ = writeln!(
+        s,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+        escape_html(&title(statuses)),
+        total.total(),
+        total.failed,
+        total.skipped + total.review + total.warned + total.known,
+        checklists.values().map(|checks| time(checks)).sum::<f64>()
+    );
+    for (checklist, checks) in &checklists {
+        let counts = counts(checks);
+        let name = escape_html(&checklist.display().to_string());
+        let _ = writeln!(
+            s,
+            "  <testsuite name=\"{name}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{:.3}\">",
+            checks.len(),
+            counts.failed,
+            counts.skipped + counts.review + counts.warned + counts.known,
+            time(checks)
+        );
+        for (check, status) in checks {
+            let _ = write!(
+                s,
+                "    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+                escape_html(check),
+                escape_html(&file_name(checklist)),
+                seconds(status)
+            );
+            let (element, message, reason) = match status.status() {
+                StatusStatus::Pass => {
+                    let _ = writeln!(s, "/>");
+                    continue;
+                }
+                StatusStatus::Fail { reason } => ("failure", reason.main().to_string(), reason),
+                StatusStatus::Skip { reason } => ("skipped", reason.main().to_string(), reason),
+                StatusStatus::Review { reason } => (
+                    "skipped",
+                    format!("Needs review: {}", reason.main()),
+                    reason,
+                ),
+                StatusStatus::Warn { reason } => {
+                    ("skipped", format!("Warning: {}", reason.main()), reason)
+                }
+                StatusStatus::Known { reason } => (
+                    "skipped",
+                    format!("Known failure: {}", reason.main()),
+                    reason,
+                ),
+            };
+            let _ = writeln!(s, ">");
+            let details = details(reason);
+            if details.is_empty() {
+                let _ = writeln!(
+                    s,
+                    "      <{element} message=\"{}\"/>",
+                    escape_html(&message)
+                );
+            } else {
+                let _ = writeln!(
+                    s,
+                    "      <{element} message=\"{}\">{}</{element}>",
+                    escape_html(&message),
+                    escape_html(&details.join("\n"))
+                );
+            }
+            let _ = writeln!(s, "    </testcase>");
+        }
+        let _ = writeln!(s, "  </testsuite>");
+    }
+    let _ = writeln!(s, "</testsuites>");
+    s
+}
+
+/// `path` relative to the current dir, where SARIF consumers expect paths in the repo to be, or
+/// else as a file URI
+fn artifact_uri(path: &Path) -> String {
+    let relative = env::current_dir()
+        .ok()
+        .and_then(|dir| path.strip_prefix(dir).ok().map(Path::to_path_buf));
+    match relative {
+        Some(relative) => relative.display().to_string(),
+        None if path.is_absolute() => format!("file://{}", path.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// SARIF 2.1 of the results, for code scanning. A result per failed check (a warning per check
+/// that only warned, and note per check that needs review or is a known failure), located where
+/// the reason points, or else at the check's checklist. Known failures are marked unchanged since
+/// the baseline
+pub fn sarif(statuses: &Statuses) -> Result<String> {
+    let checklists = by_checklist(statuses);
+
+    let mut rules = Vec::new();
+    let mut results = Vec::new();
+    for (checklist, checks) in &checklists {
+        for (check, status) in checks {
+            let (level, reason) = match status.status() {
+                StatusStatus::Fail { reason } => ("error", reason),
+                StatusStatus::Warn { reason } => ("warning", reason),
+                StatusStatus::Review { reason } | StatusStatus::Known { reason } => {
+                    ("note", reason)
+                }
+                StatusStatus::Pass | StatusStatus::Skip { .. } => continue,
+            };
+
+            let rule = format!("{}/{check}", file_name(checklist));
+            let rule_index = match rules.iter().position(|r| *r == rule) {
+                Some(i) => i,
+                None => {
+                    rules.push(rule.clone());
+                    rules.len() - 1
+                }
+            };
+
+            let mut message = reason.main().to_string();
+            for line in details(reason) {
+                message.push('\n');
+                message.push_str(&line);
+            }
+
+            let location = match reason.location() {
+                Some(location) => json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": artifact_uri(&location.path) },
+                        "region": {
+                            "startLine": location.line,
+                            "startColumn": location.column,
+                        },
+                    },
+                }),
+                None => json!({
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": artifact_uri(checklist) },
+                    },
+                }),
+            };
+
+            let mut result = json!({
+                "ruleId": rule,
+                "ruleIndex": rule_index,
+                "level": level,
+                "message": { "text": message },
+                "locations": [location],
+            });
+            if status.is_known() {
+                result["baselineState"] = json!("unchanged");
+            }
+            results.push(result);
+        }
+    }
+
+    let rules = rules
+        .iter()
+        .map(|rule| json!({ "id": rule, "shortDescription": { "text": rule } }))
+        .collect::<Vec<_>>();
+    let sarif = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": THIS_CRATE_NAME,
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    });
+    Ok(serde_json::to_string_pretty(&sarif)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ExitCodes, Severity};
+    use std::path::PathBuf;
+
+    #[test]
+    fn junit_escapes_and_counts_failures() {
+        let mut statuses = Statuses::new();
+        let checklist = PathBuf::from("a.toml");
+        let pass = Status::new(false, StatusStatus::Pass);
+        let fail = Status::fail(String::from("<missing>"), Some(String::from("a & b")));
+        statuses.insert(checklist.clone(), String::from("passes"), pass);
+        statuses.insert(checklist, String::from("fails \"here\""), fail);
+
+        let xml = junit(&statuses);
+        assert!(xml.contains("<testsuite name=\"a.toml\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase name=\"fails &quot;here&quot;\""));
+        assert!(xml.contains("<failure message=\"&lt;missing&gt;\">a &amp; b</failure>"));
+    }
+
+    #[test]
+    fn markdown_table_rows_of_shown_checks() {
+        let mut statuses = Statuses::new();
+        let checklist = PathBuf::from("a.toml");
+        let pass = Status::new(false, StatusStatus::Pass);
+        let fail = Status::fail(String::from("a | b"), None);
+        statuses.insert(checklist.clone(), String::from("passes"), pass);
+        statuses.insert(checklist, String::from("fails"), fail);
+
+        let table = markdown_table(&statuses, Show::Failed);
+        assert!(table.contains("| a.toml | fails | FAIL | a \\| b |"));
+        assert!(!table.contains("passes"));
+    }
+
+    #[test]
+    fn sarif_results_of_failures() {
+        let mut statuses = Statuses::new();
+        let checklist = PathBuf::from("a.toml");
+        let pass = Status::new(false, StatusStatus::Pass);
+        let fail = Status::fail(String::from("File missing"), None);
+        statuses.insert(checklist.clone(), String::from("passes"), pass);
+        statuses.insert(checklist, String::from("fails"), fail);
+
+        let sarif: serde_json::Value = serde_json::from_str(&sarif(&statuses).unwrap()).unwrap();
+        let results = sarif["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0]["ruleId"], "a.toml/fails");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[0]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "a.toml"
+        );
+    }
+
+    #[test]
+    fn failures_below_fail_on_only_warn() {
+        let mut statuses = Statuses::new();
+        let fail = Status::fail(String::from("File missing"), None);
+        let warn = fail
+            .clone()
+            .with_severity(Severity::Warning, Severity::Error);
+        assert!(warn.is_warning());
+        statuses.insert(PathBuf::from("a.toml"), String::from("warns"), warn);
+        assert_eq!(statuses.exit_code(&ExitCodes::default()), 0);
+        assert_eq!(statuses.counts().warned, 1);
+
+        let sarif: serde_json::Value = serde_json::from_str(&sarif(&statuses).unwrap()).unwrap();
+        assert_eq!(sarif["runs"][0]["results"][0]["level"], "warning");
+
+        let fail = fail.with_severity(Severity::Warning, Severity::Warning);
+        statuses.insert(PathBuf::from("a.toml"), String::from("fails"), fail);
+        assert_eq!(statuses.exit_code(&ExitCodes::default()), 1);
+    }
+
+    #[test]
+    fn checks_stay_in_the_order_they_ran() {
+        let mut statuses = Statuses::new();
+        for (checklist, name) in [("b.toml", "z"), ("a.toml", "y"), ("b.toml", "a")] {
+            let pass = Status::new(false, StatusStatus::Pass);
+            statuses.insert(PathBuf::from(checklist), String::from(name), pass);
+        }
+
+        let json = serde_json::to_string(&statuses).unwrap();
+        let statuses: Statuses = serde_json::from_str(&json).unwrap();
+        let checklists = by_checklist(&statuses);
+        let order = checklists
+            .iter()
+            .flat_map(|(checklist, checks)| checks.iter().map(move |(name, _)| (*checklist, *name)))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            [
+                (Path::new("b.toml"), "z"),
+                (Path::new("b.toml"), "a"),
+                (Path::new("a.toml"), "y")
+            ]
+        );
+    }
+}
@@ -0,0 +1,106 @@
+//! Choosing which checks run, e.g. `--only file:README --skip tag:network`
+use crate::types::Check;
+use anyhow::{bail, Result};
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Tag every check that needs the network has, without being tagged by hand
+pub const NETWORK_TAG: &str = "network";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Id,
+    Tag,
+    /// Checklist file name or path
+    Checklist,
+    /// Check name
+    Name,
+    /// Check type, along with a check name
+    Type(String),
+}
+
+/// Matches checks by id, tag, checklist, name, or type. Written as `KIND:VALUE`, e.g.
+/// `id:readme`, `tag:network`, `checklist:rust.toml`, `name:README`. Any other kind is a check type
+/// and VALUE part of the name (`file:README`), and without a kind it's part of the name
+#[derive(Debug, Clone, PartialEq, Eq, DeserializeFromStr, SerializeDisplay)]
+pub struct Selector {
+    kind: Kind,
+    value: String,
+}
+
+impl FromStr for Selector {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let Some((kind, value)) = s.split_once(':') else {
+            return Ok(Self {
+                kind: Kind::Name,
+                value: s.to_string(),
+            });
+        };
+        let kind = match kind {
+            "id" => Kind::Id,
+            "tag" => Kind::Tag,
+            "checklist" => Kind::Checklist,
+            "name" => Kind::Name,
+            "" => bail!("Missing the kind of selector before ':' in '{s}'"),
+            ttype => Kind::Type(ttype.to_string()),
+        };
+        Ok(Self {
+            kind,
+            value: value.to_string(),
+        })
+    }
+}
+
+impl Display for Selector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            Kind::Id => write!(f, "id:{}", self.value),
+            Kind::Tag => write!(f, "tag:{}", self.value),
+            Kind::Checklist => write!(f, "checklist:{}", self.value),
+            Kind::Name => write!(f, "name:{}", self.value),
+            Kind::Type(ttype) => write!(f, "{ttype}:{}", self.value),
+        }
+    }
+}
+
+impl Selector {
+    pub fn matches(&self, checklist_path: &Path, check: &Check) -> bool {
+        let value = self.value.as_str();
+        match &self.kind {
+            Kind::Id => check.id() == Some(value),
+            Kind::Tag => {
+                check.tags().iter().any(|tag| tag == value)
+                    || (value == NETWORK_TAG && check.needs_network())
+            }
+            Kind::Checklist => {
+                checklist_path.file_name().is_some_and(|name| name == value)
+                    || checklist_path.to_string_lossy().contains(value)
+            }
+            Kind::Name => check.description().contains(value),
+            Kind::Type(ttype) => {
+                check.ttype().name() == *ttype && check.description().contains(value)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_selectors() {
+        let selector: Selector = "file:README".parse().unwrap();
+        assert_eq!(selector.kind, Kind::Type(String::from("file")));
+        assert_eq!(selector.value, "README");
+        assert_eq!(selector.to_string(), "file:README");
+
+        let selector: Selector = "README".parse().unwrap();
+        assert_eq!(selector.kind, Kind::Name);
+        assert!(":x".parse::<Selector>().is_err());
+    }
+}
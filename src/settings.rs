@@ -1,14 +1,19 @@
 use crate::cli::Cli;
 
-use crate::types::RemoteFile;
-use crate::THIS_CRATE_NAME;
+use crate::select::Selector;
+use crate::types::{ExitCodes, RemoteFile, Severity, Show};
+use crate::{PROJECT_CONFIG_FILE_NAME, THIS_CRATE_NAME};
 use anyhow::{bail, Result};
+use clap::ValueEnum;
 use log::debug;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
 use std::fs::File;
 use std::io::Write;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::{env, fs};
+use toml_edit::{DocumentMut, Item};
 
 fn default_user_checklists() -> bool {
     true
@@ -34,6 +39,54 @@ fn default_clear_cache() -> bool {
     false
 }
 
+fn default_full_diff() -> bool {
+    false
+}
+
+fn default_changed_only() -> bool {
+    false
+}
+
+fn default_fix() -> bool {
+    false
+}
+
+fn default_update_baseline() -> bool {
+    false
+}
+
+fn default_offline() -> bool {
+    false
+}
+
+fn default_home() -> bool {
+    false
+}
+
+fn default_checklist_dirs() -> Vec<String> {
+    [".checklists", "checklists", "checks", ".checks"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_checklist_files() -> Vec<String> {
+    [".checklist.toml", "checklist.toml"]
+        .map(String::from)
+        .to_vec()
+}
+
+fn default_strict_undefined() -> bool {
+    false
+}
+
+fn default_sync_checklists() -> bool {
+    false
+}
+
+fn default_fast() -> bool {
+    false
+}
+
 fn default_external_checklists() -> Vec<RemoteFile> {
     Vec::new()
 }
@@ -42,16 +95,184 @@ fn default_external_templates() -> Vec<RemoteFile> {
     Vec::new()
 }
 
-pub fn write_default_config(path: &Path) -> Result<()> {
+/// What each setting in the config file does, written above it in the default config
+const SETTING_DOCS: [(&str, &str); 27] = [
+    (
+        "user_checklists",
+        "Use user-wide checklists from the config dir",
+    ),
+    ("fail_fast", "Stop after the first failure"),
+    ("no_read_cache", "Do not read from cache"),
+    ("no_write_cache", "Do not write to cache"),
+    (
+        "no_cache",
+        "Do not read from or write to cache, implies 'no_read_cache' and 'no_write_cache'",
+    ),
+    ("clear_cache", "Delete the project's cache before running"),
+    (
+        "external_checklists",
+        "Remote checklists to pull, e.g. 'https://example.com/checklist.toml::<sha256>'",
+    ),
+    (
+        "required_checklists",
+        "Remote checklists every project is checked against, only settable here. Project configs \
+         can't set only, skip, exit_codes, fail_on or update_baseline while there are any",
+    ),
+    (
+        "external_templates",
+        "Remote templates to pull, or .tar.gz, .tgz, .tar or .zip archives of templates",
+    ),
+    (
+        "full_diff",
+        "Print diffs in full instead of truncating long ones",
+    ),
+    (
+        "show",
+        "Which check results to report: all, failed, or failed+skipped",
+    ),
+    (
+        "fail_on",
+        "Least severe failing check that fails the run: info, warning, or error",
+    ),
+    ("only", "Only run checks matching one of these selectors"),
+    ("skip", "Don't run checks matching any of these selectors"),
+    (
+        "changed_only",
+        "With 'changed', also skip checks that aren't about files",
+    ),
+    ("fix", "Fix problems where possible"),
+    (
+        "update_baseline",
+        "Record the checks failing now as the project's known failures",
+    ),
+    (
+        "offline",
+        "Don't use the network: skip checks that need it and only use cached remote files",
+    ),
+    ("home", "Audit the home directory instead of a project"),
+    (
+        "strict_undefined",
+        "Fail when a template uses a variable that isn't set",
+    ),
+    (
+        "sync_checklists",
+        "Regenerate checklists from their templates before running them",
+    ),
+    (
+        "fast",
+        "Skip running the checks when nothing changed since the last green run",
+    ),
+    (
+        "checklist_dirs",
+        "Names of dirs in the project whose checklists are used",
+    ),
+    ("checklist_files", "Names of checklist files in the project"),
+    ("exit_codes", "Exit code to use for each check outcome"),
+    ("meta", "Metadata (e.g. team) attached to every report"),
+    (
+        "vars",
+        "Facts for checklists and templates, overriding any fact of the same name",
+    ),
+];
+
+/// Settings that are unset by default, written commented out with an example value
+const UNSET_SETTINGS: [(&str, &str, &str); 4] = [
+    (
+        "artifacts_dir",
+        "Write full diffs of failed checks to this dir",
+        "\"checklints-artifacts\"",
+    ),
+    (
+        "changed",
+        "Only run file and directory checks that cover files changed since this git ref",
+        "\"HEAD\"",
+    ),
+    (
+        "otlp_endpoint",
+        "Export a trace of each run to this OTLP/HTTP endpoint",
+        "\"http://localhost:4318\"",
+    ),
+    (
+        "name",
+        "Project name, instead of the name of the project dir",
+        "\"my-project\"",
+    ),
+];
+
+fn doc_comment(name: &str) -> String {
+    SETTING_DOCS
+        .iter()
+        .find(|(setting, _)| *setting == name)
+        .map(|(_, doc)| format!("# {doc}\n"))
+        .unwrap_or_default()
+}
+
+/// The default settings as a config file, each setting with a comment on what it does
+pub fn default_config() -> Result<String> {
     let config = MaybeSettings::default();
-    let contents = toml::to_string(&config)?;
+    let mut doc: DocumentMut = toml::to_string(&config)?.parse()?;
+
+    // Before the tables, so they're not part of one once uncommented
+    let mut unset = UNSET_SETTINGS
+        .map(|(name, doc, example)| format!("\n# {doc}\n# {name} = {example}\n"))
+        .concat();
+    for (i, (mut key, item)) in doc.iter_mut().enumerate() {
+        let blank = if i == 0 { "" } else { "\n" };
+        let comment = format!("{blank}{}", doc_comment(key.get()));
+        match item {
+            Item::Table(table) => {
+                let comment = format!("{}{comment}", std::mem::take(&mut unset));
+                table.decor_mut().set_prefix(comment);
+            }
+            _ => key.leaf_decor_mut().set_prefix(comment),
+        }
+    }
+
+    let mut contents = doc.to_string();
+    contents.push_str(&unset);
+    Ok(contents)
+}
+
+pub fn write_default_config(path: &Path) -> Result<()> {
+    let contents = default_config()?;
     let mut f = File::create(path)?;
     debug!("Writing default config to {}", path.display());
     write!(f, "{contents}")?;
     Ok(())
 }
 
-#[derive(Debug)]
+/// Where a setting's value came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Default,
+    ConfigFile(PathBuf),
+    Env,
+    Cli,
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::ConfigFile(path) => write!(f, "config file {}", path.display()),
+            Source::Env => write!(f, "env"),
+            Source::Cli => write!(f, "CLI"),
+        }
+    }
+}
+
+/// Settings whose values from each layer are added together, instead of replacing the last
+const APPENDED_SETTINGS: [&str; 7] = [
+    "external_checklists",
+    "required_checklists",
+    "external_templates",
+    "meta",
+    "vars",
+    "only",
+    "skip",
+];
+
+#[derive(Debug, Serialize)]
 pub struct Settings {
     user_checklists: bool,
     fail_fast: bool,
@@ -59,7 +280,32 @@ pub struct Settings {
     no_write_cache: bool,
     clear_cache: bool,
     external_checklists: Vec<RemoteFile>,
+    required_checklists: Vec<RemoteFile>,
     external_templates: Vec<RemoteFile>,
+    full_diff: bool,
+    artifacts_dir: Option<PathBuf>,
+    show: Show,
+    fail_on: Severity,
+    exit_codes: ExitCodes,
+    meta: BTreeMap<String, String>,
+    vars: BTreeMap<String, String>,
+    only: Vec<Selector>,
+    skip: Vec<Selector>,
+    name: Option<String>,
+    changed: Option<String>,
+    changed_only: bool,
+    fix: bool,
+    update_baseline: bool,
+    offline: bool,
+    otlp_endpoint: Option<String>,
+    home: bool,
+    strict_undefined: bool,
+    sync_checklists: bool,
+    fast: bool,
+    checklist_dirs: Vec<String>,
+    checklist_files: Vec<String>,
+    #[serde(skip)]
+    sources: BTreeMap<String, Vec<Source>>,
 }
 
 impl Settings {
@@ -95,9 +341,144 @@ impl Settings {
         &self.external_checklists
     }
 
+    /// External checklists that always run. Only the user config may set these
+    pub fn required_checklists(&self) -> &[RemoteFile] {
+        &self.required_checklists
+    }
+
     pub fn external_templates(&self) -> &[RemoteFile] {
         &self.external_templates
     }
+
+    pub fn full_diff(&self) -> bool {
+        self.full_diff
+    }
+
+    pub fn artifacts_dir(&self) -> Option<&Path> {
+        self.artifacts_dir.as_deref()
+    }
+
+    pub fn show(&self) -> Show {
+        self.show
+    }
+
+    pub fn fail_on(&self) -> Severity {
+        self.fail_on
+    }
+
+    pub fn exit_codes(&self) -> &ExitCodes {
+        &self.exit_codes
+    }
+
+    pub fn meta(&self) -> &BTreeMap<String, String> {
+        &self.meta
+    }
+
+    /// User-supplied variables, merged into the facts
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
+
+    /// Only run checks matching one of these
+    pub fn only(&self) -> &[Selector] {
+        &self.only
+    }
+
+    /// Don't run checks matching any of these
+    pub fn skip(&self) -> &[Selector] {
+        &self.skip
+    }
+
+    /// Project name, overriding the one derived from the project dir
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Git ref to compare against, only checks covering files changed since then are run
+    pub fn changed(&self) -> Option<&str> {
+        self.changed.as_deref()
+    }
+
+    /// When only running changed files, also skip checks that aren't about files
+    pub fn changed_only(&self) -> bool {
+        self.changed_only
+    }
+
+    /// Try to fix problems (e.g. install missing tools) instead of only reporting them
+    pub fn fix(&self) -> bool {
+        self.fix
+    }
+
+    /// Rewrite the baseline with the checks that fail in this run
+    pub fn update_baseline(&self) -> bool {
+        self.update_baseline
+    }
+
+    /// Don't use the network. Checks that need it are skipped, remote files come from the cache
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// OTLP/HTTP endpoint to export a trace of the run to
+    pub fn otlp_endpoint(&self) -> Option<&str> {
+        self.otlp_endpoint.as_deref()
+    }
+
+    /// Audit the user's home directory instead of a project
+    pub fn home(&self) -> bool {
+        self.home
+    }
+
+    /// Fail templates that use an undefined variable, instead of rendering it empty
+    pub fn strict_undefined(&self) -> bool {
+        self.strict_undefined
+    }
+
+    /// Regenerate checklists from their templates at the start of every run
+    pub fn sync_checklists(&self) -> bool {
+        self.sync_checklists
+    }
+
+    /// Report the last run's result without running any checks when nothing changed since it passed
+    pub fn fast(&self) -> bool {
+        self.fast
+    }
+
+    /// Names of dirs in the project whose checklists are used
+    pub fn checklist_dirs(&self) -> &[String] {
+        &self.checklist_dirs
+    }
+
+    /// Names of checklist files in the project
+    pub fn checklist_files(&self) -> &[String] {
+        &self.checklist_files
+    }
+
+    /// Layers the setting named `name` was set by, the last one taking effect unless its values
+    /// are added together. Empty when no layer set it, or the settings weren't built in layers
+    pub fn sources(&self, name: &str) -> &[Source] {
+        self.sources.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every setting that is set, as TOML, with the layers that set it in a comment
+    pub fn effective(&self) -> Result<String> {
+        let toml::Value::Table(table) = toml::Value::try_from(self)? else {
+            bail!("Settings are not a table");
+        };
+        let mut effective = String::new();
+        for (name, value) in table {
+            let sources = match self.sources(&name) {
+                [] => Source::Default.to_string(),
+                sources => sources
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+            effective.push_str(&format!("{name} = {value}  # {sources}\n"));
+        }
+        Ok(effective)
+    }
 }
 
 impl Default for Settings {
@@ -109,7 +490,31 @@ impl Default for Settings {
             no_write_cache: default_no_write_cache(),
             clear_cache: default_clear_cache(),
             external_checklists: default_external_checklists(),
+            required_checklists: Vec::new(),
             external_templates: default_external_templates(),
+            full_diff: default_full_diff(),
+            artifacts_dir: None,
+            show: Show::default(),
+            fail_on: Severity::default(),
+            exit_codes: ExitCodes::default(),
+            meta: BTreeMap::new(),
+            vars: BTreeMap::new(),
+            only: Vec::new(),
+            skip: Vec::new(),
+            name: None,
+            changed: None,
+            changed_only: default_changed_only(),
+            fix: default_fix(),
+            update_baseline: default_update_baseline(),
+            offline: default_offline(),
+            otlp_endpoint: None,
+            home: default_home(),
+            strict_undefined: default_strict_undefined(),
+            sync_checklists: default_sync_checklists(),
+            fast: default_fast(),
+            checklist_dirs: default_checklist_dirs(),
+            checklist_files: default_checklist_files(),
+            sources: BTreeMap::new(),
         }
     }
 }
@@ -125,11 +530,39 @@ pub struct MaybeSettings {
     #[serde(default)]
     external_checklists: Vec<RemoteFile>,
     #[serde(default)]
+    required_checklists: Vec<RemoteFile>,
+    #[serde(default)]
     external_templates: Vec<RemoteFile>,
+    full_diff: Option<bool>,
+    artifacts_dir: Option<PathBuf>,
+    show: Option<Show>,
+    fail_on: Option<Severity>,
+    exit_codes: Option<ExitCodes>,
+    #[serde(default)]
+    meta: BTreeMap<String, String>,
+    #[serde(default)]
+    vars: BTreeMap<String, String>,
+    #[serde(default)]
+    only: Vec<Selector>,
+    #[serde(default)]
+    skip: Vec<Selector>,
+    name: Option<String>,
+    changed: Option<String>,
+    changed_only: Option<bool>,
+    fix: Option<bool>,
+    update_baseline: Option<bool>,
+    offline: Option<bool>,
+    otlp_endpoint: Option<String>,
+    home: Option<bool>,
+    strict_undefined: Option<bool>,
+    sync_checklists: Option<bool>,
+    fast: Option<bool>,
+    checklist_dirs: Option<Vec<String>>,
+    checklist_files: Option<Vec<String>>,
 }
 
 impl MaybeSettings {
-    fn to_settings(self) -> Result<Settings> {
+    fn into_settings(self) -> Result<Settings> {
         let Some(user_checklists) = self.user_checklists else {
             bail!("Settings option 'user_checklists' not set");
         };
@@ -138,21 +571,9 @@ impl MaybeSettings {
         };
 
         let (no_read_cache, no_write_cache) = match self.no_cache {
-            Some(no_cache) => {
-                // No cache implies no_read and no_write
-                if no_cache {
-                    (true, true)
-                } else {
-                    let Some(no_read_cache) = self.no_read_cache else {
-                        bail!("Settings option 'no_read_cache' not set");
-                    };
-                    let Some(no_write_cache) = self.no_write_cache else {
-                        bail!("Settings option 'no_write_cache' not set");
-                    };
-                    (no_read_cache, no_write_cache)
-                }
-            }
-            None => {
+            // No cache implies no_read and no_write
+            Some(true) => (true, true),
+            _ => {
                 let Some(no_read_cache) = self.no_read_cache else {
                     bail!("Settings option 'no_read_cache' not set");
                 };
@@ -168,8 +589,65 @@ impl MaybeSettings {
         };
 
         let external_checklists = self.external_checklists;
+        let required_checklists = self.required_checklists;
         let external_templates = self.external_templates;
 
+        let Some(full_diff) = self.full_diff else {
+            bail!("Settings option 'full_diff' not set");
+        };
+
+        let Some(show) = self.show else {
+            bail!("Settings option 'show' not set");
+        };
+
+        let Some(fail_on) = self.fail_on else {
+            bail!("Settings option 'fail_on' not set");
+        };
+
+        let Some(exit_codes) = self.exit_codes else {
+            bail!("Settings option 'exit_codes' not set");
+        };
+
+        let Some(changed_only) = self.changed_only else {
+            bail!("Settings option 'changed_only' not set");
+        };
+
+        let Some(fix) = self.fix else {
+            bail!("Settings option 'fix' not set");
+        };
+
+        let Some(update_baseline) = self.update_baseline else {
+            bail!("Settings option 'update_baseline' not set");
+        };
+
+        let Some(offline) = self.offline else {
+            bail!("Settings option 'offline' not set");
+        };
+
+        let Some(home) = self.home else {
+            bail!("Settings option 'home' not set");
+        };
+
+        let Some(checklist_dirs) = self.checklist_dirs else {
+            bail!("Settings option 'checklist_dirs' not set");
+        };
+
+        let Some(checklist_files) = self.checklist_files else {
+            bail!("Settings option 'checklist_files' not set");
+        };
+
+        let Some(strict_undefined) = self.strict_undefined else {
+            bail!("Settings option 'strict_undefined' not set");
+        };
+
+        let Some(sync_checklists) = self.sync_checklists else {
+            bail!("Settings option 'sync_checklists' not set");
+        };
+
+        let Some(fast) = self.fast else {
+            bail!("Settings option 'fast' not set");
+        };
+
         Ok(Settings {
             user_checklists,
             fail_fast,
@@ -177,7 +655,31 @@ impl MaybeSettings {
             no_write_cache,
             clear_cache,
             external_checklists,
+            required_checklists,
             external_templates,
+            full_diff,
+            artifacts_dir: self.artifacts_dir,
+            show,
+            fail_on,
+            exit_codes,
+            meta: self.meta,
+            vars: self.vars,
+            only: self.only,
+            skip: self.skip,
+            name: self.name,
+            changed: self.changed,
+            changed_only,
+            fix,
+            update_baseline,
+            offline,
+            otlp_endpoint: self.otlp_endpoint,
+            home,
+            strict_undefined,
+            sync_checklists,
+            fast,
+            checklist_dirs,
+            checklist_files,
+            sources: BTreeMap::new(),
         })
     }
 }
@@ -192,7 +694,30 @@ impl MaybeSettings {
             no_cache: None,
             clear_cache: None,
             external_checklists: Vec::new(),
+            required_checklists: Vec::new(),
             external_templates: Vec::new(),
+            full_diff: None,
+            artifacts_dir: None,
+            show: None,
+            fail_on: None,
+            exit_codes: None,
+            meta: BTreeMap::new(),
+            vars: BTreeMap::new(),
+            only: Vec::new(),
+            skip: Vec::new(),
+            name: None,
+            changed: None,
+            changed_only: None,
+            fix: None,
+            update_baseline: None,
+            offline: None,
+            otlp_endpoint: None,
+            home: None,
+            strict_undefined: None,
+            sync_checklists: None,
+            fast: None,
+            checklist_dirs: None,
+            checklist_files: None,
         }
     }
 
@@ -209,10 +734,6 @@ impl MaybeSettings {
             self.no_read_cache = Some(enable);
         }
 
-        if let Some(enable) = layer.no_read_cache {
-            self.no_read_cache = Some(enable);
-        }
-
         if let Some(enable) = layer.no_write_cache {
             self.no_write_cache = Some(enable);
         }
@@ -228,8 +749,88 @@ impl MaybeSettings {
         self.external_checklists
             .append(&mut layer.external_checklists);
 
+        self.required_checklists
+            .append(&mut layer.required_checklists);
+
         self.external_templates
             .append(&mut layer.external_templates);
+
+        if let Some(enable) = layer.full_diff {
+            self.full_diff = Some(enable);
+        }
+
+        if let Some(dir) = layer.artifacts_dir {
+            self.artifacts_dir = Some(dir);
+        }
+
+        if let Some(show) = layer.show {
+            self.show = Some(show);
+        }
+
+        if let Some(fail_on) = layer.fail_on {
+            self.fail_on = Some(fail_on);
+        }
+
+        if let Some(exit_codes) = layer.exit_codes {
+            self.exit_codes = Some(exit_codes);
+        }
+
+        self.meta.append(&mut layer.meta);
+        self.vars.append(&mut layer.vars);
+        self.only.append(&mut layer.only);
+        self.skip.append(&mut layer.skip);
+
+        if let Some(name) = layer.name {
+            self.name = Some(name);
+        }
+
+        if let Some(base_ref) = layer.changed {
+            self.changed = Some(base_ref);
+        }
+
+        if let Some(enable) = layer.changed_only {
+            self.changed_only = Some(enable);
+        }
+
+        if let Some(enable) = layer.fix {
+            self.fix = Some(enable);
+        }
+
+        if let Some(enable) = layer.update_baseline {
+            self.update_baseline = Some(enable);
+        }
+
+        if let Some(enable) = layer.offline {
+            self.offline = Some(enable);
+        }
+
+        if let Some(value) = layer.otlp_endpoint {
+            self.otlp_endpoint = Some(value);
+        }
+
+        if let Some(enable) = layer.home {
+            self.home = Some(enable);
+        }
+
+        if let Some(enable) = layer.strict_undefined {
+            self.strict_undefined = Some(enable);
+        }
+
+        if let Some(enable) = layer.sync_checklists {
+            self.sync_checklists = Some(enable);
+        }
+
+        if let Some(enable) = layer.fast {
+            self.fast = Some(enable);
+        }
+
+        if let Some(dirs) = layer.checklist_dirs {
+            self.checklist_dirs = Some(dirs);
+        }
+
+        if let Some(files) = layer.checklist_files {
+            self.checklist_files = Some(files);
+        }
     }
 
     pub fn from_args(args: Cli) -> Self {
@@ -262,6 +863,53 @@ impl MaybeSettings {
         layer.external_checklists = args.external_checklist;
         layer.external_templates = args.external_template;
 
+        if args.full_diff {
+            layer.full_diff = Some(true);
+        }
+
+        layer.artifacts_dir = args.artifacts_dir;
+        layer.show = args.show;
+        layer.fail_on = args.fail_on;
+        layer.meta = args.meta.into_iter().collect();
+        layer.vars = args.vars.into_iter().collect();
+        layer.only = args.only;
+        layer.skip = args.skip;
+        layer.changed = args.changed;
+
+        if args.changed_only {
+            layer.changed_only = Some(true);
+        }
+
+        if args.fix {
+            layer.fix = Some(true);
+        }
+
+        if args.update_baseline {
+            layer.update_baseline = Some(true);
+        }
+
+        if args.offline {
+            layer.offline = Some(true);
+        }
+
+        layer.otlp_endpoint = args.otlp_endpoint;
+
+        if args.home {
+            layer.home = Some(true);
+        }
+
+        if args.strict_undefined {
+            layer.strict_undefined = Some(true);
+        }
+
+        if args.sync_checklists {
+            layer.sync_checklists = Some(true);
+        }
+
+        if args.fast {
+            layer.fast = Some(true);
+        }
+
         layer
     }
 
@@ -269,35 +917,121 @@ impl MaybeSettings {
         let mut layer = Self::empty();
 
         let key = "USER_CHECKLISTS";
-        if let Ok(user_checklists) = env::var(prefix_key(key)) {
+        if env::var(prefix_key(key)).is_ok() {
             layer.user_checklists = Some(true);
         }
 
         let key = "FAIL_FAST";
-        if let Ok(fail_fast) = env::var(prefix_key(key)) {
+        if env::var(prefix_key(key)).is_ok() {
             layer.fail_fast = Some(true);
         }
 
         let key = "NO_CACHE";
-        if let Ok(no_cache) = env::var(prefix_key(key)) {
+        if env::var(prefix_key(key)).is_ok() {
             layer.no_cache = Some(true);
         }
 
         let key = "NO_READ_CACHE";
-        if let Ok(no_read_cache) = env::var(prefix_key(key)) {
+        if env::var(prefix_key(key)).is_ok() {
             layer.no_read_cache = Some(true);
         }
 
         let key = "NO_WRITE_CACHE";
-        if let Ok(no_write_cache) = env::var(prefix_key(key)) {
+        if env::var(prefix_key(key)).is_ok() {
             layer.no_write_cache = Some(true);
         }
 
         let key = "CLEAR_CACHE";
-        if let Ok(clear_cache) = env::var(prefix_key(key)) {
+        if env::var(prefix_key(key)).is_ok() {
             layer.clear_cache = Some(true);
         }
 
+        let key = "FULL_DIFF";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.full_diff = Some(true);
+        }
+
+        let key = "ARTIFACTS_DIR";
+        if let Ok(artifacts_dir) = env::var(prefix_key(key)) {
+            layer.artifacts_dir = Some(PathBuf::from(artifacts_dir));
+        }
+
+        let key = "SHOW";
+        if let Ok(show) = env::var(prefix_key(key)) {
+            let Ok(show) = Show::from_str(&show, true) else {
+                bail!("Invalid value '{show}' for {}", prefix_key(key));
+            };
+            layer.show = Some(show);
+        }
+
+        let key = "FAIL_ON";
+        if let Ok(fail_on) = env::var(prefix_key(key)) {
+            let Ok(fail_on) = Severity::from_str(&fail_on, true) else {
+                bail!("Invalid value '{fail_on}' for {}", prefix_key(key));
+            };
+            layer.fail_on = Some(fail_on);
+        }
+
+        let key = "CHANGED";
+        if let Ok(base_ref) = env::var(prefix_key(key)) {
+            layer.changed = Some(base_ref);
+        }
+
+        let key = "CHANGED_ONLY";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.changed_only = Some(true);
+        }
+
+        let key = "FIX";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.fix = Some(true);
+        }
+
+        let key = "UPDATE_BASELINE";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.update_baseline = Some(true);
+        }
+
+        let key = "OFFLINE";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.offline = Some(true);
+        }
+
+        let key = "OTLP_ENDPOINT";
+        if let Ok(value) = env::var(prefix_key(key)) {
+            layer.otlp_endpoint = Some(value);
+        }
+
+        let key = "HOME_MODE";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.home = Some(true);
+        }
+
+        let key = "CHECKLIST_DIRS";
+        if let Ok(value) = env::var(prefix_key(key)) {
+            layer.checklist_dirs = Some(value.split(',').map(String::from).collect());
+        }
+
+        let key = "CHECKLIST_FILES";
+        if let Ok(value) = env::var(prefix_key(key)) {
+            layer.checklist_files = Some(value.split(',').map(String::from).collect());
+        }
+
+        let key = "STRICT_UNDEFINED";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.strict_undefined = Some(true);
+        }
+
+        let key = "SYNC_CHECKLISTS";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.sync_checklists = Some(true);
+        }
+
+        let key = "FAST";
+        if env::var(prefix_key(key)).is_ok() {
+            layer.fast = Some(true);
+        }
+
         Ok(layer)
     }
 }
@@ -312,7 +1046,30 @@ impl Default for MaybeSettings {
             no_cache: Some(default_no_cache()),
             clear_cache: Some(default_clear_cache()),
             external_checklists: default_external_checklists(),
+            required_checklists: Vec::new(),
             external_templates: default_external_templates(),
+            full_diff: Some(default_full_diff()),
+            artifacts_dir: None,
+            show: Some(Show::default()),
+            fail_on: Some(Severity::default()),
+            exit_codes: Some(ExitCodes::default()),
+            meta: BTreeMap::new(),
+            vars: BTreeMap::new(),
+            only: Vec::new(),
+            skip: Vec::new(),
+            name: None,
+            changed: None,
+            changed_only: Some(default_changed_only()),
+            fix: Some(default_fix()),
+            update_baseline: Some(default_update_baseline()),
+            offline: Some(default_offline()),
+            otlp_endpoint: None,
+            home: Some(default_home()),
+            strict_undefined: Some(default_strict_undefined()),
+            sync_checklists: Some(default_sync_checklists()),
+            fast: Some(default_fast()),
+            checklist_dirs: Some(default_checklist_dirs()),
+            checklist_files: Some(default_checklist_files()),
         }
     }
 }
@@ -322,9 +1079,21 @@ fn prefix_key(key: &str) -> String {
     format!("{prefix}_{key}")
 }
 
-#[derive(Default)]
 pub struct SettingsBuilder {
     settings: MaybeSettings,
+    /// Layers that set each setting
+    sources: BTreeMap<String, Vec<Source>>,
+}
+
+impl Default for SettingsBuilder {
+    fn default() -> Self {
+        let mut builder = Self {
+            settings: MaybeSettings::empty(),
+            sources: BTreeMap::new(),
+        };
+        builder.layer(MaybeSettings::default(), Source::Default);
+        builder
+    }
 }
 
 impl SettingsBuilder {
@@ -332,26 +1101,93 @@ impl SettingsBuilder {
         Self::default()
     }
 
-    pub fn build(self) -> Result<Settings> {
-        self.settings.to_settings()
+    pub fn build(mut self) -> Result<Settings> {
+        // No cache implies no_read and no_write
+        if self.settings.no_cache == Some(true)
+            && let Some(sources) = self.sources.get("no_cache").cloned()
+        {
+            self.sources
+                .insert(String::from("no_read_cache"), sources.clone());
+            self.sources.insert(String::from("no_write_cache"), sources);
+        }
+        let mut settings = self.settings.into_settings()?;
+        settings.sources = self.sources;
+        Ok(settings)
+    }
+
+    /// Layer `layer` over the settings so far, recording `source` for each setting it sets
+    fn layer(&mut self, layer: MaybeSettings, source: Source) {
+        if let Ok(serde_json::Value::Object(values)) = serde_json::to_value(&layer) {
+            for (name, value) in values {
+                let set = match &value {
+                    serde_json::Value::Null => false,
+                    serde_json::Value::Array(values) => !values.is_empty(),
+                    serde_json::Value::Object(values) => !values.is_empty(),
+                    _ => true,
+                };
+                if !set {
+                    continue;
+                }
+                if !APPENDED_SETTINGS.contains(&name.as_str()) {
+                    self.sources.remove(&name);
+                }
+                self.sources.entry(name).or_default().push(source.clone());
+            }
+        }
+        self.settings.layer(layer);
     }
 
     pub fn env_layer(mut self) -> Result<Self> {
         let layer = MaybeSettings::from_env()?;
-        self.settings.layer(layer);
+        self.layer(layer, Source::Env);
         Ok(self)
     }
 
     pub fn config_layer(mut self, config_file: &Path) -> Result<Self> {
         let contents = fs::read_to_string(config_file)?;
         let layer: MaybeSettings = toml::from_str(&contents)?;
-        self.settings.layer(layer);
+        self.layer(layer, Source::ConfigFile(config_file.to_path_buf()));
+        Ok(self)
+    }
+
+    /// Layer the project's own config file, if it has one
+    pub fn project_config_layer(mut self, project_dir: &Path) -> Result<Self> {
+        let config_file = project_dir.join(PROJECT_CONFIG_FILE_NAME);
+        if !config_file.is_file() {
+            return Ok(self);
+        }
+        debug!("Reading project config {}", config_file.display());
+        let contents = fs::read_to_string(&config_file)?;
+        let layer: MaybeSettings = toml::from_str(&contents)?;
+        if !layer.required_checklists.is_empty() {
+            bail!(
+                "'required_checklists' can only be set in the user config, not {}",
+                config_file.display()
+            );
+        }
+        // Nor can the project keep the required checks from running or failing the run
+        if !self.settings.required_checklists.is_empty() {
+            let overrides = [
+                ("only", !layer.only.is_empty()),
+                ("skip", !layer.skip.is_empty()),
+                ("exit_codes", layer.exit_codes.is_some()),
+                ("fail_on", layer.fail_on.is_some()),
+                ("update_baseline", layer.update_baseline.is_some()),
+            ];
+            if let Some((key, _)) = overrides.iter().find(|(_, set)| *set) {
+                bail!(
+                    "'{key}' can't be set in {} when there are required checklists",
+                    config_file.display()
+                );
+            }
+        }
+        self.layer(layer, Source::ConfigFile(config_file));
         Ok(self)
     }
 
     pub fn arg_layer(mut self, args: Cli) -> Self {
         let layer = MaybeSettings::from_args(args);
-        self.settings.layer(layer);
+        self.layer(layer, Source::Cli);
         self
     }
 
@@ -390,6 +1226,11 @@ impl SettingsBuilder {
         self
     }
 
+    pub fn add_required_checklist(mut self, checklist: RemoteFile) -> Self {
+        self.settings.required_checklists.push(checklist);
+        self
+    }
+
     pub fn add_external_template(mut self, template: RemoteFile) -> Self {
         self.settings.external_templates.push(template);
         self
@@ -404,4 +1245,162 @@ impl SettingsBuilder {
         self.settings.external_templates = templates;
         self
     }
+
+    pub fn full_diff(mut self, enable: bool) -> Self {
+        self.settings.full_diff = Some(enable);
+        self
+    }
+
+    pub fn artifacts_dir(mut self, dir: PathBuf) -> Self {
+        self.settings.artifacts_dir = Some(dir);
+        self
+    }
+
+    pub fn show(mut self, show: Show) -> Self {
+        self.settings.show = Some(show);
+        self
+    }
+
+    pub fn fail_on(mut self, fail_on: Severity) -> Self {
+        self.settings.fail_on = Some(fail_on);
+        self
+    }
+
+    pub fn exit_codes(mut self, exit_codes: ExitCodes) -> Self {
+        self.settings.exit_codes = Some(exit_codes);
+        self
+    }
+
+    pub fn name(mut self, name: String) -> Self {
+        self.settings.name = Some(name);
+        self
+    }
+
+    pub fn changed(mut self, base_ref: String) -> Self {
+        self.settings.changed = Some(base_ref);
+        self
+    }
+
+    pub fn changed_only(mut self, enable: bool) -> Self {
+        self.settings.changed_only = Some(enable);
+        self
+    }
+
+    pub fn fix(mut self, enable: bool) -> Self {
+        self.settings.fix = Some(enable);
+        self
+    }
+
+    pub fn update_baseline(mut self, enable: bool) -> Self {
+        self.settings.update_baseline = Some(enable);
+        self
+    }
+
+    pub fn offline(mut self, enable: bool) -> Self {
+        self.settings.offline = Some(enable);
+        self
+    }
+
+    pub fn otlp_endpoint(mut self, value: String) -> Self {
+        self.settings.otlp_endpoint = Some(value);
+        self
+    }
+
+    pub fn home(mut self, enable: bool) -> Self {
+        self.settings.home = Some(enable);
+        self
+    }
+
+    pub fn strict_undefined(mut self, enable: bool) -> Self {
+        self.settings.strict_undefined = Some(enable);
+        self
+    }
+
+    pub fn sync_checklists(mut self, enable: bool) -> Self {
+        self.settings.sync_checklists = Some(enable);
+        self
+    }
+
+    pub fn fast(mut self, enable: bool) -> Self {
+        self.settings.fast = Some(enable);
+        self
+    }
+
+    pub fn checklist_dirs(mut self, dirs: Vec<String>) -> Self {
+        self.settings.checklist_dirs = Some(dirs);
+        self
+    }
+
+    pub fn checklist_files(mut self, files: Vec<String>) -> Self {
+        self.settings.checklist_files = Some(files);
+        self
+    }
+
+    pub fn add_meta(mut self, key: String, value: String) -> Self {
+        self.settings.meta.insert(key, value);
+        self
+    }
+
+    pub fn add_var(mut self, key: String, value: String) -> Self {
+        self.settings.vars.insert(key, value);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_of_layered_settings() {
+        let path = env::temp_dir().join(format!("checklints-settings-{}.toml", std::process::id()));
+        fs::write(&path, "fail_fast = true\nmeta = { a = \"1\" }\n").unwrap();
+        let settings = Settings::builder().config_layer(&path).unwrap().build();
+        fs::remove_file(&path).unwrap();
+        let settings = settings.unwrap();
+
+        let config = Source::ConfigFile(path);
+        assert_eq!(settings.sources("fail_fast"), std::slice::from_ref(&config));
+        assert_eq!(settings.sources("full_diff"), [Source::Default]);
+        assert_eq!(settings.sources("meta"), [config]);
+        assert!(settings.sources("vars").is_empty());
+        assert!(settings
+            .effective()
+            .unwrap()
+            .contains("fail_fast = true  # config file"));
+    }
+
+    #[test]
+    fn project_config_cannot_disable_required_checklists() {
+        let dir = env::temp_dir().join(format!("checklints-required-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let required: RemoteFile = "https://example.com/required.toml".parse().unwrap();
+        let overrides = [
+            "only = [\"checklist:project.toml\"]",
+            "skip = [\"checklist:required.toml\"]",
+            "exit_codes = { fail = 0 }",
+            "fail_on = \"warning\"",
+            "update_baseline = true",
+        ];
+        for config in overrides {
+            fs::write(dir.join(PROJECT_CONFIG_FILE_NAME), config).unwrap();
+            let without_required = Settings::builder().project_config_layer(&dir);
+            assert!(without_required.is_ok(), "{config}");
+            let with_required = Settings::builder()
+                .add_required_checklist(required.clone())
+                .project_config_layer(&dir);
+            assert!(with_required.is_err(), "{config}");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn default_config_parses() {
+        let config = default_config().unwrap();
+        toml::from_str::<MaybeSettings>(&config).unwrap();
+
+        let uncommented = config.replace("# name = ", "name = ");
+        let layer: MaybeSettings = toml::from_str(&uncommented).unwrap();
+        assert_eq!(layer.name.as_deref(), Some("my-project"));
+    }
 }
@@ -0,0 +1,180 @@
+//! Filters and functions available to templates, on top of stock minijinja
+use crate::walk;
+use minijinja::value::{Value, ValueKind};
+use minijinja::{Environment, Error, ErrorKind};
+use semver::{BuildMetadata, Prerelease, Version};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::{env, fs};
+
+fn invalid(msg: String) -> Error {
+    Error::new(ErrorKind::InvalidOperation, msg)
+}
+
+/// `read_file("path")`: contents of a file, relative to the working dir
+fn read_file(path: String) -> Result<String, Error> {
+    fs::read_to_string(walk::expand_tilde(&path))
+        .map_err(|e| invalid(format!("can't read '{path}': {e}")))
+}
+
+/// `env("NAME", default)`: value of an env var, or `default` when it isn't set
+fn env_var(name: String, default: Option<String>) -> Result<String, Error> {
+    match (env::var(&name), default) {
+        (Ok(value), _) => Ok(value),
+        (Err(_), Some(default)) => Ok(default),
+        (Err(_), None) => Err(invalid(format!("env var '{name}' is not set"))),
+    }
+}
+
+/// `now(format)`: current local time, RFC 3339 unless a strftime `format` is given
+fn now(format: Option<String>) -> String {
+    let now = chrono::Local::now();
+    match format {
+        Some(format) => now.format(&format).to_string(),
+        None => now.to_rfc3339(),
+    }
+}
+
+/// `"1.2.3" | semver_bump("minor")`: next major, minor or (default) patch version
+fn semver_bump(version: String, part: Option<String>) -> Result<String, Error> {
+    let mut version = Version::parse(&version).map_err(|e| invalid(format!("'{version}': {e}")))?;
+    match part.as_deref().unwrap_or("patch") {
+        "major" => {
+            version.major += 1;
+            version.minor = 0;
+            version.patch = 0;
+        }
+        "minor" => {
+            version.minor += 1;
+            version.patch = 0;
+        }
+        "patch" => version.patch += 1,
+        part => {
+            return Err(invalid(format!(
+                "unknown version part '{part}', expected major, minor or patch"
+            )));
+        }
+    }
+    version.pre = Prerelease::EMPTY;
+    version.build = BuildMetadata::EMPTY;
+    Ok(version.to_string())
+}
+
+/// `value | to_toml`: a map as a TOML document, anything else as a TOML value
+fn to_toml(value: Value) -> Result<String, Error> {
+    let toml = if value.kind() == ValueKind::Map {
+        toml::to_string(&value).map_err(|e| invalid(e.to_string()))?
+    } else {
+        toml::Value::try_from(&value)
+            .map_err(|e| invalid(e.to_string()))?
+            .to_string()
+    };
+    Ok(toml)
+}
+
+/// `json_string | from_json`: parsed JSON, e.g. a fact holding a command's JSON output
+fn from_json(value: String) -> Result<Value, Error> {
+    let json: serde_json::Value =
+        serde_json::from_str(&value).map_err(|e| invalid(format!("invalid JSON: {e}")))?;
+    Ok(Value::from_serialize(json))
+}
+
+/// `value | hash("blake3")`: hex digest, sha256 unless another algorithm is given
+fn hash(value: String, algorithm: Option<String>) -> Result<String, Error> {
+    match algorithm.as_deref().unwrap_or("sha256") {
+        "sha256" => Ok(hex::encode(Sha256::digest(value.as_bytes()))),
+        "blake3" => Ok(blake3::hash(value.as_bytes()).to_hex().to_string()),
+        algorithm => Err(invalid(format!(
+            "unknown hash algorithm '{algorithm}', expected sha256 or blake3"
+        ))),
+    }
+}
+
+/// Register checklints' filters and functions
+pub fn add_builtins(env: &mut Environment) {
+    env.add_function("read_file", read_file);
+    env.add_function("env", env_var);
+    env.add_function("now", now);
+    env.add_filter("semver_bump", semver_bump);
+    env.add_filter("to_toml", to_toml);
+    env.add_filter("from_json", from_json);
+    env.add_filter("hash", hash);
+}
+
+/// Render a template. When it fails on an undefined value (with strict undefined behavior), the
+/// error names the variables the template uses that nothing provides
+pub fn render(
+    env: &Environment,
+    name: &str,
+    vars: &HashMap<String, String>,
+) -> anyhow::Result<String> {
+    let template = env.get_template(name)?;
+    let e = match template.render(vars) {
+        Ok(contents) => return Ok(contents),
+        Err(e) => e,
+    };
+    if e.kind() != ErrorKind::UndefinedError {
+        return Err(e.into());
+    }
+
+    let mut undefined = template
+        .undeclared_variables(false)
+        .into_iter()
+        .filter(|var| !vars.contains_key(var) && !env.globals().any(|(global, _)| global == var))
+        .map(|var| format!("'{var}'"))
+        .collect::<Vec<_>>();
+    if undefined.is_empty() {
+        return Err(e.into());
+    }
+    undefined.sort();
+    Err(anyhow::Error::new(e).context(format!(
+        "Template '{name}' uses undefined variable {}",
+        undefined.join(", ")
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use minijinja::context;
+
+    fn render(source: &str, ctx: Value) -> String {
+        let mut env = Environment::new();
+        add_builtins(&mut env);
+        env.render_str(source, ctx).unwrap()
+    }
+
+    #[test]
+    fn semver_bump_resets_lower_parts() {
+        let ctx = context! { v => "1.2.3-rc.1" };
+        assert_eq!(render("{{ v | semver_bump }}", ctx.clone()), "1.2.4");
+        assert_eq!(
+            render("{{ v | semver_bump('minor') }}", ctx.clone()),
+            "1.3.0"
+        );
+        assert_eq!(render("{{ v | semver_bump('major') }}", ctx), "2.0.0");
+    }
+
+    #[test]
+    fn to_toml_renders_tables_and_values() {
+        let ctx = context! { t => context! { name => "x", n => 1 }, s => "a" };
+        assert_eq!(
+            render("{{ t | to_toml }}", ctx.clone()),
+            "n = 1\nname = \"x\"\n"
+        );
+        assert_eq!(render("{{ s | to_toml }}", ctx), "\"a\"");
+    }
+
+    #[test]
+    fn strict_render_names_undefined_variables() {
+        let mut env = Environment::new();
+        add_builtins(&mut env);
+        env.set_undefined_behavior(minijinja::UndefinedBehavior::Strict);
+        env.add_template("t", "{{ a }} {{ b }} {{ now() }}")
+            .unwrap();
+
+        let vars = HashMap::from([(String::from("a"), String::from("1"))]);
+        let e = super::render(&env, "t", &vars).unwrap_err();
+        assert_eq!(e.to_string(), "Template 't' uses undefined variable 'b'");
+    }
+}
@@ -1,18 +1,49 @@
-use crate::command::run_command_line;
-use crate::INDENT;
-use anyhow::{bail, Result};
-use colored::Colorize;
+use crate::annotate::{self, Location};
+use crate::baseline;
+use crate::checks::{
+    BinariesCheck, BudgetCheck, DuplicatesCheck, FreshnessCheck, FrontMatterCheck, GitignoreCheck,
+    GroupCheck, ImageCheck, LineLimitsCheck, ManualCheck, Metrics, PlatformCheck,
+    RustWorkspaceCheck, ScriptCheck, SecretsCheck, SpellingCheck, SqlCheck, SubprojectCheck,
+    TimeWindowCheck, WhitespaceCheck,
+};
+use crate::command::{run_command, run_command_line};
+use crate::region;
+use crate::settings::Settings;
+use crate::template;
+use crate::walk;
+use crate::{INDENT, THIS_CRATE_NAME};
+use anyhow::{bail, Context, Result};
+use colored::{ColoredString, Colorize};
 use different::{line_diff, Diff, DiffSettings};
+use indexmap::IndexMap;
 use log::debug;
 use minijinja::Environment;
-use reqwest::StatusCode;
+use regex::Regex;
+use reqwest::blocking::{Client, RequestBuilder};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Method, StatusCode};
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DisplayFromStr};
-use std::collections::HashMap;
+use serde_json_path::JsonPath;
+use serde_with::{
+    serde_as, DeserializeFromStr, DisplayFromStr, DurationMilliSeconds, SerializeDisplay,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsString;
 use std::fmt::Display;
+use std::fs::OpenOptions;
+use std::io::{self, IsTerminal, Write};
+use std::net::{TcpStream, ToSocketAddrs};
 use std::path::{Path, PathBuf};
-use std::time::Duration;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
 use std::{env, fs};
+use uuid::Uuid;
+
+/// Lines of a diff (or other long failure reason) printed before truncating
+const MAX_DIFF_LINES: usize = 40;
+
+const MAX_SLUG_LEN: usize = 100;
 
 fn default_exit_code() -> i32 {
     exitcode::OK
@@ -53,8 +84,21 @@ fn dir_compare(
     str_compare(&expected, &actual, diff_settings).map(|x| x.to_string())
 }
 
+/// Standard methods, matched case-insensitively
+const HTTP_METHODS: [(&str, HttpMethod); 9] = [
+    ("GET", HttpMethod::Get),
+    ("POST", HttpMethod::Post),
+    ("PUT", HttpMethod::Put),
+    ("DELETE", HttpMethod::Delete),
+    ("HEAD", HttpMethod::Head),
+    ("CONNECT", HttpMethod::Connect),
+    ("OPTIONS", HttpMethod::Options),
+    ("TRACE", HttpMethod::Trace),
+    ("PATCH", HttpMethod::Patch),
+];
+
 #[derive(
-    Debug, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, derive_more::Display, Hash,
+    Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, SerializeDisplay, DeserializeFromStr,
 )]
 pub enum HttpMethod {
     Get,
@@ -62,11 +106,52 @@ pub enum HttpMethod {
     Put,
     Delete,
     Head,
-    Deleate,
     Connect,
     Options,
     Trace,
     Patch,
+    /// Nonstandard method (e.g. PURGE), which must be uppercase
+    Custom(String),
+}
+
+impl FromStr for HttpMethod {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some((_, method)) = HTTP_METHODS
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        {
+            return Ok(method.clone());
+        }
+
+        // Uppercase is the convention for custom methods, and catches typos of standard ones
+        let is_custom = !s.is_empty()
+            && s.chars()
+                .all(|c| c.is_ascii_uppercase() || c == '-' || c == '_')
+            && Method::from_bytes(s.as_bytes()).is_ok();
+        if is_custom {
+            return Ok(Self::Custom(s.to_string()));
+        }
+
+        let standard = HTTP_METHODS.map(|(name, _)| name).join(", ");
+        Err(format!(
+            "unknown HTTP method '{s}', expected one of {standard}, or an uppercase custom method"
+        ))
+    }
+}
+
+impl Display for HttpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Self::Custom(method) = self {
+            return write!(f, "{method}");
+        }
+        let (name, _) = HTTP_METHODS
+            .iter()
+            .find(|(_, method)| method == self)
+            .unwrap();
+        write!(f, "{name}")
+    }
 }
 
 pub trait CheckTrait {
@@ -81,8 +166,51 @@ pub trait CheckTrait {
     fn describe(&self) -> String;
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum TemplateMode {
+    /// File must match the template exactly
+    #[default]
+    Exact,
+    /// Every line of the template must be somewhere in the file
+    Subset,
+    /// Every line of the template must be in the file, in the same order
+    Ordered,
+}
+
+impl TemplateMode {
+    /// Template lines missing from `actual`. Blank template lines are ignored
+    fn missing_lines<'a>(&self, expected: &'a str, actual: &str) -> Vec<&'a str> {
+        let expected = expected.lines().filter(|line| !line.trim().is_empty());
+        match self {
+            Self::Exact => Vec::new(),
+            Self::Subset => {
+                let actual = actual.lines().collect::<HashSet<_>>();
+                expected.filter(|line| !actual.contains(line)).collect()
+            }
+            Self::Ordered => {
+                // Each line is looked for after the last one found, a missing one doesn't move it
+                let actual = actual.lines().collect::<Vec<_>>();
+                let mut next = 0;
+                expected
+                    .filter(
+                        |line| match actual[next..].iter().position(|actual| actual == line) {
+                            Some(i) => {
+                                next += i + 1;
+                                false
+                            }
+                            None => true,
+                        },
+                    )
+                    .collect()
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct FileCheck {
+    /// Path to the file, or a glob matching several files
     path: PathBuf,
 
     /// Exact contents of file
@@ -95,63 +223,96 @@ pub struct FileCheck {
     /// Template to check against
     /// Path relative to checklist file
     template: Option<PathBuf>,
+
+    /// How the populated template is compared with the file
+    #[serde(default)]
+    template_mode: TemplateMode,
+
+    /// Only compare the template with the managed region of the file, the lines between
+    /// 'BEGIN <region>' and 'END <region>' markers (e.g. '# BEGIN checklints')
+    region: Option<String>,
+
+    /// Variables for populating the template, on top of (and overriding) the facts
+    #[serde(default)]
+    vars: BTreeMap<String, String>,
+
+    /// Also match gitignored files when `path` is a glob
+    #[serde(default)]
+    include_ignored: bool,
 }
 
 impl FileCheck {
     pub fn path(&self) -> &Path {
         &self.path
     }
-}
 
-impl CheckTrait for FileCheck {
-    fn describe(&self) -> String {
-        let mut s = format!("File {}: must exist", self.path.display());
+    pub fn is_glob(&self) -> bool {
+        walk::is_glob(&self.path.to_string_lossy())
+    }
 
-        if !self.contains.is_empty() {
-            s.push_str(&format!(", must contain {:?}", self.contains));
-        }
+    pub fn vars(&self) -> &BTreeMap<String, String> {
+        &self.vars
+    }
 
-        if let Some(contents) = &self.contents {
-            s.push_str(&format!(", contents must exactly match {:?}", contents));
-        }
+    pub fn template(&self) -> Option<&Path> {
+        self.template.as_deref()
+    }
 
-        s
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
     }
 
-    fn do_check(
+    /// Facts with this check's own variables applied
+    fn template_vars(&self, facts: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut vars = facts.clone();
+        vars.extend(self.vars.clone());
+        vars
+    }
+
+    fn check_file(
         &self,
+        path: &Path,
         diff_settings: &DiffSettings,
         env: &Environment,
         this_file_path: &Path,
         vars: &HashMap<String, String>,
     ) -> Result<Status> {
-        if !self.path.is_file() {
+        if !path.is_file() {
             return Ok(Status::fail(
                 String::from("Path is not a valid file"),
-                Some(self.path.display().to_string()),
+                Some(path.display().to_string()),
             ));
         }
 
-        let actual_contents = fs::read_to_string(&self.path)?;
+        let actual_contents = fs::read_to_string(path)?;
+        let mut results = Vec::new();
 
         if let Some(expected_contents) = &self.contents {
-            if let Some(diff) = str_compare(expected_contents, &actual_contents, diff_settings) {
-                return Ok(Status::fail(
-                    "Contents differ".to_string(),
-                    Some(diff.to_string()),
-                ));
-            }
+            let status = match str_compare(expected_contents, &actual_contents, diff_settings) {
+                Some(diff) => Status::fail("Contents differ".to_string(), Some(diff.to_string()))
+                    .with_location(Location::new(
+                        path,
+                        annotate::first_difference(expected_contents, &actual_contents),
+                    )),
+                None => Status::new(false, StatusStatus::Pass),
+            };
+            results.push((String::from("contents match"), status));
         }
 
-        if !self.contains.is_empty() {
-            for expected_fragment in &self.contains {
-                if !actual_contents.contains(expected_fragment) {
-                    return Ok(Status::fail(
-                        String::from("Expected fragment not found in file"),
-                        Some(format!("{}\n{expected_fragment}", self.path.display())),
-                    ));
-                }
-            }
+        for expected_fragment in &self.contains {
+            let status = if actual_contents.contains(expected_fragment) {
+                Status::new(false, StatusStatus::Pass)
+            } else {
+                Status::fail(
+                    String::from("Expected fragment not found in file"),
+                    Some(format!("{}\n{expected_fragment}", path.display())),
+                )
+                .with_location(Location::new(
+                    path,
+                    annotate::nearest_line(expected_fragment, &actual_contents),
+                ))
+            };
+            results.push((format!("contains {expected_fragment:?}"), status));
         }
 
         if let Some(template) = &self.template {
@@ -163,34 +324,108 @@ impl CheckTrait for FileCheck {
             };
 
             let template_name = &template.display().to_string();
-            let templ = env.get_template(template_name)?;
             debug!(
                 "Checking '{}' against template '{}'",
-                self.path.display(),
+                path.display(),
                 template_name
             );
 
-            let expected = templ.render(vars)?; // TODO
-            if let Some(diff) = str_compare(&expected, &actual_contents, diff_settings) {
-                return Ok(Status::fail(
-                    String::from("Populated template does not match file"),
-                    Some(diff.to_string()),
-                ));
-            }
+            let expected = template::render(env, template_name, &self.template_vars(vars))?; // TODO
+            let (actual, line_offset) = match &self.region {
+                Some(name) => match region::find(&actual_contents, name) {
+                    Some(found) => (&actual_contents[found.range], found.line_offset),
+                    None => {
+                        let status = Status::fail(
+                            format!("Managed region '{name}' not found"),
+                            Some(format!("expected 'BEGIN {name}' and 'END {name}' lines")),
+                        );
+                        results.push((format!("matches template {template_name}"), status));
+                        return Ok(combine(results, "assertions"));
+                    }
+                },
+                None => (actual_contents.as_str(), 0),
+            };
+            // Locations within the region, as lines of the whole file
+            let location =
+                |(line, column): (usize, usize)| Location::new(path, (line + line_offset, column));
+
+            let status = if self.template_mode == TemplateMode::Exact {
+                match str_compare(&expected, actual, diff_settings) {
+                    Some(diff) => Status::fail(
+                        String::from("Populated template does not match file"),
+                        Some(diff.to_string()),
+                    )
+                    .with_location(location(annotate::first_difference(&expected, actual))),
+                    None => Status::new(false, StatusStatus::Pass),
+                }
+            } else {
+                let missing = self.template_mode.missing_lines(&expected, actual);
+                if missing.is_empty() {
+                    Status::new(false, StatusStatus::Pass)
+                } else {
+                    let main = match self.template_mode {
+                        TemplateMode::Ordered => "Populated template lines missing or out of order",
+                        _ => "Populated template lines missing from file",
+                    };
+                    Status::fail(String::from(main), Some(missing.join("\n")))
+                        .with_location(location(annotate::nearest_line(missing[0], actual)))
+                }
+            };
+            results.push((format!("matches template {template_name}"), status));
         }
 
-        Ok(Status::new(false, StatusStatus::Pass))
+        Ok(combine(results, "assertions"))
     }
 }
 
-fn dir_contents(dir: &Path) -> Result<Vec<PathBuf>> {
-    let mut dirs = Vec::new();
-    for entry in fs::read_dir(dir)? {
-        let entry = entry?;
-        let path = entry.path();
-        dirs.push(path);
+impl CheckTrait for FileCheck {
+    fn describe(&self) -> String {
+        let mut s = if self.is_glob() {
+            format!("Files matching {}: must exist", self.path.display())
+        } else {
+            format!("File {}: must exist", self.path.display())
+        };
+
+        if !self.contains.is_empty() {
+            s.push_str(&format!(", must contain {:?}", self.contains));
+        }
+
+        if let Some(contents) = &self.contents {
+            s.push_str(&format!(", contents must exactly match {:?}", contents));
+        }
+
+        s
+    }
+
+    fn do_check(
+        &self,
+        diff_settings: &DiffSettings,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        if !self.is_glob() {
+            let path = walk::expand_tilde(&self.path.to_string_lossy());
+            return self.check_file(&path, diff_settings, env, this_file_path, vars);
+        }
+
+        let pattern = self.path.to_string_lossy();
+        let paths = walk::glob(&pattern, self.include_ignored)?;
+        if paths.is_empty() {
+            return Ok(Status::fail(
+                String::from("No files match glob"),
+                Some(pattern.to_string()),
+            ));
+        }
+
+        let mut results = Vec::new();
+        for path in paths {
+            let status = self.check_file(&path, diff_settings, env, this_file_path, vars)?;
+            results.push((path.display().to_string(), status));
+        }
+
+        Ok(combine(results, "files"))
     }
-    Ok(dirs)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
@@ -206,6 +441,10 @@ pub struct DirectoryCheck {
     /// TODO: also consider making enum
     #[serde(default)]
     contains: Vec<String>,
+
+    /// Also consider gitignored entries of the directory
+    #[serde(default)]
+    include_ignored: bool,
 }
 
 impl CheckTrait for DirectoryCheck {
@@ -229,25 +468,24 @@ impl CheckTrait for DirectoryCheck {
     fn do_check(
         &self,
         diff_settings: &DiffSettings,
-        env: &Environment,
-        this_file_path: &Path,
-        vars: &HashMap<String, String>,
+        _env: &Environment,
+        _this_file_path: &Path,
+        _vars: &HashMap<String, String>,
     ) -> Result<Status> {
-        if !self.path.is_dir() {
+        let dir = walk::expand_tilde(&self.path.to_string_lossy());
+        if !dir.is_dir() {
             return Ok(Status::fail(
                 String::from("Path is not a valid directory"),
                 Some(self.path.display().to_string()),
             ));
         }
 
-        let actual_contents = dir_contents(&self.path)?;
+        let actual_contents = walk::dir_children(&dir, self.include_ignored)?;
 
         if !self.contents.is_empty() {
-            let expected_contents: Vec<PathBuf> = self
-                .contents
-                .iter()
-                .map(|name| self.path.join(name))
-                .collect();
+            let mut expected_contents: Vec<PathBuf> =
+                self.contents.iter().map(|name| dir.join(name)).collect();
+            expected_contents.sort();
             if let Some(diff) = dir_compare(&expected_contents, &actual_contents, diff_settings) {
                 return Ok(Status::fail(
                     String::from("Contents differ"),
@@ -256,23 +494,25 @@ impl CheckTrait for DirectoryCheck {
             }
         }
 
-        if !self.contains.is_empty() {
-            for name in &self.contains {
-                let expected_path = self.path.join(name);
-                if !actual_contents.contains(&expected_path) {
-                    return Ok(Status::fail(
-                        String::from("Expected entry not found in directory"),
-                        Some(format!(
-                            "dir: {}, path: {}",
-                            self.path.display(),
-                            expected_path.display()
-                        )),
-                    ));
-                }
-            }
+        let mut results = Vec::new();
+        for name in &self.contains {
+            let expected_path = dir.join(name);
+            let status = if actual_contents.contains(&expected_path) {
+                Status::new(false, StatusStatus::Pass)
+            } else {
+                Status::fail(
+                    String::from("Expected entry not found in directory"),
+                    Some(format!(
+                        "dir: {}, path: {}",
+                        dir.display(),
+                        expected_path.display()
+                    )),
+                )
+            };
+            results.push((format!("contains '{name}'"), status));
         }
 
-        Ok(Status::new(false, StatusStatus::Pass))
+        Ok(combine(results, "entries"))
     }
 }
 
@@ -290,21 +530,235 @@ pub struct CommandCheck {
     stdout_contains: Vec<String>,
     #[serde(default)]
     stderr_contains: Vec<String>,
+
+    /// Remove ANSI escape codes (e.g. colors) from output before comparing
+    #[serde(default = "default_true")]
+    strip_ansi: bool,
+
+    /// Collapse runs of whitespace and drop blank lines before comparing
+    #[serde(default)]
+    normalize_whitespace: bool,
+
+    /// Compare the exact output, ignoring `strip_ansi` and `normalize_whitespace` and not trimming
+    #[serde(default)]
+    raw: bool,
+}
+
+pub(crate) fn default_true() -> bool {
+    true
+}
+
+/// Every line with runs of whitespace collapsed to a single space, without blank lines
+fn normalize_whitespace(s: &str) -> String {
+    s.lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl CommandCheck {
+    /// Output (or expected output) as it should be compared
+    fn prepare(&self, output: &[u8]) -> String {
+        if self.raw {
+            return String::from_utf8_lossy(output).to_string();
+        }
+
+        let output = if self.strip_ansi {
+            strip_ansi_escapes::strip(output)
+        } else {
+            output.to_vec()
+        };
+        let output = String::from_utf8_lossy(&output);
+        if self.normalize_whitespace {
+            normalize_whitespace(&output)
+        } else {
+            output.trim().to_string()
+        }
+    }
+
+    fn compare(
+        &self,
+        expected: &str,
+        actual: &str,
+        diff_settings: &DiffSettings,
+    ) -> Option<String> {
+        let expected = self.prepare(expected.as_bytes());
+        if !self.raw {
+            return str_compare(&expected, actual, diff_settings);
+        }
+
+        if expected == actual {
+            return None;
+        }
+        Some(line_diff(&expected, actual, diff_settings).to_string())
+    }
+
+    /// Add whether `actual` matches each expectation of the named stream to `results`
+    fn check_stream(
+        &self,
+        name: &str,
+        actual: &[u8],
+        expected: Option<&String>,
+        contains: &[String],
+        diff_settings: &DiffSettings,
+        results: &mut Vec<(String, Status)>,
+    ) {
+        let actual = self.prepare(actual);
+
+        if let Some(expected) = expected {
+            let status = match self.compare(expected, &actual, diff_settings) {
+                Some(diff) => Status::fail(format!("{name} differs"), Some(diff)),
+                None => Status::new(false, StatusStatus::Pass),
+            };
+            results.push((format!("{name} matches"), status));
+        }
+
+        for expected_fragment in contains {
+            let fragment = self.prepare(expected_fragment.as_bytes());
+            let status = if actual.contains(&fragment) {
+                Status::new(false, StatusStatus::Pass)
+            } else {
+                Status::fail(
+                    format!("Expected fragment not found in {name}"),
+                    Some(expected_fragment.clone()),
+                )
+            };
+            results.push((format!("{name} contains {expected_fragment:?}"), status));
+        }
+    }
 }
 
 impl CheckTrait for CommandCheck {
     fn describe(&self) -> String {
-        todo!();
+        let mut s = format!("Command '{}' must exit with {}", self.cmd, self.code);
+
+        if let Some(expected_stdout) = &self.expected_stdout {
+            s.push_str(&format!(", stdout must match '{expected_stdout}'"));
+        }
+        if !self.stdout_contains.is_empty() {
+            s.push_str(&format!(", stdout must contain {:?}", self.stdout_contains));
+        }
+        if let Some(expected_stderr) = &self.expected_stderr {
+            s.push_str(&format!(", stderr must match '{expected_stderr}'"));
+        }
+        if !self.stderr_contains.is_empty() {
+            s.push_str(&format!(", stderr must contain {:?}", self.stderr_contains));
+        }
+
+        s
     }
 
     fn do_check(
         &self,
         diff_settings: &DiffSettings,
-        env: &Environment,
-        this_file_path: &Path,
+        _env: &Environment,
+        _this_file_path: &Path,
         vars: &HashMap<String, String>,
     ) -> Result<Status> {
-        todo!();
+        let output = match run_command_line(&self.cmd, Some(vars)) {
+            Ok(output) => output,
+            Err(e) => {
+                return Ok(Status::fail(
+                    String::from("Unable to run command"),
+                    Some(e.to_string()),
+                ));
+            }
+        };
+
+        if output.code() != self.code {
+            let mut details = format!("expected {}, got {}", self.code, output.code());
+            let stderr = self.prepare(output.raw_stderr());
+            if !stderr.trim().is_empty() {
+                details.push_str(&format!("\n{stderr}"));
+            }
+            return Ok(Status::fail(
+                String::from("Unexpected exit code"),
+                Some(details),
+            ));
+        }
+
+        let mut results = Vec::new();
+        self.check_stream(
+            "stdout",
+            output.raw_stdout(),
+            self.expected_stdout.as_ref(),
+            &self.stdout_contains,
+            diff_settings,
+            &mut results,
+        );
+        self.check_stream(
+            "stderr",
+            output.raw_stderr(),
+            self.expected_stderr.as_ref(),
+            &self.stderr_contains,
+            diff_settings,
+            &mut results,
+        );
+
+        Ok(combine(results, "assertions"))
+    }
+}
+
+/// Where to read a (possibly secret) value from at check time
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ValueSource {
+    Env(String),
+    Fact(String),
+    /// A plain string is used as is
+    #[serde(untagged)]
+    Literal(String),
+}
+
+impl ValueSource {
+    pub(crate) fn resolve(&self, vars: &HashMap<String, String>) -> Result<String> {
+        let value = match self {
+            Self::Env(key) => {
+                let Ok(value) = env::var(key) else {
+                    bail!("Env var '{key}' not set");
+                };
+                value
+            }
+            Self::Fact(key) => {
+                let Some(value) = vars.get(key) else {
+                    bail!("Fact '{key}' not set");
+                };
+                value.clone()
+            }
+            Self::Literal(value) => value.clone(),
+        };
+        Ok(value)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum HttpAuth {
+    Bearer {
+        token: ValueSource,
+    },
+    Basic {
+        username: String,
+        password: Option<ValueSource>,
+    },
+}
+
+impl HttpMethod {
+    fn to_method(&self) -> Method {
+        match self {
+            Self::Get => Method::GET,
+            Self::Post => Method::POST,
+            Self::Put => Method::PUT,
+            Self::Delete => Method::DELETE,
+            Self::Head => Method::HEAD,
+            Self::Connect => Method::CONNECT,
+            Self::Options => Method::OPTIONS,
+            Self::Trace => Method::TRACE,
+            Self::Patch => Method::PATCH,
+            // Validated when parsed
+            Self::Custom(method) => Method::from_bytes(method.as_bytes()).unwrap(),
+        }
     }
 }
 
@@ -319,10 +773,137 @@ pub struct HttpCheck {
 
     url: String,
 
+    /// Extra request headers
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+
+    /// Bearer or basic auth, with secrets read from env vars or facts
+    auth: Option<HttpAuth>,
+
+    /// Request body to send (e.g. for POST/PUT)
+    body: Option<String>,
+
+    /// Content type of the request body
+    content_type: Option<String>,
+
     #[serde(default)]
     body_contains: Vec<String>,
 
     expected_body: Option<String>,
+
+    /// Fail if the response (its status and headers, not the whole body) takes longer than this to
+    /// arrive (e.g. "500ms")
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    max_latency: Option<humantime::Duration>,
+
+    /// Give up on the request after this long (e.g. "5s", default 30s)
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    timeout: Option<humantime::Duration>,
+
+    /// JSON Schema file (relative to the checklist) the response body must conform to
+    schema: Option<PathBuf>,
+
+    /// Like `schema`, but a template populated with facts first
+    schema_template: Option<PathBuf>,
+}
+
+/// How long to wait for an HTTP check's request when it doesn't set a timeout
+const DEFAULT_HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl HttpCheck {
+    fn request(&self, vars: &HashMap<String, String>) -> Result<RequestBuilder> {
+        let timeout = self.timeout.map_or(DEFAULT_HTTP_TIMEOUT, Into::into);
+        let client = Client::builder().timeout(timeout).build()?;
+        let mut request = client.request(self.method.to_method(), &self.url);
+
+        for (key, value) in &self.headers {
+            request = request.header(key, value);
+        }
+
+        if let Some(auth) = &self.auth {
+            request = match auth {
+                HttpAuth::Bearer { token } => request.bearer_auth(token.resolve(vars)?),
+                HttpAuth::Basic { username, password } => {
+                    let password = password.as_ref().map(|p| p.resolve(vars)).transpose()?;
+                    request.basic_auth(username, password)
+                }
+            };
+        }
+
+        if let Some(body) = &self.body {
+            if let Some(content_type) = &self.content_type {
+                request = request.header(CONTENT_TYPE, content_type);
+            }
+            request = request.body(body.clone());
+        }
+
+        Ok(request)
+    }
+
+    /// JSON Schemas the body must conform to, by name
+    fn schemas(
+        &self,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Vec<(String, serde_json::Value)>> {
+        let base = this_file_path.parent().unwrap();
+        let mut schemas = Vec::new();
+
+        if let Some(schema) = &self.schema {
+            let path = rel_to(base, schema);
+            let contents = fs::read_to_string(&path)
+                .with_context(|| format!("Unable to read schema {}", path.display()))?;
+            let schema = serde_json::from_str(&contents)
+                .with_context(|| format!("Schema {} is not JSON", path.display()))?;
+            schemas.push((path.display().to_string(), schema));
+        }
+
+        if let Some(template) = &self.schema_template {
+            let name = rel_to(base, template).display().to_string();
+            let contents = template::render(env, &name, vars)?;
+            let schema = serde_json::from_str(&contents)
+                .with_context(|| format!("Populated schema {name} is not JSON"))?;
+            schemas.push((name, schema));
+        }
+
+        Ok(schemas)
+    }
+
+    /// Every way `body` violates the schemas
+    fn schema_violations(
+        &self,
+        body: &str,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Vec<String>> {
+        let schemas = self.schemas(env, this_file_path, vars)?;
+        if schemas.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body: serde_json::Value = match serde_json::from_str(body) {
+            Ok(body) => body,
+            Err(e) => return Ok(vec![format!("body is not JSON: {e}")]),
+        };
+
+        let mut violations = Vec::new();
+        for (name, schema) in schemas {
+            let validator = jsonschema::validator_for(&schema)
+                .map_err(|e| anyhow::anyhow!("Invalid schema {name}: {e}"))?;
+            for error in validator.iter_errors(&body) {
+                let location = match error.instance_path().as_str() {
+                    "" => "/",
+                    location => location,
+                };
+                violations.push(format!("{location}: {error}"));
+            }
+        }
+        Ok(violations)
+    }
 }
 
 impl CheckTrait for HttpCheck {
@@ -340,6 +921,14 @@ impl CheckTrait for HttpCheck {
             s.push_str(&format!(", body must contain {:?}", self.body_contains));
         }
 
+        if let Some(max_latency) = &self.max_latency {
+            s.push_str(&format!(", within {max_latency}"));
+        }
+
+        if let Some(schema) = self.schema.as_ref().or(self.schema_template.as_ref()) {
+            s.push_str(&format!(", body must conform to {}", schema.display()));
+        }
+
         s
     }
 
@@ -350,36 +939,175 @@ impl CheckTrait for HttpCheck {
         this_file_path: &Path,
         vars: &HashMap<String, String>,
     ) -> Result<Status> {
-        todo!();
-    }
-}
-
+        let request = match self.request(vars) {
+            Ok(request) => request,
+            Err(e) => {
+                return Ok(Status::fail(
+                    String::from("Unable to build request"),
+                    Some(e.to_string()),
+                ));
+            }
+        };
+
+        let start = Instant::now();
+        let response = match request.send() {
+            Ok(response) => response,
+            Err(e) => {
+                return Ok(Status::fail(
+                    String::from("Request failed"),
+                    Some(format!("{} {}: {e}", self.method, self.url)),
+                ));
+            }
+        };
+        // The response is returned once its headers arrive, before the body is read
+        let latency = start.elapsed();
+
+        let code = response.status();
+        if code != self.code {
+            return Ok(Status::fail(
+                String::from("Unexpected status code"),
+                Some(format!("expected {}, got {code}", self.code)),
+            ));
+        }
+
+        let actual_body = response.text()?;
+
+        if let Some(max_latency) = self.max_latency
+            && latency > *max_latency
+        {
+            return Ok(Status::fail(
+                String::from("Response too slow"),
+                Some(format!(
+                    "took {}, budget is {max_latency}",
+                    format_duration(latency)
+                )),
+            ));
+        }
+
+        let mut results = Vec::new();
+
+        if let Some(expected_body) = &self.expected_body {
+            let status = match str_compare(expected_body, &actual_body, diff_settings) {
+                Some(diff) => Status::fail(String::from("Body differs"), Some(diff)),
+                None => Status::new(false, StatusStatus::Pass),
+            };
+            results.push((String::from("body matches"), status));
+        }
+
+        for expected_fragment in &self.body_contains {
+            let status = if actual_body.contains(expected_fragment) {
+                Status::new(false, StatusStatus::Pass)
+            } else {
+                Status::fail(
+                    String::from("Expected fragment not found in body"),
+                    Some(expected_fragment.clone()),
+                )
+            };
+            results.push((format!("body contains {expected_fragment:?}"), status));
+        }
+
+        if self.schema.is_some() || self.schema_template.is_some() {
+            let violations = self.schema_violations(&actual_body, env, this_file_path, vars)?;
+            let status = if violations.is_empty() {
+                Status::new(false, StatusStatus::Pass)
+            } else {
+                Status::fail(
+                    String::from("Body does not match schema"),
+                    Some(violations.join("\n")),
+                )
+            };
+            results.push((String::from("body conforms to schema"), status));
+        }
+
+        Ok(combine(results, "assertions"))
+    }
+}
+
+/// Accepted value(s) of a var
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(untagged)]
+pub enum VarValue {
+    /// Must equal exactly
+    Exact(String),
+    /// Must equal one of
+    OneOf(Vec<String>),
+    /// Must match the regex
+    Regex { regex: String },
+}
+
+impl VarValue {
+    fn matches(&self, actual: &str) -> Result<bool> {
+        let matches = match self {
+            Self::Exact(expected) => actual == expected,
+            Self::OneOf(allowed) => allowed.iter().any(|a| a == actual),
+            Self::Regex { regex } => {
+                let Ok(re) = Regex::new(regex) else {
+                    bail!("Invalid regex '{regex}'");
+                };
+                re.is_match(actual)
+            }
+        };
+        Ok(matches)
+    }
+}
+
+impl Display for VarValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Exact(expected) => write!(f, "'{expected}'"),
+            Self::OneOf(allowed) => write!(f, "one of {allowed:?}"),
+            Self::Regex { regex } => write!(f, "a match for /{regex}/"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub struct VarCheck {
+    /// Fact name, falling back to an env var of the same name
     key: String,
     /// Omitting (None) means any value is ok, but must be set
-    value: Option<String>,
+    value: Option<VarValue>,
 }
 
 impl CheckTrait for VarCheck {
     fn describe(&self) -> String {
-        let mut s = format!("Var {} ", self.key);
+        let mut s = format!("Var {}", self.key);
         if let Some(value) = &self.value {
             s.push_str(&format!(" must be set to {value}"));
         } else {
-            s.push_str("must be set");
+            s.push_str(" must be set");
         }
         s
     }
 
     fn do_check(
         &self,
-        diff_settings: &DiffSettings,
-        env: &Environment,
-        this_file_path: &Path,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
+        _this_file_path: &Path,
         vars: &HashMap<String, String>,
     ) -> Result<Status> {
-        todo!();
+        let key = &self.key;
+        let actual = match vars.get(key) {
+            Some(actual) => actual.clone(),
+            None => match env::var(key) {
+                Ok(actual) => actual,
+                Err(_) => {
+                    return Ok(Status::fail(format!("Var '{key}' not set"), None));
+                }
+            },
+        };
+
+        if let Some(value) = &self.value
+            && !value.matches(&actual)?
+        {
+            return Ok(Status::fail(
+                format!("Var '{key}' has unexpected value"),
+                Some(format!("expected {value}, got '{actual}'")),
+            ));
+        }
+
+        Ok(Status::new(false, StatusStatus::Pass))
     }
 }
 
@@ -391,9 +1119,49 @@ pub enum CheckType {
     Command(CommandCheck),
     Http(HttpCheck),
     VarSet(VarCheck),
+    Freshness(FreshnessCheck),
+    Sql(SqlCheck),
+    #[serde(rename = "line-limits")]
+    LineLimits(LineLimitsCheck),
+    Whitespace(WhitespaceCheck),
+    Duplicates(DuplicatesCheck),
+    Gitignore(GitignoreCheck),
+    Group(GroupCheck),
+    Script(ScriptCheck),
+    Budget(BudgetCheck),
+    Secrets(SecretsCheck),
+    Platform(PlatformCheck),
+    Time(TimeWindowCheck),
+    Manual(ManualCheck),
+    #[serde(rename = "front-matter")]
+    FrontMatter(FrontMatterCheck),
+    Spelling(SpellingCheck),
+    Image(ImageCheck),
+    Binaries(BinariesCheck),
+    #[serde(rename = "rust-workspace")]
+    RustWorkspace(RustWorkspaceCheck),
+    Subproject(SubprojectCheck),
 }
 
 impl CheckType {
+    /// The `type` the check is written with in a checklist
+    pub fn name(&self) -> String {
+        serde_json::to_value(self)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(String::from))
+            .unwrap_or_default()
+    }
+
+    /// The file, directory, or glob this check is about, if it is a file check
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File(f) => Some(f.path()),
+            Self::Directory(d) => Some(&d.path),
+            Self::Subproject(s) => Some(s.path()),
+            _ => None,
+        }
+    }
+
     fn describe(&self) -> String {
         match self {
             Self::File(f) => f.describe(),
@@ -401,6 +1169,25 @@ impl CheckType {
             Self::Command(c) => c.describe(),
             Self::Http(h) => h.describe(),
             Self::VarSet(v) => v.describe(),
+            Self::Freshness(f) => f.describe(),
+            Self::Sql(s) => s.describe(),
+            Self::LineLimits(l) => l.describe(),
+            Self::Whitespace(w) => w.describe(),
+            Self::Duplicates(d) => d.describe(),
+            Self::Gitignore(g) => g.describe(),
+            Self::Group(g) => g.describe(),
+            Self::Script(s) => s.describe(),
+            Self::Budget(b) => b.describe(),
+            Self::Secrets(s) => s.describe(),
+            Self::Platform(p) => p.describe(),
+            Self::Time(t) => t.describe(),
+            Self::Manual(m) => m.describe(),
+            Self::FrontMatter(f) => f.describe(),
+            Self::Spelling(s) => s.describe(),
+            Self::Image(i) => i.describe(),
+            Self::Binaries(b) => b.describe(),
+            Self::RustWorkspace(r) => r.describe(),
+            Self::Subproject(s) => s.describe(),
         }
     }
 
@@ -417,18 +1204,85 @@ impl CheckType {
             Self::Command(c) => c.do_check(diff_settings, env, this_file_path, vars),
             Self::Http(h) => h.do_check(diff_settings, env, this_file_path, vars),
             Self::VarSet(v) => v.do_check(diff_settings, env, this_file_path, vars),
+            Self::Freshness(f) => f.do_check(diff_settings, env, this_file_path, vars),
+            Self::Sql(s) => s.do_check(diff_settings, env, this_file_path, vars),
+            Self::LineLimits(l) => l.do_check(diff_settings, env, this_file_path, vars),
+            Self::Whitespace(w) => w.do_check(diff_settings, env, this_file_path, vars),
+            Self::Duplicates(d) => d.do_check(diff_settings, env, this_file_path, vars),
+            Self::Gitignore(g) => g.do_check(diff_settings, env, this_file_path, vars),
+            Self::Group(g) => g.do_check(diff_settings, env, this_file_path, vars),
+            Self::Script(s) => s.do_check(diff_settings, env, this_file_path, vars),
+            Self::Budget(b) => b.do_check(diff_settings, env, this_file_path, vars),
+            Self::Secrets(s) => s.do_check(diff_settings, env, this_file_path, vars),
+            Self::Platform(p) => p.do_check(diff_settings, env, this_file_path, vars),
+            Self::Time(t) => t.do_check(diff_settings, env, this_file_path, vars),
+            Self::Manual(m) => m.do_check(diff_settings, env, this_file_path, vars),
+            Self::FrontMatter(f) => f.do_check(diff_settings, env, this_file_path, vars),
+            Self::Spelling(s) => s.do_check(diff_settings, env, this_file_path, vars),
+            Self::Image(i) => i.do_check(diff_settings, env, this_file_path, vars),
+            Self::Binaries(b) => b.do_check(diff_settings, env, this_file_path, vars),
+            Self::RustWorkspace(r) => r.do_check(diff_settings, env, this_file_path, vars),
+            Self::Subproject(s) => s.do_check(diff_settings, env, this_file_path, vars),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum ConditionsMode {
+    /// Run only if every condition is met
+    #[default]
+    All,
+    /// Run if at least one condition is met
+    Any,
+}
+
+/// Skip status if `conditions` aren't met according to `mode`
+fn unmet_conditions(
+    conditions: &[Condition],
+    mode: ConditionsMode,
+    diff_settings: &DiffSettings,
+    env: &Environment,
+    this_file_path: &Path,
+    vars: &HashMap<String, String>,
+) -> Result<Option<Status>> {
+    if conditions.is_empty() {
+        return Ok(None);
+    }
+
+    let mut unmet = Vec::new();
+    for condition in conditions {
+        let status = condition.do_check(diff_settings, env, this_file_path, vars)?;
+        match (mode, status.is_skipped()) {
+            (ConditionsMode::All, true) => return Ok(Some(status)),
+            (ConditionsMode::Any, false) => return Ok(None),
+            (ConditionsMode::Any, true) => unmet.push(condition.describe()),
+            (ConditionsMode::All, false) => {}
+        }
+    }
+
+    match mode {
+        ConditionsMode::All => Ok(None),
+        ConditionsMode::Any => Ok(Some(Status::skip(
+            String::from("No condition met"),
+            Some(unmet.join("\n")),
+        ))),
+    }
+}
+
+/// A check that gates another check: when the condition isn't met, the check is skipped
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
-struct Condition {
+pub struct Condition {
     description: Option<String>,
+    /// Condition is met when the check does not pass
+    #[serde(default)]
+    negate: bool,
     #[serde(flatten)]
     condition: CheckType,
 }
 
 impl CheckTrait for Condition {
+    /// Pass if the condition is met, otherwise skip
     fn do_check(
         &self,
         diff_settings: &DiffSettings,
@@ -436,24 +1290,173 @@ impl CheckTrait for Condition {
         this_file_path: &Path,
         vars: &HashMap<String, String>,
     ) -> Result<Status> {
-        self.condition
-            .do_check(diff_settings, env, this_file_path, vars)
+        let status = self
+            .condition
+            .do_check(diff_settings, env, this_file_path, vars)?;
+
+        if status.is_success() != self.negate {
+            return Ok(Status::new(false, StatusStatus::Pass));
+        }
+
+        Ok(Status::skip(
+            format!("Condition not met: {}", self.describe()),
+            None,
+        ))
     }
 
     fn describe(&self) -> String {
-        todo!();
+        if let Some(description) = &self.description {
+            return description.clone();
+        }
+
+        let description = self.condition.describe();
+        if self.negate {
+            format!("not ({description})")
+        } else {
+            description
+        }
+    }
+}
+
+/// Remediation of a failing check, applied with '--fix'
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash, derive_more::Display)]
+#[serde(rename_all = "lowercase")]
+pub enum Fix {
+    /// Write the populated template, relative to the checklist file, to the check's file
+    #[display("write template '{}'", _0.display())]
+    Template(PathBuf),
+    /// Run a command, with the facts as env vars
+    #[display("run '{_0}'")]
+    Command(String),
+    /// Append text to the check's file, creating it if needed
+    #[display("append to file")]
+    Append(String),
+}
+
+impl Fix {
+    /// File a template or append fix writes to
+    fn target(check: &CheckType) -> Result<PathBuf> {
+        match check {
+            CheckType::File(f) if !f.is_glob() => Ok(walk::expand_tilde(&f.path.to_string_lossy())),
+            _ => bail!("Only checks of a single file can be fixed by writing to it"),
+        }
+    }
+
+    pub fn apply(
+        &self,
+        check: &CheckType,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<()> {
+        match self {
+            Self::Template(template) => {
+                let path = Self::target(check)?;
+                let vars = match check {
+                    CheckType::File(f) => f.template_vars(vars),
+                    _ => vars.clone(),
+                };
+                let template = rel_to(this_file_path.parent().unwrap(), template);
+                let mut contents = template::render(env, &template.display().to_string(), &vars)?;
+                // Rendering drops the template's trailing newline
+                if !contents.ends_with('\n') {
+                    contents.push('\n');
+                }
+                // Only the managed region is ours to write, the rest of the file is left as is
+                if let CheckType::File(FileCheck {
+                    region: Some(name), ..
+                }) = check
+                {
+                    let existing = fs::read_to_string(&path).unwrap_or_default();
+                    let Some(replaced) = region::replace(&existing, name, &contents) else {
+                        bail!(
+                            "{} has no managed region '{name}' to write to",
+                            path.display()
+                        );
+                    };
+                    contents = replaced;
+                }
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                fs::write(path, contents)?;
+            }
+            Self::Command(command) => {
+                let output = run_command_line(command, Some(vars))?;
+                if output.code() != 0 {
+                    let stderr = output.stderr().map(|s| s.as_str()).unwrap_or_default();
+                    bail!("Fix '{command}' failed: {stderr}");
+                }
+            }
+            Self::Append(text) => {
+                let path = Self::target(check)?;
+                let mut f = OpenOptions::new().create(true).append(true).open(path)?;
+                f.write_all(text.as_bytes())?;
+            }
+        }
+        Ok(())
     }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Hash)]
+#[serde(from = "CheckFields")]
 pub struct Check {
     #[serde(flatten)]
     check: CheckType,
+    /// Used to select the check, and to acknowledge manual checks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<String>,
     description: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    severity: Severity,
+    conditions: Vec<Condition>,
+    /// How `conditions` combine
+    conditions_mode: ConditionsMode,
+    requirements: Vec<Requirement>,
+    /// How to fix the check when it fails
+    fix: Option<Fix>,
+}
+
+/// `Check` as written in a checklist. A manual check's id is the check's id, and a flattened
+/// check type can't share a key with the check, so it's handed down after parsing
+#[derive(Deserialize)]
+struct CheckFields {
+    #[serde(flatten)]
+    check: CheckType,
+    id: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    severity: Severity,
     #[serde(default)]
     conditions: Vec<Condition>,
     #[serde(default)]
+    conditions_mode: ConditionsMode,
+    #[serde(default)]
     requirements: Vec<Requirement>,
+    fix: Option<Fix>,
+}
+
+impl From<CheckFields> for Check {
+    fn from(fields: CheckFields) -> Self {
+        let mut check = fields.check;
+        if let CheckType::Manual(m) = &mut check {
+            m.set_id(fields.id.clone());
+        }
+        Self {
+            check,
+            id: fields.id,
+            description: fields.description,
+            tags: fields.tags,
+            severity: fields.severity,
+            conditions: fields.conditions,
+            conditions_mode: fields.conditions_mode,
+            requirements: fields.requirements,
+            fix: fields.fix,
+        }
+    }
 }
 
 impl Check {
@@ -465,34 +1468,125 @@ impl Check {
         self.check.describe()
     }
 
-    pub fn do_check(
+    /// Status of the first condition or requirement that isn't met, reported instead of running
+    /// the check
+    pub fn unmet(
         &self,
         diff_settings: &DiffSettings,
         env: &Environment,
         this_file_path: &Path,
         vars: &HashMap<String, String>,
-    ) -> Result<Status> {
-        for condition in &self.conditions {
-            let status = condition.do_check(diff_settings, env, this_file_path, vars)?;
-            if status.is_skipped() {
-                return Ok(status);
-            }
+    ) -> Result<Option<Status>> {
+        if let Some(status) = unmet_conditions(
+            &self.conditions,
+            self.conditions_mode,
+            diff_settings,
+            env,
+            this_file_path,
+            vars,
+        )? {
+            return Ok(Some(status));
         }
 
         for requirement in &self.requirements {
             let status = requirement.do_check(diff_settings, env, this_file_path, vars)?;
-            if status.is_failure() {
-                return Ok(status);
+            if !status.is_success() {
+                return Ok(Some(status));
             }
         }
 
+        Ok(None)
+    }
+
+    pub fn do_check(
+        &self,
+        diff_settings: &DiffSettings,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Status> {
+        if let Some(status) = self.unmet(diff_settings, env, this_file_path, vars)? {
+            return Ok(status);
+        }
+
         self.check
             .do_check(diff_settings, env, this_file_path, vars)
     }
 
+    /// Like `do_check`, but budget checks also compare against, and update, the metrics of
+    /// previous runs
+    pub fn do_check_with_metrics(
+        &self,
+        diff_settings: &DiffSettings,
+        env: &Environment,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+        metrics: &mut Metrics,
+    ) -> Result<Status> {
+        let CheckType::Budget(budget) = &self.check else {
+            return self.do_check(diff_settings, env, this_file_path, vars);
+        };
+
+        if let Some(status) = self.unmet(diff_settings, env, this_file_path, vars)? {
+            return Ok(status);
+        }
+
+        let key = self.metric_key(this_file_path);
+        Ok(budget.check_against(&key, vars, metrics))
+    }
+
+    /// Where the previous value of a budget check's metric is kept. Checklists, even of different
+    /// projects, can measure metrics of the same name
+    fn metric_key(&self, checklist_path: &Path) -> String {
+        format!(
+            "{}#{}",
+            checklist_path.display(),
+            baseline::check_id(checklist_path, self)
+        )
+    }
+
     pub fn ttype(&self) -> &CheckType {
         &self.check
     }
+
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Let checks that read files of the project (acknowledgements, subprojects) find them under
+    /// `root`, and give nested runs the options of this one
+    pub(crate) fn set_project(&mut self, root: &Path, run_args: &[OsString]) {
+        match &mut self.check {
+            CheckType::Manual(m) => m.set_root(root),
+            CheckType::Subproject(s) => s.set_project(root, run_args),
+            CheckType::Group(g) => g
+                .checks_mut()
+                .iter_mut()
+                .for_each(|check| check.set_project(root, run_args)),
+            _ => {}
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        self.severity
+    }
+
+    pub fn requirements(&self) -> &[Requirement] {
+        &self.requirements
+    }
+
+    pub fn fix(&self) -> Option<&Fix> {
+        self.fix.as_ref()
+    }
+
+    /// Can't run without the network
+    pub fn needs_network(&self) -> bool {
+        matches!(self.check, CheckType::Http(_)) || self.requirements.iter().any(|r| r.is_network())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -500,23 +1594,97 @@ impl Check {
 enum FactValue {
     #[serde(rename = "eval-command")]
     Command { command: String },
+    /// Several facts from one command's JSON output
+    #[serde(rename = "eval-command-json")]
+    CommandJson {
+        command: String,
+        /// Fact key -> JSONPath into the output (e.g. "$.packages[0].name")
+        keys: BTreeMap<String, String>,
+    },
     #[serde(rename = "literal")]
     Literal { value: String },
     #[serde(rename = "env-var")]
     Env { key: String },
+    /// Response body of a GET request, or the value at `jsonpath` within it
+    #[serde(rename = "http")]
+    Http {
+        url: String,
+        jsonpath: Option<String>,
+    },
+    /// Contents of a file (relative to the checklist), or the first capture group (else whole
+    /// match) of `regex` within it
+    #[serde(rename = "file")]
+    File {
+        path: PathBuf,
+        regex: Option<String>,
+    },
+    /// Supplied by the user through `--var`, `[vars]` in the config or an env var named after the
+    /// fact. Falls back to `default`, then to asking interactively
+    #[serde(rename = "input")]
+    Input {
+        default: Option<String>,
+        /// Question to ask when prompting
+        prompt: Option<String>,
+    },
+}
+
+/// Value of an 'input' fact
+fn input_value(
+    key: &str,
+    default: Option<&String>,
+    prompt: Option<&String>,
+    vars: &HashMap<String, String>,
+) -> Result<String> {
+    if let Some(value) = vars.get(key) {
+        return Ok(value.clone());
+    }
+    if let Ok(value) = env::var(key) {
+        return Ok(value);
+    }
+    if let Some(default) = default {
+        return Ok(default.clone());
+    }
+
+    if !io::stdin().is_terminal() {
+        bail!("Fact '{key}' must be supplied, set env var '{key}' or pass '--var {key}=VALUE'");
+    }
+    // On stderr, so results printed on stdout stay parseable
+    match prompt {
+        Some(prompt) => eprint!("{prompt}: "),
+        None => eprint!("Value for '{key}': "),
+    }
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    let answer = answer.trim();
+    if answer.is_empty() {
+        bail!("No value given for fact '{key}'");
+    }
+    Ok(answer.to_string())
+}
+
+/// First capture group of `regex` in `text`, or the whole match if it has no groups
+fn first_capture<'a>(regex: &Regex, text: &'a str) -> Option<&'a str> {
+    let captures = regex.captures(text)?;
+    let m = captures.get(1).or_else(|| captures.get(0))?;
+    Some(m.as_str())
+}
+
+/// Run a command, returning its stdout
+fn command_stdout(command: &str, vars: &HashMap<String, String>) -> Result<String> {
+    let output = run_command_line(command, Some(vars))?;
+    let Some(stdout) = output.stdout() else {
+        bail!("Command produced empty output");
+    };
+    Ok(stdout.clone())
 }
 
 impl FactValue {
-    fn value(&self, vars: &HashMap<String, String>) -> Result<String> {
+    fn value(&self, this_file_path: &Path, vars: &HashMap<String, String>) -> Result<String> {
         let value = match self {
-            Self::Command { command } => {
-                let output = run_command_line(command, Some(vars))?;
-                let Some(stdout) = output.stdout() else {
-                    bail!("Command produced empty output");
-                };
-
-                stdout.clone()
-            }
+            Self::Command { command } => command_stdout(command, vars)?,
+            Self::CommandJson { .. } => bail!("'eval-command-json' facts have multiple values"),
+            Self::Input { .. } => bail!("'input' facts are resolved by key"),
             Self::Literal { value } => value.to_string(),
             Self::Env { key } => {
                 let Ok(value) = env::var(key) else {
@@ -524,6 +1692,104 @@ impl FactValue {
                 };
                 value
             }
+            Self::Http { url, jsonpath } => {
+                let response = Client::new().get(url).send()?.error_for_status()?;
+                let Some(jsonpath) = jsonpath else {
+                    return Ok(response.text()?.trim().to_string());
+                };
+
+                let json: serde_json::Value = response
+                    .json()
+                    .with_context(|| format!("Response from {url} is not JSON"))?;
+                json_path_value(&json, jsonpath)?
+            }
+            Self::File { path, regex } => {
+                let path = rel_to(this_file_path.parent().unwrap(), path);
+                let contents = fs::read_to_string(&path)
+                    .with_context(|| format!("Unable to read {}", path.display()))?;
+                let Some(regex) = regex else {
+                    return Ok(contents.trim().to_string());
+                };
+
+                let regex = Regex::new(regex)?;
+                let Some(capture) = first_capture(&regex, &contents) else {
+                    bail!("'{regex}' does not match {}", path.display());
+                };
+                capture.to_string()
+            }
+        };
+        Ok(value)
+    }
+
+    /// Key -> value for each path in `keys`
+    fn json_values(
+        command: &str,
+        keys: &BTreeMap<String, String>,
+        vars: &HashMap<String, String>,
+    ) -> Result<Vec<(String, String)>> {
+        let stdout = command_stdout(command, vars)?;
+        let json: serde_json::Value = serde_json::from_str(&stdout)
+            .with_context(|| format!("Output of '{command}' is not JSON"))?;
+
+        let mut values = Vec::new();
+        for (key, path) in keys {
+            let value = json_path_value(&json, path)
+                .with_context(|| format!("Unable to get fact '{key}'"))?;
+            values.push((key.clone(), value));
+        }
+        Ok(values)
+    }
+}
+
+/// The single value at `path` in `json`, without quotes if it's a string
+pub(crate) fn json_path_value(json: &serde_json::Value, path: &str) -> Result<String> {
+    let json_path = JsonPath::parse(path).with_context(|| format!("Invalid JSONPath '{path}'"))?;
+    let value = match json_path.query(json).exactly_one() {
+        Ok(serde_json::Value::String(s)) => s.clone(),
+        Ok(value) => value.to_string(),
+        Err(e) => bail!("JSONPath '{path}': {e}"),
+    };
+    Ok(value)
+}
+
+/// Post-processing step applied to a fact's value
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum FactFilter {
+    Trim,
+    Lowercase,
+    Uppercase,
+    /// Line at this index, counting from the end if negative
+    Line(isize),
+    /// First capture group of the regex, or the whole match if it has none
+    RegexCapture(String),
+}
+
+impl FactFilter {
+    fn apply(&self, value: &str) -> Result<String> {
+        let value = match self {
+            Self::Trim => value.trim().to_string(),
+            Self::Lowercase => value.to_lowercase(),
+            Self::Uppercase => value.to_uppercase(),
+            Self::Line(index) => {
+                let lines = value.lines().collect::<Vec<_>>();
+                let resolved = if *index < 0 {
+                    lines.len().checked_sub(index.unsigned_abs())
+                } else {
+                    Some(index.unsigned_abs())
+                };
+                let Some(line) = resolved.and_then(|i| lines.get(i)) else {
+                    bail!("No line {index} in a value of {} line(s)", lines.len());
+                };
+                line.to_string()
+            }
+            Self::RegexCapture(regex) => {
+                let regex = Regex::new(regex)?;
+                let Some(capture) = first_capture(&regex, value) else {
+                    bail!("'{regex}' does not match '{value}'");
+                };
+                capture.to_string()
+            }
         };
         Ok(value)
     }
@@ -531,73 +1797,219 @@ impl FactValue {
 
 #[derive(Debug, Deserialize)]
 pub struct Fact {
-    key: String,
+    /// Not used by 'eval-command-json' facts, which name their own keys
+    key: Option<String>,
     #[serde(flatten)]
     value: FactValue,
+    /// Applied in order to every value
+    #[serde(default)]
+    filters: Vec<FactFilter>,
     #[serde(default, rename = "requires")]
     requirements: Vec<Requirement>,
 }
 
 impl Fact {
-    pub fn key(&self) -> String {
-        self.key.clone()
+    /// Keys of the facts this provides
+    pub fn keys(&self) -> Vec<String> {
+        match &self.value {
+            FactValue::CommandJson { keys, .. } => keys.keys().cloned().collect(),
+            _ => self.key.iter().cloned().collect(),
+        }
     }
 
-    pub fn value(&self, vars: &HashMap<String, String>) -> Result<String> {
-        self.value.value(vars)
+    /// Key -> value of each fact this provides
+    pub fn values(
+        &self,
+        this_file_path: &Path,
+        vars: &HashMap<String, String>,
+    ) -> Result<Vec<(String, String)>> {
+        let values = if let FactValue::CommandJson { command, keys } = &self.value {
+            FactValue::json_values(command, keys, vars)?
+        } else {
+            let Some(key) = &self.key else {
+                bail!("Fact is missing 'key'");
+            };
+            let value = match &self.value {
+                FactValue::Input { default, prompt } => {
+                    input_value(key, default.as_ref(), prompt.as_ref(), vars)?
+                }
+                value => value.value(this_file_path, vars)?,
+            };
+            vec![(key.clone(), value)]
+        };
+
+        values
+            .into_iter()
+            .map(|(key, mut value)| {
+                for filter in &self.filters {
+                    value = filter
+                        .apply(&value)
+                        .with_context(|| format!("Unable to filter fact '{key}'"))?;
+                }
+                Ok((key, value))
+            })
+            .collect()
     }
 
     pub fn requirements(&self) -> &[Requirement] {
         &self.requirements
     }
+
+    /// Can't be evaluated without the network
+    pub fn needs_network(&self) -> bool {
+        matches!(self.value, FactValue::Http { .. })
+            || self.requirements.iter().any(|r| r.is_network())
+    }
 }
 
 #[derive(Debug, Deserialize, PartialEq, Eq, Hash, Clone, Serialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum Requirement {
-    Command { command: String },
-    Env { key: String },
+    Command {
+        command: String,
+        /// Version constraint (e.g. ">=1.75") checked against `<command> --version`
+        version: Option<VersionReq>,
+        /// Command line that installs the command (e.g. "cargo install just")
+        install: Option<String>,
+    },
+    Env {
+        key: String,
+    },
+    File {
+        path: PathBuf,
+    },
+    Network {
+        /// Address to connect to (e.g. "crates.io:443"), ideally one the check itself talks to
+        host: String,
+    },
+}
+
+/// How long to wait when probing the network
+const NETWORK_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+fn can_connect(host: &str) -> bool {
+    let Ok(addrs) = host.to_socket_addrs() else {
+        return false;
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, NETWORK_PROBE_TIMEOUT).is_ok())
+}
+
+impl Requirement {
+    /// The install command of a command requirement whose command is missing
+    pub fn missing_install(&self) -> Option<(&str, &str)> {
+        match self {
+            Self::Command {
+                command,
+                install: Some(install),
+                ..
+            } if which::which(command).is_err() => Some((command, install)),
+            _ => None,
+        }
+    }
+
+    pub fn is_network(&self) -> bool {
+        matches!(self, Self::Network { .. })
+    }
+}
+
+/// Where a requirement is needed, plus how to install it if known
+fn requirement_details(this_file_path: &Path, install: Option<&String>) -> String {
+    let mut details = format!("Required for a check in {}", this_file_path.display());
+    if let Some(install) = install {
+        details.push_str(&format!("\nInstall it with: {install}"));
+    }
+    details
+}
+
+/// The first version number printed by `<command> --version`
+fn command_version(command: &str) -> Result<Option<Version>> {
+    let output = run_command(&command, &["--version"], None)?;
+    let text = [output.stdout(), output.stderr()]
+        .into_iter()
+        .flatten()
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let re = Regex::new(r"(\d+)\.(\d+)(?:\.(\d+))?")?;
+    let Some(captures) = re.captures(&text) else {
+        return Ok(None);
+    };
+    let part = |i: usize| captures.get(i).map_or(Ok(0), |m| m.as_str().parse::<u64>());
+    Ok(Some(Version::new(part(1)?, part(2)?, part(3)?)))
 }
 
 impl CheckTrait for Requirement {
     fn do_check(
         &self,
-        diff_settings: &DiffSettings,
-        env: &Environment,
+        _diff_settings: &DiffSettings,
+        _env: &Environment,
         this_file_path: &Path,
-        vars: &HashMap<String, String>,
+        _vars: &HashMap<String, String>,
     ) -> Result<Status> {
         let status = match self {
-            Self::Command { command } => match which::which(command) {
-                Ok(_) => Status::new(false, StatusStatus::Pass),
-                Err(_) => Status::new(
-                    false,
-                    StatusStatus::Fail {
-                        reason: Reason {
-                            main: format!("Command not found '{command}'"),
-                            secondary: Some(format!(
-                                "Required for a check in {}",
-                                this_file_path.display()
-                            )),
-                        },
+            Self::Command {
+                command,
+                version,
+                install,
+            } => match which::which(command) {
+                Ok(_) => match version {
+                    Some(req) => match command_version(command)? {
+                        Some(found) if req.matches(&found) => {
+                            Status::new(false, StatusStatus::Pass)
+                        }
+                        Some(found) => Status::fail(
+                            format!("'{command}' {found} does not satisfy version {req}"),
+                            Some(requirement_details(this_file_path, install.as_ref())),
+                        ),
+                        None => Status::fail(
+                            format!("Unable to determine the version of '{command}'"),
+                            Some(requirement_details(this_file_path, install.as_ref())),
+                        ),
                     },
+                    None => Status::new(false, StatusStatus::Pass),
+                },
+                Err(_) => Status::fail(
+                    format!("Command not found '{command}'"),
+                    Some(requirement_details(this_file_path, install.as_ref())),
                 ),
             },
             Self::Env { key } => match env::var(key) {
                 Ok(_) => Status::new(false, StatusStatus::Pass),
-                Err(_) => Status::new(
-                    false,
-                    StatusStatus::Fail {
-                        reason: Reason {
-                            main: format!("Env var '{key}' not set"),
-                            secondary: Some(format!(
-                                "Required for a check in {}",
-                                this_file_path.display()
-                            )),
-                        },
-                    },
+                Err(_) => Status::fail(
+                    format!("Env var '{key}' not set"),
+                    Some(format!(
+                        "Required for a check in {}",
+                        this_file_path.display()
+                    )),
                 ),
             },
+            Self::Network { host } => {
+                if can_connect(host) {
+                    Status::new(false, StatusStatus::Pass)
+                } else {
+                    // Not the check's fault, so skip rather than fail
+                    Status::skip(
+                        format!("No network connection, unable to reach {host}"),
+                        None,
+                    )
+                }
+            }
+            Self::File { path } => {
+                if path.is_file() {
+                    Status::new(false, StatusStatus::Pass)
+                } else {
+                    Status::fail(
+                        format!("File '{}' does not exist", path.display()),
+                        Some(format!(
+                            "Required for a check in {}",
+                            this_file_path.display()
+                        )),
+                    )
+                }
+            }
         };
         Ok(status)
     }
@@ -607,6 +2019,17 @@ impl CheckTrait for Requirement {
     }
 }
 
+/// The `[templates]` table of a checklist
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct TemplatesSection {
+    /// Dirs, relative to the checklist file, whose templates are all registered
+    #[serde(default)]
+    dirs: Vec<PathBuf>,
+}
+
+/// Top level keys of a checklist file
+pub const CHECKLIST_KEYS: [&str; 5] = ["fact", "condition", "check", "requires", "templates"];
+
 #[derive(Debug, Deserialize)]
 struct ChecklistFileContents {
     #[serde(rename = "fact", default)]
@@ -617,6 +2040,8 @@ struct ChecklistFileContents {
     checks: Vec<Check>,
     #[serde(rename = "requires", default)]
     requirements: Vec<Requirement>,
+    #[serde(default)]
+    templates: TemplatesSection,
 }
 
 #[derive(Debug)]
@@ -633,10 +2058,22 @@ impl Checklist {
         Ok(Self { checks, path })
     }
 
+    /// Parse a checklist's contents, only to see whether they are valid
+    pub(crate) fn parse(contents: &str) -> Result<(), toml::de::Error> {
+        toml::from_str::<ChecklistFileContents>(contents).map(|_| ())
+    }
+
     pub fn checks(&self) -> &[Check] {
         &self.checks.checks
     }
 
+    pub(crate) fn set_project(&mut self, root: &Path, run_args: &[OsString]) {
+        self.checks
+            .checks
+            .iter_mut()
+            .for_each(|check| check.set_project(root, run_args));
+    }
+
     pub fn name(&self) -> Result<String> {
         let Some(name) = self.path.as_os_str().to_str() else {
             bail!("Unable to get name from path {}", self.path.display());
@@ -648,39 +2085,126 @@ impl Checklist {
         &self.path
     }
 
+    /// Templates the checklist uses: its declared template dirs, and templates of its checks
     pub fn templates(&self) -> Vec<PathBuf> {
-        let mut templates = Vec::new();
-        let checklist_path = &self.path;
+        let base = self.path.parent().unwrap();
+        let mut templates = self
+            .checks
+            .templates
+            .dirs
+            .iter()
+            .map(|dir| rel_to(base, dir))
+            .collect::<Vec<_>>();
         for check in &self.checks.checks {
-            match &check.check {
-                CheckType::File(f) => {
-                    if let Some(name) = &f.template {
-                        let template = rel_to(checklist_path.parent().unwrap(), name);
-                        debug!("found template {}", template.display());
-                        templates.push(template);
-                    }
-                }
-                CheckType::Directory(d) => {
-                    // TODO
-                }
-                CheckType::Command(c) => {
-                    // TODO
-                }
-                CheckType::Http(h) => {
-                    // TODO
-                }
-                CheckType::VarSet(v) => {
-                    // TODO
-                }
-            }
+            check_templates(check, &self.path, &mut templates);
         }
-
         templates
     }
 
     pub fn facts(&self) -> &[Fact] {
         &self.checks.facts
     }
+
+    pub fn conditions(&self) -> &[Condition] {
+        &self.checks.conditions
+    }
+
+    pub fn requirements(&self) -> &[Requirement] {
+        &self.checks.requirements
+    }
+
+    /// Checks in this checklist can't run without the network
+    pub fn needs_network(&self) -> bool {
+        self.checks.requirements.iter().any(|r| r.is_network())
+    }
+
+    /// Status of every check in this checklist, if the checklist's own conditions or requirements
+    /// aren't met. These apply before each check's own
+    pub fn inherited_status(
+        &self,
+        diff_settings: &DiffSettings,
+        env: &Environment,
+        vars: &HashMap<String, String>,
+    ) -> Result<Option<Status>> {
+        if let Some(status) = unmet_conditions(
+            &self.checks.conditions,
+            ConditionsMode::All,
+            diff_settings,
+            env,
+            &self.path,
+            vars,
+        )? {
+            return Ok(Some(status.inherited()));
+        }
+
+        for requirement in &self.checks.requirements {
+            let status = requirement.do_check(diff_settings, env, &self.path, vars)?;
+            if !status.is_success() {
+                return Ok(Some(status.inherited()));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn check_templates(check: &Check, checklist_path: &Path, templates: &mut Vec<PathBuf>) {
+    if let Some(Fix::Template(name)) = &check.fix {
+        let template = rel_to(checklist_path.parent().unwrap(), name);
+        debug!("found template {}", template.display());
+        templates.push(template);
+    }
+
+    match &check.check {
+        CheckType::File(f) => {
+            if let Some(name) = &f.template {
+                let template = rel_to(checklist_path.parent().unwrap(), name);
+                debug!("found template {}", template.display());
+                templates.push(template);
+            }
+        }
+        CheckType::Directory(_) => {
+            // TODO
+        }
+        CheckType::Command(_) => {
+            // TODO
+        }
+        CheckType::Http(h) => {
+            if let Some(name) = &h.schema_template {
+                let template = rel_to(checklist_path.parent().unwrap(), name);
+                debug!("found template {}", template.display());
+                templates.push(template);
+            }
+        }
+        CheckType::VarSet(_) => {
+            // TODO
+        }
+        CheckType::Freshness(_)
+        | CheckType::Sql(_)
+        | CheckType::LineLimits(_)
+        | CheckType::Whitespace(_)
+        | CheckType::Duplicates(_)
+        | CheckType::Gitignore(_)
+        | CheckType::Script(_)
+        | CheckType::Budget(_)
+        | CheckType::Secrets(_)
+        | CheckType::Platform(_)
+        | CheckType::Time(_)
+        | CheckType::Manual(_)
+        | CheckType::FrontMatter(_)
+        | CheckType::Spelling(_)
+        | CheckType::Image(_)
+        | CheckType::Binaries(_)
+        | CheckType::RustWorkspace(_)
+        | CheckType::Subproject(_) => {
+            // No templates
+        }
+        CheckType::Group(g) => {
+            for check in g.checks() {
+                check_templates(check, checklist_path, templates);
+            }
+        }
+    }
 }
 
 fn rel_to(a: &Path, b: &Path) -> PathBuf {
@@ -691,11 +2215,30 @@ fn rel_to(a: &Path, b: &Path) -> PathBuf {
 pub struct Reason {
     main: String,
     secondary: Option<String>,
+    /// Every assertion of a check that makes several, passed or not
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    assertions: Vec<Assertion>,
+    /// Where in a file the check failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
 }
 
 impl Reason {
     pub fn new(main: String, secondary: Option<String>) -> Self {
-        Self { main, secondary }
+        Self {
+            main,
+            secondary,
+            assertions: Vec::new(),
+            location: None,
+        }
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    pub fn assertions(&self) -> &[Assertion] {
+        &self.assertions
     }
 
     pub fn main(&self) -> &str {
@@ -717,45 +2260,167 @@ impl Display for Reason {
     }
 }
 
+/// Outcome of one of several assertions a check makes
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Assertion {
+    description: String,
+    passed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    location: Option<Location>,
+}
+
+impl Assertion {
+    pub fn description(&self) -> &str {
+        &self.description
+    }
+
+    pub fn passed(&self) -> bool {
+        self.passed
+    }
+
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+/// Status of a check made of several assertions (`noun`), reporting all of them instead of only
+/// the first to fail. A single assertion's status is returned as is
+fn combine(results: Vec<(String, Status)>, noun: &str) -> Status {
+    let failed = results
+        .iter()
+        .filter(|(_, status)| status.is_failure())
+        .collect::<Vec<_>>();
+    if failed.is_empty() {
+        return Status::new(false, StatusStatus::Pass);
+    }
+    if results.len() == 1 {
+        return results.into_iter().next().unwrap().1;
+    }
+
+    let main = format!("{} of {} {noun} failed", failed.len(), results.len());
+    let secondary = failed
+        .iter()
+        .filter_map(|(description, status)| match status.status() {
+            StatusStatus::Fail { reason } => Some(format!("{description}: {reason}")),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    let assertions = results
+        .iter()
+        .map(|(description, status)| Assertion {
+            description: description.clone(),
+            passed: !status.is_failure(),
+            location: status.location().cloned(),
+        })
+        .collect::<Vec<_>>();
+    let mut reason = Reason::new(main, Some(secondary));
+    reason.location = assertions
+        .iter()
+        .find(|assertion| !assertion.passed)
+        .and_then(|assertion| assertion.location.clone());
+    reason.assertions = assertions;
+    Status::new(false, StatusStatus::Fail { reason })
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum StatusStatus {
     Pass,
-    Skip { reason: Reason },
-    Fail { reason: Reason },
+    Skip {
+        reason: Reason,
+    },
+    Fail {
+        reason: Reason,
+    },
+    /// Can't be decided automatically, a human has to look at it
+    Review {
+        reason: Reason,
+    },
+    /// Failed, but the check is less severe than '--fail-on', so the run doesn't fail
+    Warn {
+        reason: Reason,
+    },
+    /// Failed, but the failure is in the project's baseline, so the run doesn't fail
+    Known {
+        reason: Reason,
+    },
 }
 
 impl StatusStatus {
-    pub fn is_skipped(&self) -> bool {
+    /// Short lowercase name of the outcome, without the reason
+    pub fn label(&self) -> &'static str {
         match self {
-            StatusStatus::Skip { .. } => true,
-            _ => false,
+            StatusStatus::Pass => "pass",
+            StatusStatus::Skip { .. } => "skip",
+            StatusStatus::Fail { .. } => "fail",
+            StatusStatus::Review { .. } => "review",
+            StatusStatus::Warn { .. } => "warn",
+            StatusStatus::Known { .. } => "known",
         }
     }
 
-    pub fn is_success(&self) -> bool {
+    pub fn reason(&self) -> Option<&Reason> {
         match self {
-            StatusStatus::Pass => true,
-            _ => false,
+            StatusStatus::Pass => None,
+            StatusStatus::Skip { reason }
+            | StatusStatus::Fail { reason }
+            | StatusStatus::Review { reason }
+            | StatusStatus::Warn { reason }
+            | StatusStatus::Known { reason } => Some(reason),
         }
     }
 
+    pub fn is_skipped(&self) -> bool {
+        matches!(self, StatusStatus::Skip { .. })
+    }
+
+    pub fn is_success(&self) -> bool {
+        matches!(self, StatusStatus::Pass)
+    }
+
     pub fn is_failure(&self) -> bool {
-        match self {
-            StatusStatus::Fail { .. } => true,
-            _ => false,
-        }
+        matches!(self, StatusStatus::Fail { .. })
+    }
+
+    pub fn needs_review(&self) -> bool {
+        matches!(self, StatusStatus::Review { .. })
+    }
+
+    pub fn is_warning(&self) -> bool {
+        matches!(self, StatusStatus::Warn { .. })
+    }
+
+    pub fn is_known(&self) -> bool {
+        matches!(self, StatusStatus::Known { .. })
     }
 }
 
+#[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Status {
     status: StatusStatus,
     cached: bool,
+    /// How long the check took to run, in the run that produced this status
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(
+        default,
+        rename = "duration_ms",
+        skip_serializing_if = "Option::is_none"
+    )]
+    duration: Option<Duration>,
+    /// Results of the nested run the check made, e.g. on a subproject
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    nested: Option<Box<Statuses>>,
 }
 
 impl Status {
     pub fn new(cached: bool, status: StatusStatus) -> Self {
-        Self { cached, status }
+        Self {
+            cached,
+            status,
+            duration: None,
+            nested: None,
+        }
     }
 
     pub fn fail(main: String, secondary: Option<String>) -> Self {
@@ -767,6 +2432,96 @@ impl Status {
         )
     }
 
+    pub fn skip(main: String, secondary: Option<String>) -> Self {
+        Self::new(
+            false,
+            StatusStatus::Skip {
+                reason: Reason::new(main, secondary),
+            },
+        )
+    }
+
+    pub fn review(main: String, secondary: Option<String>) -> Self {
+        Self::new(
+            false,
+            StatusStatus::Review {
+                reason: Reason::new(main, secondary),
+            },
+        )
+    }
+
+    /// Same outcome, with the reason pointing at a place in a file
+    pub fn with_location(mut self, location: Location) -> Self {
+        match &mut self.status {
+            StatusStatus::Pass => {}
+            StatusStatus::Skip { reason }
+            | StatusStatus::Fail { reason }
+            | StatusStatus::Review { reason }
+            | StatusStatus::Warn { reason }
+            | StatusStatus::Known { reason } => reason.location = Some(location),
+        }
+        self
+    }
+
+    /// Where in a file the check failed, if known
+    pub fn location(&self) -> Option<&Location> {
+        match &self.status {
+            StatusStatus::Pass => None,
+            StatusStatus::Skip { reason }
+            | StatusStatus::Fail { reason }
+            | StatusStatus::Review { reason }
+            | StatusStatus::Warn { reason }
+            | StatusStatus::Known { reason } => reason.location(),
+        }
+    }
+
+    /// Same outcome, with the reason marked as coming from the checklist rather than the check
+    pub fn inherited(&self) -> Self {
+        let mark = |reason: &Reason| {
+            Reason::new(
+                format!("{} (inherited from checklist)", reason.main()),
+                reason.secondary().cloned(),
+            )
+        };
+        let status = match &self.status {
+            StatusStatus::Pass => StatusStatus::Pass,
+            StatusStatus::Skip { reason } => StatusStatus::Skip {
+                reason: mark(reason),
+            },
+            StatusStatus::Fail { reason } => StatusStatus::Fail {
+                reason: mark(reason),
+            },
+            StatusStatus::Review { reason } => StatusStatus::Review {
+                reason: mark(reason),
+            },
+            StatusStatus::Warn { reason } => StatusStatus::Warn {
+                reason: mark(reason),
+            },
+            StatusStatus::Known { reason } => StatusStatus::Known {
+                reason: mark(reason),
+            },
+        };
+        Self::new(self.cached, status)
+    }
+
+    /// Same outcome, except a failure of a check less severe than `fail_on` is a warning
+    pub fn with_severity(mut self, severity: Severity, fail_on: Severity) -> Self {
+        if severity < fail_on
+            && let StatusStatus::Fail { reason } = self.status
+        {
+            self.status = StatusStatus::Warn { reason };
+        }
+        self
+    }
+
+    /// Same outcome, except a failure is a known one
+    pub fn known(mut self) -> Self {
+        if let StatusStatus::Fail { reason } = self.status {
+            self.status = StatusStatus::Known { reason };
+        }
+        self
+    }
+
     pub fn mark_as_cached(&mut self) {
         self.cached = true;
     }
@@ -775,6 +2530,24 @@ impl Status {
         self.cached
     }
 
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = Some(duration);
+    }
+
+    pub fn duration(&self) -> Option<Duration> {
+        self.duration
+    }
+
+    /// Same outcome, along with the results of the nested run that produced it
+    pub fn with_nested(mut self, statuses: Statuses) -> Self {
+        self.nested = Some(Box::new(statuses));
+        self
+    }
+
+    pub fn nested(&self) -> Option<&Statuses> {
+        self.nested.as_deref()
+    }
+
     pub fn is_skipped(&self) -> bool {
         self.status.is_skipped()
     }
@@ -787,6 +2560,18 @@ impl Status {
         self.status.is_failure()
     }
 
+    pub fn needs_review(&self) -> bool {
+        self.status.needs_review()
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.status.is_warning()
+    }
+
+    pub fn is_known(&self) -> bool {
+        self.status.is_known()
+    }
+
     pub fn status(&self) -> &StatusStatus {
         &self.status
     }
@@ -798,15 +2583,265 @@ impl Display for Status {
             StatusStatus::Pass => "Pass",
             StatusStatus::Skip { reason } => &format!("Skipped ({reason})"),
             StatusStatus::Fail { reason } => &format!("Failed ({reason})"),
+            StatusStatus::Review { reason } => &format!("Needs review ({reason})"),
+            StatusStatus::Warn { reason } => &format!("Warning ({reason})"),
+            StatusStatus::Known { reason } => &format!("Known failure ({reason})"),
         };
         write!(f, "{s}")
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+fn default_pass_code() -> i32 {
+    exitcode::OK
+}
+
+fn default_skip_code() -> i32 {
+    1
+}
+
+fn default_fail_code() -> i32 {
+    1
+}
+
+fn default_review_code() -> i32 {
+    exitcode::OK
+}
+
+fn default_warn_code() -> i32 {
+    exitcode::OK
+}
+
+fn default_known_code() -> i32 {
+    exitcode::OK
+}
+
+fn default_regression_code() -> i32 {
+    default_fail_code()
+}
+
+/// Exit code to use for each check outcome
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExitCodes {
+    #[serde(default = "default_pass_code")]
+    pass: i32,
+    #[serde(default = "default_skip_code")]
+    skip: i32,
+    #[serde(default = "default_fail_code")]
+    fail: i32,
+    #[serde(default = "default_review_code")]
+    review: i32,
+    /// A check less severe than '--fail-on' failed
+    #[serde(default = "default_warn_code")]
+    warn: i32,
+    /// A check failed, but the failure is in the baseline
+    #[serde(default = "default_known_code")]
+    known: i32,
+    /// 'diff' found checks failing that didn't in the baseline run
+    #[serde(default = "default_regression_code")]
+    regression: i32,
+}
+
+impl ExitCodes {
+    pub fn regression(&self) -> i32 {
+        self.regression
+    }
+}
+
+impl Default for ExitCodes {
+    fn default() -> Self {
+        Self {
+            pass: default_pass_code(),
+            skip: default_skip_code(),
+            fail: default_fail_code(),
+            review: default_review_code(),
+            warn: default_warn_code(),
+            known: default_known_code(),
+            regression: default_regression_code(),
+        }
+    }
+}
+
+/// Which check results to report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+pub enum Show {
+    #[default]
+    #[serde(rename = "all")]
+    #[value(name = "all")]
+    All,
+    #[serde(rename = "failed")]
+    #[value(name = "failed")]
+    Failed,
+    #[serde(rename = "failed+skipped")]
+    #[value(name = "failed+skipped")]
+    FailedAndSkipped,
+}
+
+impl Show {
+    pub fn includes(&self, status: &Status) -> bool {
+        match self {
+            Self::All => true,
+            Self::Failed => status.is_failure() || status.is_warning(),
+            Self::FailedAndSkipped => {
+                status.is_failure()
+                    || status.is_warning()
+                    || status.is_known()
+                    || status.is_skipped()
+                    || status.needs_review()
+            }
+        }
+    }
+}
+
+/// How much a failing check matters. Failures of checks less severe than '--fail-on' are
+/// reported as warnings and don't fail the run
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    #[default]
+    Error,
+}
+
+/// Overall outcome of a run
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq, derive_more::Display)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Verdict {
+    #[display("PASS")]
+    Pass,
+    #[display("FAIL")]
+    Fail,
+    #[display("REVIEW")]
+    Review,
+}
+
+/// Outcome of a run at a glance
+#[derive(Debug, Serialize)]
+pub struct Summary {
+    verdict: Verdict,
+    exit_code: i32,
+    counts: Counts,
+}
+
+/// How many checks ended up with each outcome
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Counts {
+    pub passed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    #[serde(default)]
+    pub review: usize,
+    /// Failed, but not severe enough to fail the run
+    #[serde(default)]
+    pub warned: usize,
+    /// Failed, but a known failure in the baseline
+    #[serde(default)]
+    pub known: usize,
+    /// Of all of the above, how many came from the cache
+    #[serde(default)]
+    pub cached: usize,
+}
+
+impl Counts {
+    pub fn add(&mut self, status: &Status) {
+        if status.is_success() {
+            self.passed += 1;
+        } else if status.is_failure() {
+            self.failed += 1;
+        } else if status.is_skipped() {
+            self.skipped += 1;
+        } else if status.needs_review() {
+            self.review += 1;
+        } else if status.is_warning() {
+            self.warned += 1;
+        } else if status.is_known() {
+            self.known += 1;
+        }
+        if status.is_cached() {
+            self.cached += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &Counts) {
+        self.passed += other.passed;
+        self.failed += other.failed;
+        self.skipped += other.skipped;
+        self.review += other.review;
+        self.warned += other.warned;
+        self.known += other.known;
+        self.cached += other.cached;
+    }
+
+    pub fn total(&self) -> usize {
+        self.passed + self.failed + self.skipped + self.review + self.warned + self.known
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        if self.failed > 0 {
+            Verdict::Fail
+        } else if self.review > 0 {
+            Verdict::Review
+        } else {
+            Verdict::Pass
+        }
+    }
+}
+
+impl Display for Counts {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} skipped",
+            self.passed, self.failed, self.skipped
+        )?;
+        if self.warned > 0 {
+            write!(f, ", {} warned", self.warned)?;
+        }
+        if self.known > 0 {
+            write!(f, ", {} known", self.known)?;
+        }
+        if self.review > 0 {
+            write!(f, ", {} need review", self.review)?;
+        }
+        Ok(())
+    }
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Statuses {
-    /// Map checklist path to map of check name to check status
-    map: HashMap<PathBuf, HashMap<String, Status>>,
+    /// Unique id of the run that produced these statuses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    run_id: Option<Uuid>,
+    /// Name of the project the checks ran against
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    project: Option<String>,
+    /// Arbitrary metadata about the run (commit SHA, CI job, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    meta: BTreeMap<String, String>,
+    /// How long the run took
+    #[serde_as(as = "Option<DurationMilliSeconds<u64>>")]
+    #[serde(
+        default,
+        rename = "duration_ms",
+        skip_serializing_if = "Option::is_none"
+    )]
+    duration: Option<Duration>,
+    /// Map checklist path to map of check name to check status, both in the order they ran
+    map: IndexMap<PathBuf, IndexMap<String, Status>>,
 }
 
 impl Default for Statuses {
@@ -817,56 +2852,232 @@ impl Default for Statuses {
 
 impl Statuses {
     pub fn new() -> Self {
-        let map = HashMap::new();
-        Self { map }
+        let map = IndexMap::new();
+        Self {
+            run_id: None,
+            project: None,
+            meta: BTreeMap::new(),
+            duration: None,
+            map,
+        }
+    }
+
+    /// Read a results file written with '--output json'
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Unable to read results file {}", path.display()))?;
+        let statuses = serde_json::from_str(&contents)
+            .with_context(|| format!("Invalid results file {}", path.display()))?;
+        Ok(statuses)
+    }
+
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = Some(duration);
+    }
+
+    pub fn set_run_id(&mut self, run_id: Uuid) {
+        self.run_id = Some(run_id);
+    }
+
+    pub fn run_id(&self) -> Option<Uuid> {
+        self.run_id
+    }
+
+    pub fn set_project(&mut self, project: String) {
+        self.project = Some(project);
+    }
+
+    pub fn project(&self) -> Option<&str> {
+        self.project.as_deref()
+    }
+
+    pub fn set_meta(&mut self, meta: BTreeMap<String, String>) {
+        self.meta = meta;
+    }
+
+    pub fn meta(&self) -> &BTreeMap<String, String> {
+        &self.meta
+    }
+
+    /// Every check's checklist path, name, and status
+    pub fn iter(&self) -> impl Iterator<Item = (&Path, &str, &Status)> {
+        self.map.iter().flat_map(|(checklist_path, checks)| {
+            checks
+                .iter()
+                .map(move |(name, status)| (checklist_path.as_path(), name.as_str(), status))
+        })
+    }
+
+    pub fn counts(&self) -> Counts {
+        let mut counts = Counts::default();
+        for (_, _, status) in self.iter() {
+            counts.add(status);
+        }
+        counts
+    }
+
+    /// No check failed or needs review
+    pub fn is_green(&self) -> bool {
+        !self.any(Status::is_failure) && !self.any(Status::needs_review)
+    }
+
+    fn any(&self, predicate: impl Fn(&Status) -> bool) -> bool {
+        self.map
+            .values()
+            .any(|checks| checks.values().any(&predicate))
+    }
+
+    /// Exit code of the most severe outcome that maps to a non-zero code
+    pub fn exit_code(&self, codes: &ExitCodes) -> i32 {
+        let outcomes = [
+            (codes.fail, self.any(Status::is_failure)),
+            (codes.review, self.any(Status::needs_review)),
+            (codes.warn, self.any(Status::is_warning)),
+            (codes.known, self.any(Status::is_known)),
+            (codes.skip, self.any(Status::is_skipped)),
+            (codes.pass, self.any(Status::is_success)),
+        ];
+
+        outcomes
+            .into_iter()
+            .find(|(code, present)| *present && *code != 0)
+            .map(|(code, _)| code)
+            .unwrap_or(exitcode::OK)
     }
 
-    pub fn exit_code(&self) -> i32 {
-        let mut code = 0;
-        for checklist in self.map.values() {
-            for status in checklist.values() {
-                if *status.status() != StatusStatus::Pass {
-                    code = 1;
-                    break;
+    pub fn insert(&mut self, checklist_path: PathBuf, job_name: String, job_status: Status) {
+        self.map
+            .entry(checklist_path)
+            .or_default()
+            .insert(job_name, job_status);
+    }
+
+    /// Copy of these statuses with only the checks `show` asks for
+    pub fn filtered(&self, show: Show) -> Self {
+        let mut filtered = Self::new();
+        filtered.run_id = self.run_id;
+        filtered.project = self.project.clone();
+        filtered.set_meta(self.meta.clone());
+        filtered.duration = self.duration;
+        for (checklist_path, checks) in &self.map {
+            for (name, status) in checks {
+                if show.includes(status) {
+                    filtered.insert(checklist_path.clone(), name.clone(), status.clone());
                 }
             }
         }
+        filtered
+    }
 
-        code
+    pub fn summary(&self, codes: &ExitCodes) -> Summary {
+        let counts = self.counts();
+        Summary {
+            verdict: counts.verdict(),
+            exit_code: self.exit_code(codes),
+            counts,
+        }
     }
 
-    pub fn insert(&mut self, checklist_path: PathBuf, job_name: String, job_status: Status) {
-        if !self.map.contains_key(&checklist_path) {
-            self.map.insert(checklist_path.clone(), HashMap::new());
+    /// Counts and how long the run took, e.g. "12 passed, 2 failed, 3 skipped (1 cached) in 4.2s"
+    pub fn summary_line(&self) -> String {
+        let counts = self.counts();
+        let mut line = counts.to_string();
+        if counts.cached > 0 {
+            line.push_str(&format!(" ({} cached)", counts.cached));
         }
+        if let Some(duration) = self.duration {
+            line.push_str(&format!(" in {:.1}s", duration.as_secs_f64()));
+        }
+        line
+    }
 
-        let inner = self.map.get_mut(&checklist_path).unwrap();
-        inner.insert(job_name, job_status);
+    /// Single greppable line, e.g. "checklints: FAIL (41 passed, 3 failed, 0 skipped)"
+    pub fn verdict_line(&self) -> String {
+        let counts = self.counts();
+        format!("{THIS_CRATE_NAME}: {} ({counts})", counts.verdict())
     }
 
-    pub fn json(&self) -> Result<String> {
-        let json = serde_json::to_string_pretty(&self)?;
+    pub fn json(&self, codes: &ExitCodes) -> Result<String> {
+        self.shown_json(Show::All, codes)
+    }
+
+    /// JSON of the checks `show` includes, with a summary of them all
+    pub fn shown_json(&self, show: Show, codes: &ExitCodes) -> Result<String> {
+        #[derive(Serialize)]
+        struct Report<'a> {
+            #[serde(flatten)]
+            statuses: &'a Statuses,
+            summary: Summary,
+        }
+
+        let report = Report {
+            statuses: &self.filtered(show),
+            summary: self.summary(codes),
+        };
+        let json = serde_json::to_string_pretty(&report)?;
         Ok(json)
     }
 
-    pub fn print(&self) {
-        let last_index = self.map.len() - 1;
+    /// Write the full reason of every failed check into `dir`
+    pub fn write_artifacts(&self, dir: &Path) -> Result<()> {
+        for (checklist_path, checks) in &self.map {
+            for (name, status) in checks {
+                let StatusStatus::Fail { reason } = status.status() else {
+                    continue;
+                };
+                let Some(secondary) = reason.secondary() else {
+                    continue;
+                };
+
+                let path = artifact_path(dir, checklist_path, name);
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                debug!("Writing artifact {}", path.display());
+                fs::write(&path, secondary)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn print(&self, settings: &Settings) {
+        if let Some(project) = &self.project {
+            println!("Project '{}'", project.bold());
+        }
+
+        for (key, value) in &self.meta {
+            println!("{}", format!("{key}: {value}").dimmed());
+        }
+
+        if self.project.is_some() || !self.meta.is_empty() {
+            println!();
+        }
+
+        let last_index = self.map.len().saturating_sub(1);
         for (i, (checklist_path, checks)) in self.map.iter().enumerate() {
             let checklist_name = checklist_path.file_name().unwrap().to_str().unwrap();
             print_section_header(checklist_name);
 
             for (name, status) in checks {
-                print_status(status, name, None); // TODO: introduce timings back
+                let artifact = settings
+                    .artifacts_dir()
+                    .map(|dir| artifact_path(dir, checklist_path, name));
+                print_status(status, name, settings.full_diff(), artifact.as_deref());
             }
 
             if i < last_index {
                 println!();
             }
         }
+
+        if let Some(run_id) = self.run_id {
+            println!();
+            println!("{}", format!("Run {run_id}").dimmed());
+        }
     }
 }
 
-fn format_duration(d: Duration) -> String {
+pub(crate) fn format_duration(d: Duration) -> String {
     let truncated = Duration::from_millis(d.as_millis() as u64);
     if truncated == Duration::ZERO {
         "< 1ms".to_string()
@@ -879,14 +3090,62 @@ fn print_section_header(name: &str) {
     println!("> Checklist '{}'", name.cyan());
 }
 
-fn print_status(status: &Status, desc: &str, duration: Option<Duration>) {
-    let (status_str, reason) = match status.status() {
+fn slug(s: &str) -> String {
+    s.chars()
+        .take(MAX_SLUG_LEN)
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn artifact_path(dir: &Path, checklist_path: &Path, check_name: &str) -> PathBuf {
+    let checklist_name = checklist_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    dir.join(slug(&checklist_name))
+        .join(format!("{}.diff", slug(check_name)))
+}
+
+fn print_secondary(secondary: &str, full_diff: bool, artifact: Option<&Path>) {
+    let lines: Vec<&str> = secondary.lines().collect();
+    if full_diff || lines.len() <= MAX_DIFF_LINES {
+        println!("{secondary}");
+        return;
+    }
+
+    for line in &lines[..MAX_DIFF_LINES] {
+        println!("{line}");
+    }
+    let more = lines.len() - MAX_DIFF_LINES;
+    let hint = match artifact {
+        Some(artifact) => format!("use --full-diff or see {}", artifact.display()),
+        None => String::from("use --full-diff or --artifacts-dir"),
+    };
+    println!("{}", format!("… {more} more lines ({hint})").dimmed());
+}
+
+/// Label of the outcome, and its reason
+fn status_label(status: &Status) -> (ColoredString, Option<&Reason>) {
+    match status.status() {
         StatusStatus::Skip { reason } => ("SKIP".yellow(), Some(reason)),
         StatusStatus::Pass => ("PASS".green(), None),
         StatusStatus::Fail { reason } => ("FAIL".red(), Some(reason)),
-    };
+        StatusStatus::Review { reason } => ("NEEDS REVIEW".blue(), Some(reason)),
+        StatusStatus::Warn { reason } => ("WARN".yellow(), Some(reason)),
+        StatusStatus::Known { reason } => ("KNOWN".magenta(), Some(reason)),
+    }
+}
+
+fn print_status(status: &Status, desc: &str, full_diff: bool, artifact: Option<&Path>) {
+    let (status_str, reason) = status_label(status);
     let cached = if status.is_cached() { " (cached)" } else { "" };
-    let duration = if let Some(duration) = duration {
+    let duration = if let Some(duration) = status.duration() {
         &format!(" - took {}", format_duration(duration))
     } else {
         ""
@@ -901,10 +3160,60 @@ fn print_status(status: &Status, desc: &str, duration: Option<Duration>) {
     if let Some(reason) = reason {
         let subindent = format!("{INDENT}  ");
         print!("{INDENT}{subindent}- {}", reason.main().purple());
+        if reason.secondary().is_some() {
+            print!(":");
+        }
+        println!();
+        // Assertions each point at their own location
+        if let Some(location) = reason.location()
+            && reason.assertions().is_empty()
+        {
+            println!(
+                "{INDENT}{subindent}  {}",
+                format!("--> {location}").dimmed()
+            );
+        }
+        for assertion in reason.assertions() {
+            let mark = if assertion.passed {
+                "✓".green()
+            } else {
+                "✗".red()
+            };
+            let location = match &assertion.location {
+                Some(location) => format!(" --> {location}"),
+                None => String::new(),
+            };
+            println!(
+                "{INDENT}{subindent}{INDENT}{mark} {}{}",
+                assertion.description,
+                location.dimmed()
+            );
+        }
         if let Some(secondary) = reason.secondary() {
-            println!(":\n{secondary}");
-        } else {
-            println!();
+            print_secondary(secondary, full_diff, artifact);
+        }
+    }
+
+    if let Some(nested) = status.nested() {
+        print_nested(nested, 2);
+    }
+}
+
+/// Results of a nested run, one line per check, under the check that made the run
+fn print_nested(statuses: &Statuses, depth: usize) {
+    let indent = INDENT.repeat(depth);
+    for (checklist_path, checks) in &statuses.map {
+        let checklist_name = checklist_path.file_name().unwrap_or_default();
+        println!("{indent}{}", checklist_name.to_string_lossy().bold());
+        for (name, status) in checks {
+            let (status_str, reason) = status_label(status);
+            println!("{indent}{INDENT}[{}] {name}", status_str.bold());
+            if let Some(reason) = reason {
+                println!("{indent}{INDENT}{INDENT}- {}", reason.main().purple());
+            }
+            if let Some(nested) = status.nested() {
+                print_nested(nested, depth + 2);
+            }
         }
     }
 }
@@ -919,10 +3228,14 @@ mod remote_checklist {
     use std::str::FromStr;
     use winnow::ascii::dec_uint;
     use winnow::combinator::alt;
+    use winnow::combinator::not;
     use winnow::combinator::opt;
+    use winnow::combinator::repeat;
     use winnow::combinator::seq;
 
     use winnow::prelude::*;
+    use winnow::token::any;
+    use winnow::token::none_of;
     use winnow::token::rest;
     use winnow::token::take_till;
     use winnow::token::take_until;
@@ -942,6 +3255,10 @@ mod remote_checklist {
     }
 
     impl Url {
+        pub fn scheme(&self) -> &str {
+            &self.scheme
+        }
+
         pub fn name(&self) -> String {
             match &self.path {
                 Some(path) => last_component_of(path),
@@ -1009,7 +3326,8 @@ mod remote_checklist {
 
     fn fragment(s: &mut &str) -> Result<String> {
         let _ = "#".parse_next(s)?;
-        take_until(1.., "::")
+        repeat::<_, _, (), _, _>(1.., (not("::"), any))
+            .take()
             .map(|s: &str| s.to_string())
             .parse_next(s)
     }
@@ -1026,8 +3344,10 @@ mod remote_checklist {
         .parse_next(s)
     }
 
+    /// Everything up to a query, fragment or '::hash'
     fn path(s: &mut &str) -> Result<String> {
-        alt((take_till(0.., |c: char| c == '?' || c == '#'), rest))
+        repeat::<_, _, (), _, _>(0.., (not("::"), none_of(['?', '#'])))
+            .take()
             .map(|s: &str| s.to_string())
             .parse_next(s)
     }
@@ -1053,3 +3373,142 @@ mod remote_checklist {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ordered_template_reports_only_missing_lines() {
+        let expected = "a\nb\nc\nd\n";
+        let actual = "a\nc\nd\n";
+        assert_eq!(TemplateMode::Ordered.missing_lines(expected, actual), ["b"]);
+        assert_eq!(
+            TemplateMode::Ordered.missing_lines(expected, "a\nd\nc\n"),
+            ["b", "d"]
+        );
+    }
+
+    #[test]
+    fn warnings_and_known_failures_have_their_own_exit_codes() {
+        let codes: ExitCodes = toml::from_str("warn = 2\nknown = 3\nregression = 4").unwrap();
+        let fail = || Status::fail(String::from("File missing"), None);
+        let mut statuses = Statuses::new();
+        statuses.insert(
+            PathBuf::from("a.toml"),
+            String::from("known"),
+            fail().known(),
+        );
+        assert_eq!(statuses.exit_code(&codes), 3);
+
+        let warn = fail().with_severity(Severity::Warning, Severity::Error);
+        statuses.insert(PathBuf::from("a.toml"), String::from("warns"), warn);
+        assert_eq!(statuses.exit_code(&codes), 2);
+        assert_eq!(statuses.exit_code(&ExitCodes::default()), 0);
+        assert_eq!(codes.regression(), 4);
+        assert_eq!(ExitCodes::default().regression(), 1);
+
+        let mut skipped = Statuses::new();
+        let skip = Status::skip(String::from("Offline"), None);
+        skipped.insert(PathBuf::from("a.toml"), String::from("skips"), skip);
+        assert_eq!(skipped.exit_code(&ExitCodes::default()), 1);
+    }
+
+    #[test]
+    fn json_shows_only_the_checks_asked_for() {
+        let mut statuses = Statuses::new();
+        let path = PathBuf::from("a.toml");
+        statuses.insert(
+            path.clone(),
+            String::from("passes"),
+            Status::new(false, StatusStatus::Pass),
+        );
+        statuses.insert(
+            path,
+            String::from("fails"),
+            Status::fail(String::from("File missing"), None),
+        );
+
+        let json = statuses
+            .shown_json(Show::Failed, &ExitCodes::default())
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        let checks = json["map"]["a.toml"].as_object().unwrap();
+        assert_eq!(checks.keys().collect::<Vec<_>>(), ["fails"]);
+        assert_eq!(json["summary"]["counts"]["passed"], 1);
+        assert_eq!(json["summary"]["exit_code"], 1);
+    }
+
+    #[test]
+    fn network_requirement_needs_a_host() {
+        assert!(toml::from_str::<Requirement>("type = \"network\"").is_err());
+        let requirement: Requirement =
+            toml::from_str("type = \"network\"\nhost = \"crates.io:443\"").unwrap();
+        assert!(requirement.is_network());
+    }
+
+    #[test]
+    fn json_command_fact_provides_each_key() {
+        let fact: Fact = toml::from_str(
+            r#"
+            type = "eval-command-json"
+            command = "echo '{\"packages\": [{\"name\": \"checklints\", \"version\": \"0.2.1\"}]}'"
+            keys = { PROJECT_NAME = "$.packages[0].name", VERSION = "$.packages[0].version" }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(fact.keys(), ["PROJECT_NAME", "VERSION"]);
+        let values = fact
+            .values(Path::new("checklist.toml"), &HashMap::new())
+            .unwrap();
+        assert_eq!(
+            values,
+            [
+                (String::from("PROJECT_NAME"), String::from("checklints")),
+                (String::from("VERSION"), String::from("0.2.1")),
+            ]
+        );
+    }
+
+    #[test]
+    fn file_fact_is_relative_to_the_checklist() {
+        let dir = std::env::temp_dir().join(format!("checklints-fact-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("VERSION"), "version 0.2.1\n").unwrap();
+        let fact: Fact = toml::from_str(
+            r#"
+            key = "VERSION"
+            type = "file"
+            path = "VERSION"
+            regex = 'version (\S+)'
+            "#,
+        )
+        .unwrap();
+        let values = fact.values(&dir.join("checklist.toml"), &HashMap::new());
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            values.unwrap(),
+            [(String::from("VERSION"), String::from("0.2.1"))]
+        );
+    }
+
+    #[test]
+    fn command_reports_every_missing_fragment() {
+        let check: CommandCheck =
+            toml::from_str("cmd = \"echo hello\"\nstdout_contains = [\"hello\", \"a\", \"b\"]")
+                .unwrap();
+        let vars = HashMap::new();
+        let status = check
+            .do_check(
+                &DiffSettings::new(),
+                &Environment::new(),
+                Path::new("checklists/a.toml"),
+                &vars,
+            )
+            .unwrap();
+        let StatusStatus::Fail { reason } = status.status() else {
+            panic!("expected a failure, got {status:?}");
+        };
+        assert_eq!(reason.main(), "2 of 3 assertions failed");
+    }
+}
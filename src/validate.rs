@@ -0,0 +1,335 @@
+use crate::project::Project;
+use crate::types::{Check, CheckType, Condition, Requirement, TemplatesSection, CHECKLIST_KEYS};
+use annotate_snippets::{Level, Renderer, Snippet};
+use colored::Colorize;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::fmt::Display;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml_edit::{ImDocument, Item, Table, TableLike};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// Something wrong with a project's checklists or templates
+#[derive(Debug)]
+pub struct Problem {
+    severity: Severity,
+    /// Template or checklist the problem is in
+    source: String,
+    line: Option<usize>,
+    message: String,
+    /// The problem rendered over the offending part of the source, when it's known
+    snippet: Option<String>,
+}
+
+impl Problem {
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error".red().bold(),
+            Severity::Warning => "warning".yellow().bold(),
+        };
+        if let Some(snippet) = &self.snippet {
+            return write!(f, "{snippet}");
+        }
+        let location = match self.line {
+            Some(line) => format!("{}:{line}", self.source),
+            None => self.source.clone(),
+        };
+        write!(f, "{severity}: {}\n --> {location}", self.message)
+    }
+}
+
+/// Template syntax errors, and variables templates use that nothing provides
+pub fn lint_templates(project: &Project) -> Vec<Problem> {
+    let mut problems = Vec::new();
+
+    for e in project.template_errors() {
+        problems.push(Problem {
+            severity: Severity::Error,
+            source: e.name().unwrap_or_default().to_string(),
+            line: e.line(),
+            message: e.detail().unwrap_or("invalid template").to_string(),
+            snippet: None,
+        });
+    }
+
+    let env = project.template_env();
+    let mut provided = project
+        .checklists()
+        .iter()
+        .flat_map(|checklist| checklist.facts())
+        .flat_map(|fact| fact.keys())
+        .collect::<HashSet<_>>();
+    provided.extend(project.facts().keys().cloned());
+    provided.extend(
+        project
+            .checklists()
+            .iter()
+            .flat_map(|checklist| checklist.checks())
+            .filter_map(|check| match check.ttype() {
+                CheckType::File(f) => Some(f.vars().keys().cloned()),
+                _ => None,
+            })
+            .flatten(),
+    );
+    provided.extend(env.globals().map(|(name, _)| name.to_string()));
+
+    for (name, template) in env.templates() {
+        let mut undeclared = template
+            .undeclared_variables(false)
+            .into_iter()
+            .filter(|var| !provided.contains(var))
+            .collect::<Vec<_>>();
+        undeclared.sort();
+        for var in undeclared {
+            problems.push(Problem {
+                severity: Severity::Warning,
+                source: name.to_string(),
+                line: None,
+                message: format!("variable '{var}' is not provided by any fact"),
+                snippet: None,
+            });
+        }
+    }
+
+    problems
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A node of a parsed checklist, keeping spans
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Item(&'a Item),
+    Table(&'a Table),
+    Value(&'a toml_edit::Value),
+}
+
+impl<'a> Node<'a> {
+    fn table_like(self) -> Option<&'a dyn TableLike> {
+        match self {
+            Self::Item(item) => item.as_table_like(),
+            Self::Table(table) => Some(table),
+            Self::Value(value) => value.as_inline_table().map(|t| t as &dyn TableLike),
+        }
+    }
+
+    fn element(self, i: usize) -> Option<Node<'a>> {
+        match self {
+            Self::Item(Item::ArrayOfTables(tables)) => tables.get(i).map(Self::Table),
+            Self::Item(Item::Value(value)) | Self::Value(value) => {
+                value.as_array()?.get(i).map(Self::Value)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Span of the last key in `path`
+fn key_span(doc: &Table, path: &[Segment]) -> Option<Range<usize>> {
+    let mut node = Node::Table(doc);
+    let mut span = None;
+    for segment in path {
+        node = match segment {
+            Segment::Key(key) => {
+                let (key, item) = node.table_like()?.get_key_value(key)?;
+                span = key.span();
+                Node::Item(item)
+            }
+            Segment::Index(i) => node.element(*i)?,
+        };
+    }
+    span
+}
+
+fn is_empty(value: &toml::Value) -> bool {
+    match value {
+        toml::Value::Array(array) => array.is_empty(),
+        toml::Value::Table(table) => table.is_empty(),
+        _ => false,
+    }
+}
+
+/// Keys in `input`, at any depth, that `parsed` (the same value deserialized and serialized back)
+/// doesn't have, because deserializing ignored them
+fn ignored_keys(
+    input: &toml::Value,
+    parsed: &toml::Value,
+    path: &mut Vec<Segment>,
+    found: &mut Vec<Vec<Segment>>,
+) {
+    match (input, parsed) {
+        (toml::Value::Table(input), toml::Value::Table(parsed)) => {
+            for (key, value) in input {
+                path.push(Segment::Key(key.clone()));
+                match parsed.get(key) {
+                    Some(parsed) => ignored_keys(value, parsed, path, found),
+                    // Empty values may be left out when serializing
+                    None if is_empty(value) => {}
+                    None => found.push(path.clone()),
+                }
+                path.pop();
+            }
+        }
+        (toml::Value::Array(input), toml::Value::Array(parsed)) => {
+            for (i, (value, parsed)) in input.iter().zip(parsed).enumerate() {
+                path.push(Segment::Index(i));
+                ignored_keys(value, parsed, path, found);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Round trip a value through `T`, for comparing with what was written. None if it doesn't parse
+fn round_trip<T: DeserializeOwned + Serialize>(value: &toml::Value) -> Option<toml::Value> {
+    let parsed: T = value.clone().try_into().ok()?;
+    toml::Value::try_from(parsed).ok()
+}
+
+fn schema_problem(
+    path: &Path,
+    contents: &str,
+    message: String,
+    span: Option<Range<usize>>,
+) -> Problem {
+    let source = path.display().to_string();
+    let snippet = span.map(|span| {
+        let message = Level::Error.title(&message).snippet(
+            Snippet::source(contents)
+                .origin(&source)
+                .fold(true)
+                .annotation(Level::Error.span(span)),
+        );
+        Renderer::plain().render(message).to_string()
+    });
+    Problem {
+        severity: Severity::Error,
+        source,
+        line: None,
+        message,
+        snippet,
+    }
+}
+
+/// Schema errors in a checklist file: invalid TOML, wrong types, missing or unknown check types,
+/// and fields that would be ignored
+fn lint_checklist(path: &Path) -> Vec<Problem> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            return vec![schema_problem(
+                path,
+                "",
+                format!("unable to read: {e}"),
+                None,
+            )]
+        }
+    };
+
+    // Checklist::parse reports wrong types and missing or unknown check types, with spans
+    let parsed = crate::types::Checklist::parse(&contents)
+        .and_then(|()| toml::from_str::<toml::Value>(&contents));
+    let input = match parsed {
+        Ok(input) => input,
+        Err(e) => {
+            let message = e.message().to_string();
+            return vec![schema_problem(path, &contents, message, e.span())];
+        }
+    };
+    let Ok(doc) = ImDocument::parse(contents.as_str()) else {
+        return Vec::new();
+    };
+
+    let mut ignored = Vec::new();
+    let toml::Value::Table(sections) = &input else {
+        return Vec::new();
+    };
+    for (key, value) in sections {
+        let section = Segment::Key(key.clone());
+        let parsed = match key.as_str() {
+            "check" => round_trip::<Vec<Check>>(value),
+            "condition" => round_trip::<Vec<Condition>>(value),
+            "requires" => round_trip::<Vec<Requirement>>(value),
+            "templates" => round_trip::<TemplatesSection>(value),
+            key if CHECKLIST_KEYS.contains(&key) => continue,
+            _ => {
+                ignored.push(vec![section]);
+                continue;
+            }
+        };
+        if let Some(parsed) = parsed {
+            ignored_keys(value, &parsed, &mut vec![section], &mut ignored);
+        }
+    }
+
+    ignored
+        .into_iter()
+        .map(|key_path| {
+            let Some(Segment::Key(key)) = key_path.last() else {
+                unreachable!("ignored keys end with a key");
+            };
+            let message = format!("unknown field '{key}'");
+            schema_problem(
+                path,
+                &contents,
+                message,
+                key_span(doc.as_table(), &key_path),
+            )
+        })
+        .collect()
+}
+
+/// Schema errors in checklist files, found without running anything
+pub fn lint_checklists(paths: &[PathBuf]) -> Vec<Problem> {
+    paths.iter().flat_map(|path| lint_checklist(path)).collect()
+}
+
+/// Check a project's checklists and templates without running any checks
+pub fn validate(project: &Project) -> Vec<Problem> {
+    lint_templates(project)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_fields_are_reported_at_their_key() {
+        let path =
+            std::env::temp_dir().join(format!("checklints-lint-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[[check]]\ntype = \"file\"\npath = \"a\"\ncontians = [\"x\"]\n",
+        )
+        .unwrap();
+        let problems = lint_checklist(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].message, "unknown field 'contians'");
+        assert!(problems[0]
+            .snippet
+            .as_ref()
+            .unwrap()
+            .contains("4 | contians"));
+    }
+}
@@ -0,0 +1,122 @@
+//! Copies of remote checklists and templates kept in the project, so runs don't need the network
+//! and changes to them show up in review
+use crate::cache::{external_file, hash_file};
+use crate::settings::Settings;
+use crate::types::RemoteFile;
+use anyhow::{bail, Context, Result};
+use log::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Where vendored files are kept, relative to the project
+pub const VENDOR_DIR: &str = "vendor/checklints";
+
+const MANIFEST_FILE_NAME: &str = "manifest.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct VendoredFile {
+    url: String,
+    hash: String,
+    /// Relative to the vendor dir
+    path: PathBuf,
+}
+
+/// Which remote file each vendored file is a copy of
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default, rename = "file")]
+    files: Vec<VendoredFile>,
+
+    #[serde(skip)]
+    dir: PathBuf,
+}
+
+impl Manifest {
+    /// The project's manifest, if it has vendored anything
+    pub fn load(project_dir: &Path) -> Result<Option<Self>> {
+        let dir = project_dir.join(VENDOR_DIR);
+        let path = dir.join(MANIFEST_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let contents = fs::read_to_string(&path)?;
+        let mut manifest: Self =
+            toml::from_str(&contents).with_context(|| format!("Invalid {}", path.display()))?;
+        manifest.dir = dir;
+        Ok(Some(manifest))
+    }
+
+    /// Vendored copy of a remote file. None if it wasn't vendored, or was vendored with other
+    /// contents than the hash asks for
+    pub fn get(&self, remote: &RemoteFile) -> Result<Option<PathBuf>> {
+        let url = remote.url().to_string();
+        let Some(file) = self.files.iter().find(|file| file.url == url) else {
+            return Ok(None);
+        };
+        if let Some(hash) = remote.hash()
+            && *hash != file.hash
+        {
+            info!("Vendored copy of {url} is out of date, not using it");
+            return Ok(None);
+        }
+
+        let path = self.dir.join(&file.path);
+        let hash = hash_file(&path).with_context(|| format!("Vendored {url} is missing"))?;
+        if hash != file.hash {
+            bail!(
+                "Vendored {} was modified, it no longer matches its hash in {}. Vendor it again to \
+                 update it",
+                path.display(),
+                MANIFEST_FILE_NAME
+            );
+        }
+        debug!("Using vendored {}", path.display());
+        Ok(Some(path))
+    }
+}
+
+/// Download every remote checklist and template the settings use into the project's vendor dir,
+/// replacing what was vendored before. Returns the files written
+pub fn vendor(project_dir: &Path, settings: &Settings, cache_dir: &Path) -> Result<Vec<PathBuf>> {
+    let dir = project_dir.join(VENDOR_DIR);
+    if dir.is_dir() {
+        fs::remove_dir_all(&dir)?;
+    }
+
+    let remotes = [
+        ("checklists", settings.required_checklists()),
+        ("checklists", settings.external_checklists()),
+        ("templates", settings.external_templates()),
+    ];
+    let mut manifest = Manifest::default();
+    let mut written = Vec::new();
+    for (subdir, remotes) in remotes {
+        for remote in remotes {
+            let url = remote.url().to_string();
+            if manifest.files.iter().any(|file| file.url == url) {
+                continue;
+            }
+            let name = remote.url().name();
+            let downloaded = external_file(cache_dir, &name, &url, remote.hash())?;
+            let hash = hash_file(&downloaded)?;
+
+            // Files with the same name from different places are told apart by their hash
+            let mut path = Path::new(subdir).join(&name);
+            if manifest.files.iter().any(|file| file.path == path) {
+                path = Path::new(subdir).join(format!("{}-{name}", &hash[..8]));
+            }
+            let dest = dir.join(&path);
+            fs::create_dir_all(dest.parent().unwrap())?;
+            fs::copy(&downloaded, &dest)?;
+
+            manifest.files.push(VendoredFile { url, hash, path });
+            written.push(dest);
+        }
+    }
+
+    if !manifest.files.is_empty() {
+        fs::write(dir.join(MANIFEST_FILE_NAME), toml::to_string(&manifest)?)?;
+    }
+    Ok(written)
+}
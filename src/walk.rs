@@ -0,0 +1,171 @@
+use anyhow::Result;
+use directories::BaseDirs;
+use globset::GlobBuilder;
+use ignore::{Walk, WalkBuilder};
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+
+const GLOB_CHARS: [char; 4] = ['*', '?', '[', '{'];
+
+pub fn is_glob(pattern: &str) -> bool {
+    pattern.contains(GLOB_CHARS)
+}
+
+/// Replace a leading '~' with the user's home dir
+pub fn expand_tilde(path: &str) -> PathBuf {
+    let rest = match path.strip_prefix('~') {
+        Some("") => "",
+        Some(rest) if rest.starts_with('/') => &rest[1..],
+        _ => return PathBuf::from(path),
+    };
+    match BaseDirs::new() {
+        Some(base_dirs) => base_dirs.home_dir().join(rest),
+        None => PathBuf::from(path),
+    }
+}
+
+fn walker(root: &Path, include_ignored: bool) -> WalkBuilder {
+    let respect_ignores = !include_ignored;
+    let mut builder = WalkBuilder::new(root);
+    builder
+        .hidden(false)
+        .parents(respect_ignores)
+        .ignore(respect_ignores)
+        .git_ignore(respect_ignores)
+        .git_global(respect_ignores)
+        .git_exclude(respect_ignores)
+        // Respect .gitignore files even when the project isn't (yet) a git repo
+        .require_git(false)
+        .filter_entry(|entry| entry.file_name() != ".git");
+    builder
+}
+
+fn collect(walk: Walk) -> Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in walk {
+        let entry = entry?;
+        if entry.depth() == 0 {
+            continue;
+        }
+        paths.push(entry.into_path());
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Immediate children of a directory, skipping gitignored entries unless `include_ignored` is set
+pub fn dir_children(dir: &Path, include_ignored: bool) -> Result<Vec<PathBuf>> {
+    let walk = walker(dir, include_ignored).max_depth(Some(1)).build();
+    collect(walk)
+}
+
+/// Every file and directory below `dir`, skipping gitignored entries unless `include_ignored` is set
+pub fn walk_dir(dir: &Path, include_ignored: bool) -> Result<Vec<PathBuf>> {
+    let walk = walker(dir, include_ignored).build();
+    collect(walk)
+}
+
+/// Longest leading run of path components without glob characters
+fn glob_base(pattern: &str) -> PathBuf {
+    let mut base = PathBuf::new();
+    for component in Path::new(pattern).components() {
+        if let Component::Normal(part) = component
+            && is_glob(&part.to_string_lossy())
+        {
+            break;
+        }
+        base.push(component);
+    }
+
+    // The last component is the file name itself, not a directory to walk
+    if base == Path::new(pattern) {
+        base.pop();
+    }
+
+    if base.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        base
+    }
+}
+
+/// Expand a glob pattern into the matching files, skipping gitignored paths unless `include_ignored` is set
+pub fn glob(pattern: &str, include_ignored: bool) -> Result<Vec<PathBuf>> {
+    let pattern = expand_tilde(pattern).to_string_lossy().to_string();
+    let pattern = pattern.strip_prefix("./").unwrap_or(&pattern);
+    let matcher = GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()?
+        .compile_matcher();
+
+    let base = glob_base(pattern);
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let paths = walk_dir(&base, include_ignored)?
+        .into_iter()
+        .map(|path| match path.strip_prefix(".") {
+            Ok(relative) => relative.to_path_buf(),
+            Err(_) => path,
+        })
+        .filter(|path| path.is_file() && matcher.is_match(path))
+        .collect();
+    Ok(paths)
+}
+
+/// Expand a list of paths and/or globs into the (deduplicated, sorted) files they match
+pub fn expand(patterns: &[String], include_ignored: bool) -> Result<Vec<PathBuf>> {
+    let mut paths = BTreeSet::new();
+    for pattern in patterns {
+        if is_glob(pattern) {
+            paths.extend(glob(pattern, include_ignored)?);
+        } else {
+            let path = expand_tilde(pattern);
+            if path.is_dir() {
+                let files = walk_dir(&path, include_ignored)?;
+                paths.extend(files.into_iter().filter(|p| p.is_file()));
+            } else if path.is_file() {
+                paths.insert(path);
+            }
+        }
+    }
+    Ok(paths.into_iter().collect())
+}
+
+/// Does a path, directory, or glob cover any of `paths`
+pub fn covers_any(pattern: &str, paths: &[PathBuf]) -> Result<bool> {
+    let pattern = pattern.strip_prefix("./").unwrap_or(pattern);
+    if is_glob(pattern) {
+        let matcher = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()?
+            .compile_matcher();
+        return Ok(paths.iter().any(|path| matcher.is_match(path)));
+    }
+
+    // A directory covers everything below it
+    let pattern = Path::new(pattern);
+    Ok(paths.iter().any(|path| path.starts_with(pattern)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn glob_base_stops_at_first_glob_component() {
+        assert_eq!(glob_base("src/**/*.rs"), PathBuf::from("src"));
+        assert_eq!(glob_base("*.md"), PathBuf::from("."));
+        assert_eq!(glob_base("docs/{a,b}/index.md"), PathBuf::from("docs"));
+    }
+
+    #[test]
+    fn expand_tilde_only_expands_leading_home() {
+        let home = BaseDirs::new().unwrap().home_dir().to_path_buf();
+        assert_eq!(expand_tilde("~/.config/git"), home.join(".config/git"));
+        assert_eq!(expand_tilde("~"), home);
+        assert_eq!(expand_tilde("~user/x"), PathBuf::from("~user/x"));
+        assert_eq!(expand_tilde("src/~/x"), PathBuf::from("src/~/x"));
+    }
+}
@@ -0,0 +1,336 @@
+//! Write checks by answering questions instead of by hand
+use crate::types::Checklist;
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use toml_edit::{value, Array, ArrayOfTables, DocumentMut, Item, Table};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Text,
+    /// Comma separated
+    List,
+    Bool,
+    Integer,
+}
+
+struct Field {
+    name: &'static str,
+    about: &'static str,
+    kind: Kind,
+    required: bool,
+}
+
+const fn field(name: &'static str, about: &'static str, kind: Kind, required: bool) -> Field {
+    Field {
+        name,
+        about,
+        kind,
+        required,
+    }
+}
+
+/// A check type the wizard knows how to ask about
+struct CheckKind {
+    ttype: &'static str,
+    about: &'static str,
+    fields: &'static [Field],
+}
+
+const CHECK_KINDS: &[CheckKind] = &[
+    CheckKind {
+        ttype: "file",
+        about: "A file exists, and has some contents",
+        fields: &[
+            field("path", "Path to the file, or a glob", Kind::Text, true),
+            field("contains", "Text the file must contain", Kind::List, false),
+            field(
+                "template",
+                "Template to compare against, relative to the checklist",
+                Kind::Text,
+                false,
+            ),
+        ],
+    },
+    CheckKind {
+        ttype: "directory",
+        about: "A directory exists, and has some entries",
+        fields: &[
+            field("path", "Path to the directory", Kind::Text, true),
+            field("contains", "Entries it must contain", Kind::List, false),
+        ],
+    },
+    CheckKind {
+        ttype: "command",
+        about: "A command succeeds, and prints something",
+        fields: &[
+            field("cmd", "Command to run", Kind::Text, true),
+            field(
+                "code",
+                "Expected exit code (default 0)",
+                Kind::Integer,
+                false,
+            ),
+            field(
+                "stdout_contains",
+                "Text stdout must contain",
+                Kind::List,
+                false,
+            ),
+        ],
+    },
+    CheckKind {
+        ttype: "http",
+        about: "A URL responds",
+        fields: &[
+            field("url", "URL to request", Kind::Text, true),
+            field("method", "HTTP method (e.g. GET)", Kind::Text, true),
+            field(
+                "body_contains",
+                "Text the response must contain",
+                Kind::List,
+                false,
+            ),
+        ],
+    },
+    CheckKind {
+        ttype: "varset",
+        about: "A fact or env var is set",
+        fields: &[
+            field("key", "Name of the fact or env var", Kind::Text, true),
+            field("value", "Value it must have", Kind::Text, false),
+        ],
+    },
+    CheckKind {
+        ttype: "freshness",
+        about: "A file was modified recently",
+        fields: &[
+            field("path", "Path to the file", Kind::Text, true),
+            field(
+                "max_age",
+                "Longest since it was modified (e.g. 30days)",
+                Kind::Text,
+                false,
+            ),
+            field(
+                "changed_since_last_tag",
+                "Must have changed since the last git tag",
+                Kind::Bool,
+                false,
+            ),
+        ],
+    },
+    CheckKind {
+        ttype: "gitignore",
+        about: "Paths are gitignored",
+        fields: &[field(
+            "paths",
+            "Paths that must be ignored",
+            Kind::List,
+            true,
+        )],
+    },
+    CheckKind {
+        ttype: "manual",
+        about: "Something a person has to verify",
+        fields: &[
+            field("id", "Id to acknowledge it with", Kind::Text, false),
+            field("items", "Things to verify", Kind::List, false),
+        ],
+    },
+];
+
+/// Asks questions on `output` and reads the answers from `input`
+struct Prompt<'a, R, W> {
+    input: &'a mut R,
+    output: &'a mut W,
+}
+
+impl<R: BufRead, W: Write> Prompt<'_, R, W> {
+    fn ask(&mut self, question: &str) -> Result<String> {
+        write!(self.output, "{question}: ")?;
+        self.output.flush()?;
+        let mut answer = String::new();
+        if self.input.read_line(&mut answer)? == 0 {
+            bail!("No answer to '{question}', input ended");
+        }
+        Ok(answer.trim().to_string())
+    }
+
+    /// Ask until the answer is a number from 1 to `max`, or empty if there's a default
+    fn choose(&mut self, question: &str, max: usize, default: Option<usize>) -> Result<usize> {
+        loop {
+            let answer = self.ask(question)?;
+            if answer.is_empty()
+                && let Some(default) = default
+            {
+                return Ok(default);
+            }
+            match answer.parse::<usize>() {
+                Ok(n) if (1..=max).contains(&n) => return Ok(n),
+                _ => writeln!(self.output, "Enter a number from 1 to {max}")?,
+            }
+        }
+    }
+
+    /// The value of a field, None when an optional field is left empty
+    fn field(&mut self, field: &Field) -> Result<Option<Item>> {
+        let question = if field.required {
+            format!("{} ({})", field.name, field.about)
+        } else {
+            format!("{} ({}, optional)", field.name, field.about)
+        };
+        loop {
+            let answer = self.ask(&question)?;
+            if answer.is_empty() {
+                if field.required {
+                    writeln!(self.output, "'{}' is required", field.name)?;
+                    continue;
+                }
+                return Ok(None);
+            }
+
+            let item = match field.kind {
+                Kind::Text => value(answer),
+                Kind::List => {
+                    let items = answer
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .collect::<Array>();
+                    value(items)
+                }
+                Kind::Bool => value(matches!(answer.as_str(), "y" | "Y" | "yes" | "true")),
+                Kind::Integer => match answer.parse::<i64>() {
+                    Ok(n) => value(n),
+                    Err(_) => {
+                        writeln!(self.output, "'{answer}' is not a number")?;
+                        continue;
+                    }
+                },
+            };
+            return Ok(Some(item));
+        }
+    }
+}
+
+/// Ask which checklist to add to, what kind of check, and its fields. Returns the checklist and
+/// the check as a `[[check]]` entry, which is checked to parse
+fn ask_check<R: BufRead, W: Write>(
+    prompt: &mut Prompt<R, W>,
+    checklists: &[PathBuf],
+    new_checklist: &Path,
+) -> Result<(PathBuf, String)> {
+    writeln!(prompt.output, "Checklists:")?;
+    for (i, path) in checklists.iter().enumerate() {
+        writeln!(prompt.output, "  {}. {}", i + 1, path.display())?;
+    }
+    // Offered as a choice of its own only when it doesn't exist yet
+    let new = checklists.len() + 1;
+    let offer_new = !checklists.iter().any(|path| path == new_checklist);
+    let choices = if offer_new {
+        writeln!(
+            prompt.output,
+            "  {new}. New checklist {}",
+            new_checklist.display()
+        )?;
+        new
+    } else {
+        checklists.len()
+    };
+    let checklist = match prompt.choose("Add the check to", choices, Some(1))? {
+        n if n == new => new_checklist.to_path_buf(),
+        n => checklists[n - 1].clone(),
+    };
+
+    writeln!(prompt.output, "Check types:")?;
+    for (i, kind) in CHECK_KINDS.iter().enumerate() {
+        writeln!(prompt.output, "  {}. {}: {}", i + 1, kind.ttype, kind.about)?;
+    }
+    let kind = &CHECK_KINDS[prompt.choose("Type", CHECK_KINDS.len(), None)? - 1];
+
+    let mut table = Table::new();
+    table.insert("type", value(kind.ttype));
+    let description = field(
+        "description",
+        "Shown in reports instead of a generated one",
+        Kind::Text,
+        false,
+    );
+    if let Some(item) = prompt.field(&description)? {
+        table.insert("description", item);
+    }
+    for field in kind.fields {
+        if let Some(item) = prompt.field(field)? {
+            table.insert(field.name, item);
+        }
+    }
+
+    let mut checks = ArrayOfTables::new();
+    checks.push(table);
+    let mut doc = DocumentMut::new();
+    doc.insert("check", Item::ArrayOfTables(checks));
+    let entry = doc.to_string();
+    Checklist::parse(&entry).with_context(|| format!("The check is not valid:\n{entry}"))?;
+
+    Ok((checklist, entry))
+}
+
+/// Interactively write a check and append it to one of `checklists`, or to `new_checklist`.
+/// Returns the checklist written to
+pub fn new_check<R: BufRead, W: Write>(
+    input: &mut R,
+    output: &mut W,
+    checklists: &[PathBuf],
+    new_checklist: &Path,
+) -> Result<PathBuf> {
+    let mut prompt = Prompt { input, output };
+    let (checklist, entry) = ask_check(&mut prompt, checklists, new_checklist)?;
+
+    writeln!(prompt.output, "\n{entry}")?;
+    let answer = prompt.ask(&format!("Add to {}? [Y/n]", checklist.display()))?;
+    if matches!(answer.as_str(), "n" | "N" | "no") {
+        bail!("Not adding the check");
+    }
+
+    let mut contents = fs::read_to_string(&checklist).unwrap_or_default();
+    if !contents.is_empty() {
+        if !contents.ends_with('\n') {
+            contents.push('\n');
+        }
+        contents.push('\n');
+    }
+    contents.push_str(&entry);
+    if let Some(parent) = checklist.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&checklist, contents)?;
+    Ok(checklist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers_make_a_check() {
+        let answers = "2\n1\n\nREADME.md\n## License, ## Usage\n\n";
+        let mut output = Vec::new();
+        let mut prompt = Prompt {
+            input: &mut answers.as_bytes(),
+            output: &mut output,
+        };
+        let (checklist, entry) = ask_check(
+            &mut prompt,
+            &[PathBuf::from("a.toml")],
+            Path::new("new.toml"),
+        )
+        .unwrap();
+        assert_eq!(checklist, Path::new("new.toml"));
+        assert_eq!(
+            entry,
+            "[[check]]\ntype = \"file\"\npath = \"README.md\"\ncontains = [\"## License\", \"## Usage\"]\n"
+        );
+    }
+}